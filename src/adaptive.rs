@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Bounds for the learned multipliers so that no transition can be
+/// starved out or take over completely
+const MIN_MULTIPLIER: f64 = 1.0 / 16.0;
+const MAX_MULTIPLIER: f64 = 16.0;
+
+const DEFAULT_REWARD: f64 = 0.5;
+const DEFAULT_DECAY: f64 = 0.02;
+
+/// Identifies an edge of an automaton - the automaton's name, the id of the
+/// state the edge starts from and the position of the edge amongst the
+/// state's adjacent states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct EdgeId {
+    pub automaton: &'static str,
+    pub node: usize,
+    pub edge: usize,
+}
+
+/// Weight multipliers for automaton edges, learned from feedback.
+///
+/// Each edge starts with a multiplier of 1, i.e. its predefined weight. Each
+/// time the harness reports an output as interesting, the edges chosen while
+/// producing it are rewarded and their multipliers grow by `reward`. Otherwise
+/// they decay by `decay`. This is a simple multiplicative-weights bandit per
+/// edge - productive transitions become more likely over time while the
+/// unproductive ones are gradually pushed back, though never to zero.
+///
+/// The learned tables can be persisted between runs with [`AdaptiveWeights::save`]
+/// and [`AdaptiveWeights::load`].
+pub struct AdaptiveWeights {
+    tables: HashMap<String, HashMap<(usize, usize), f64>>,
+    reward: f64,
+    decay: f64,
+}
+
+impl Default for AdaptiveWeights {
+    fn default() -> Self {
+        Self::new(DEFAULT_REWARD, DEFAULT_DECAY)
+    }
+}
+
+impl AdaptiveWeights {
+    /// Creates an empty set of tables based on the following input:
+    /// - `reward` - relative growth of an edge's multiplier upon success
+    /// - `decay` - relative shrinkage of an edge's multiplier upon failure,
+    ///   expected to be in [0, 1)
+    pub fn new(reward: f64, decay: f64) -> Self {
+        Self {
+            tables: HashMap::new(),
+            reward,
+            decay,
+        }
+    }
+
+    /// Returns the learned multiplier for the `edge`-th edge of the `node`-th
+    /// state of the automaton named `automaton`
+    pub fn multiplier(&self, automaton: &str, node: usize, edge: usize) -> f64 {
+        self.tables
+            .get(automaton)
+            .and_then(|table| table.get(&(node, edge)))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Rewards or punishes every edge in `trace`, depending on whether the
+    /// output they lead to was `interesting`
+    pub(crate) fn update(&mut self, trace: &[EdgeId], interesting: bool) {
+        let factor = if interesting {
+            1.0 + self.reward
+        } else {
+            1.0 - self.decay
+        };
+        // edges visited multiple times through a cycle are updated only once
+        for edge in trace.iter().collect::<HashSet<_>>() {
+            let multiplier = self
+                .tables
+                .entry(String::from(edge.automaton))
+                .or_default()
+                .entry((edge.node, edge.edge))
+                .or_insert(1.0);
+            *multiplier = (*multiplier * factor).clamp(MIN_MULTIPLIER, MAX_MULTIPLIER);
+        }
+    }
+
+    /// Writes the learned tables to `path`, one edge per line:
+    /// `<automaton> <node> <edge> <multiplier>`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut output = BufWriter::new(File::create(path)?);
+        for (automaton, table) in &self.tables {
+            for ((node, edge), multiplier) in table {
+                writeln!(output, "{} {} {} {}", automaton, node, edge, multiplier)?;
+            }
+        }
+        output.flush()
+    }
+
+    /// Reads tables previously written by [`AdaptiveWeights::save`], using the
+    /// default reward and decay rates
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut weights = Self::default();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match fields[..] {
+                [automaton, node, edge, multiplier] => node
+                    .parse::<usize>()
+                    .ok()
+                    .zip(edge.parse::<usize>().ok())
+                    .zip(multiplier.parse::<f64>().ok())
+                    .map(|(key, multiplier)| (automaton, key, multiplier)),
+                [] => continue,
+                _ => None,
+            };
+            let (automaton, key, multiplier) = parsed.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid weight table entry: {}", line),
+                )
+            })?;
+            weights
+                .tables
+                .entry(String::from(automaton))
+                .or_default()
+                .insert(key, multiplier.clamp(MIN_MULTIPLIER, MAX_MULTIPLIER));
+        }
+        Ok(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdaptiveWeights, EdgeId, MAX_MULTIPLIER, MIN_MULTIPLIER};
+    use std::path::PathBuf;
+    use std::{env, fs, process};
+
+    const EDGE: EdgeId = EdgeId {
+        automaton: "test",
+        node: 1,
+        edge: 2,
+    };
+
+    #[test]
+    fn unknown_edges_keep_their_weight() {
+        assert_eq!(AdaptiveWeights::default().multiplier("test", 1, 2), 1.0);
+    }
+
+    #[test]
+    fn interesting_edges_are_rewarded() {
+        let mut weights = AdaptiveWeights::new(0.5, 0.1);
+        weights.update(&[EDGE], true);
+        assert_eq!(weights.multiplier("test", 1, 2), 1.5);
+    }
+
+    #[test]
+    fn unproductive_edges_decay() {
+        let mut weights = AdaptiveWeights::new(0.5, 0.5);
+        weights.update(&[EDGE], false);
+        assert_eq!(weights.multiplier("test", 1, 2), 0.5);
+    }
+
+    #[test]
+    fn repeated_edges_are_updated_once() {
+        let mut weights = AdaptiveWeights::new(0.5, 0.1);
+        weights.update(&[EDGE, EDGE, EDGE], true);
+        assert_eq!(weights.multiplier("test", 1, 2), 1.5);
+    }
+
+    #[test]
+    fn multipliers_are_bounded() {
+        let mut weights = AdaptiveWeights::new(1.0, 0.9);
+        for _ in 0..100 {
+            weights.update(&[EDGE], true);
+        }
+        assert_eq!(weights.multiplier("test", 1, 2), MAX_MULTIPLIER);
+        for _ in 0..100 {
+            weights.update(&[EDGE], false);
+        }
+        assert_eq!(weights.multiplier("test", 1, 2), MIN_MULTIPLIER);
+    }
+
+    /// Returns a path which is unique to this process, so that concurrent
+    /// test runs don't race on the same file
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("talasum_{}_{}.txt", name, process::id()))
+    }
+
+    #[test]
+    fn tables_survive_persistence() {
+        let path = temp_path("adaptive_weights");
+        let mut weights = AdaptiveWeights::new(0.5, 0.1);
+        weights.update(&[EDGE], true);
+        weights.save(&path).unwrap();
+
+        let loaded = AdaptiveWeights::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.multiplier("test", 1, 2), 1.5);
+        assert_eq!(loaded.multiplier("test", 2, 1), 1.0);
+    }
+
+    #[test]
+    fn loading_malformed_tables_fails() {
+        let path = temp_path("adaptive_weights_invalid");
+        fs::write(&path, "test 1 two 1.5\n").unwrap();
+        let loaded = AdaptiveWeights::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! Fuzzing of binary protocols described by a [`Layout`] of their fields,
+//! e.g. fixed-width integers, varints, length-prefixed blobs and TLV records.
+
+use crate::binary_mutator::BinaryMutator;
+use crate::configuration::Config;
+use crate::randomness::PRandomizer;
+pub use crate::tokenizer::binary::{Endianness, Field, IntEncoding, Layout};
+
+/// Returns a Mutator of binary messages with the given `layout`, which
+/// fuzzes them field by field. Length prefixes are kept in line with the
+/// fields they precede unless they're fuzzed themselves.
+///
+/// # Examples
+/// ```rust
+/// use talasum::binary::{self, Endianness, Field, IntEncoding, Layout};
+///
+/// let layout = Layout::new(vec![
+///     Field::Int { name: "type", encoding: IntEncoding::Fixed(2, Endianness::Big) },
+///     Field::Tlv {
+///         name: "options",
+///         tag: IntEncoding::Fixed(1, Endianness::Big),
+///         length: IntEncoding::Varint,
+///     },
+/// ]);
+/// match binary::mutate(&layout, &[0, 1, 7, 2, 0xab, 0xcd], 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New message: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input did not match the layout")
+/// }
+/// ```
+pub fn mutate<'a>(layout: &Layout, input: &'a [u8], seed: u64) -> Option<BinaryMutator<'a>> {
+    BinaryMutator::new(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        layout,
+        Box::new(Config::new()),
+    )
+}
@@ -0,0 +1,315 @@
+use crate::configuration::Configurable;
+use crate::mutator::{choose_tokens, tokens_count};
+use crate::randomness::Randomizer;
+use crate::selector::{select, Selector};
+use crate::tokenizer::binary::{BinaryToken, BinaryTokenizer};
+use std::collections::BTreeSet;
+
+/// A mutation-based fuzzer for binary protocols.
+///
+/// It is the binary counterpart of [`crate::mutator::Mutator`]: its input is
+/// split into fields by a [`BinaryTokenizer`] and mutated field by field.
+/// Fields holding the length of another field are re-encoded after it's
+/// mutated, unless they're mutated themselves, so that a message is only
+/// malformed where it's meant to be.
+///
+/// Like [`crate::mutator::Mutator`], it is reproducible for a given seed.
+pub struct BinaryMutator<'a> {
+    seeder: Box<dyn Randomizer>,
+    tokens: Vec<BinaryToken>,
+    roots: Vec<usize>,
+    input: &'a [u8],
+    config: Box<dyn Configurable>,
+    included: Vec<Selector>,
+    excluded: Vec<Selector>,
+    selected: Vec<usize>,
+}
+
+impl<'a> BinaryMutator<'a> {
+    /// Creates a BinaryMutator instance based on the following input:
+    /// - `seeder` - will be used for generating random mutations to the input
+    /// - `input` - valid input as per the protocol's specification
+    /// - `tokenizer` - splits the input into its fields
+    ///
+    /// Result will be [`std::option::Option::None`] if the input doesn't
+    /// match the format understood by `tokenizer`.
+    pub(crate) fn new<T: BinaryTokenizer + ?Sized>(
+        seeder: Box<dyn Randomizer>,
+        input: &'a [u8],
+        tokenizer: &T,
+        config: Box<dyn Configurable>,
+    ) -> Option<Self> {
+        tokenizer.tokenize(input).map(|tokens| {
+            let nested: BTreeSet<usize> = tokens
+                .iter()
+                .flat_map(|token| token.children.iter().copied())
+                .collect();
+            let mut mutator = Self {
+                seeder,
+                roots: (0..tokens.len())
+                    .filter(|idx| !nested.contains(idx))
+                    .collect(),
+                tokens,
+                input,
+                config,
+                included: vec![],
+                excluded: vec![],
+                selected: vec![],
+            };
+            mutator.select();
+            mutator
+        })
+    }
+
+    /// Restricts mutation to the fields matched by `selector`, along with
+    /// the fields within them. May be called multiple times to select more
+    /// fields.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::binary::{self, Endianness, Field, IntEncoding, Layout};
+    /// use talasum::selector::Selector;
+    ///
+    /// let layout = Layout::new(vec![
+    ///     Field::Int { name: "version", encoding: IntEncoding::Fixed(1, Endianness::Big) },
+    ///     Field::Bytes { name: "id", length: 2 },
+    /// ]);
+    /// let mutator = binary::mutate(&layout, &[1, 0xab, 0xcd], 1234)
+    ///     .unwrap()
+    ///     .with_included_paths(Selector::new("/id").unwrap());
+    /// for fuzzed in mutator.take(10) {
+    ///     assert_eq!(fuzzed[0], 1);
+    /// }
+    /// ```
+    pub fn with_included_paths(mut self, selector: Selector) -> Self {
+        self.included.push(selector);
+        self.select();
+        self
+    }
+
+    /// Locks the fields matched by `selector`, e.g. checksums, so that they
+    /// are kept intact. Neither they nor the fields they are nested in are
+    /// mutated. Locking takes precedence over
+    /// [`BinaryMutator::with_included_paths`].
+    pub fn with_excluded_paths(mut self, selector: Selector) -> Self {
+        self.excluded.push(selector);
+        self.select();
+        self
+    }
+
+    /// Computes the indices of the tokens which can be chosen for mutation
+    /// as per the included and excluded paths
+    fn select(&mut self) {
+        let parts: Vec<(usize, usize, &str)> = self
+            .tokens
+            .iter()
+            .map(|token| (token.from, token.to, token.path.as_str()))
+            .collect();
+        let tokens = &self.tokens;
+        self.selected = select(&parts, &self.included, &self.excluded)
+            .into_iter()
+            .filter(|&idx| tokens[idx].automaton.is_some())
+            .collect();
+    }
+
+    /// Renders the bytes from `from` to `to`, which hold the tokens at
+    /// `children`, fuzzing the `chosen` ones using the `seed` value
+    fn render(
+        &self,
+        from: usize,
+        to: usize,
+        children: &[usize],
+        chosen: &BTreeSet<usize>,
+        seed: u64,
+    ) -> Vec<u8> {
+        let mut rendered: Vec<Vec<u8>> = children
+            .iter()
+            .map(|&idx| self.render_token(idx, chosen, seed))
+            .collect();
+        for (pos, &idx) in children.iter().enumerate() {
+            if let Some((content, encoding)) = self.tokens[idx].length_of {
+                let original = self.tokens[content].to - self.tokens[content].from;
+                match children.iter().position(|&child| child == content) {
+                    Some(content_pos)
+                        if !chosen.contains(&idx) && rendered[content_pos].len() != original =>
+                    {
+                        rendered[pos] = encoding.encode(rendered[content_pos].len() as u64);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut result = vec![];
+        let mut pos = from;
+        for (&idx, bytes) in children.iter().zip(rendered) {
+            result.extend_from_slice(&self.input[pos..self.tokens[idx].from]);
+            result.extend(bytes);
+            pos = self.tokens[idx].to;
+        }
+        result.extend_from_slice(&self.input[pos..to]);
+        result
+    }
+
+    /// Renders the token at index `idx`, fuzzing it after the tokens within
+    /// it if it's chosen
+    fn render_token(&self, idx: usize, chosen: &BTreeSet<usize>, seed: u64) -> Vec<u8> {
+        let token = &self.tokens[idx];
+        let rendered = self.render(token.from, token.to, &token.children, chosen, seed);
+        match token.automaton {
+            Some(automaton) if chosen.contains(&idx) => automaton.traverse(rendered, seed),
+            _ => rendered,
+        }
+    }
+
+    /// Fuzzes the whole input
+    fn fuzz(&mut self) -> Vec<u8> {
+        let next_seed = self.seeder.get();
+        let chosen: BTreeSet<usize> = choose_tokens(
+            next_seed,
+            self.selected.len(),
+            tokens_count(self.config.as_ref(), self.selected.len()),
+        )
+        .into_iter()
+        .map(|idx| self.selected[idx])
+        .collect();
+        self.render(0, self.input.len(), &self.roots, &chosen, next_seed)
+    }
+}
+
+impl<'a> Iterator for BinaryMutator<'a> {
+    type Item = Vec<u8>;
+
+    /// Computes a new fuzz value.
+    ///
+    /// Returns `None` if the input doesn't contain any fields, or none of
+    /// them is selected.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.selected.is_empty() {
+            None
+        } else {
+            Some(self.fuzz())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryMutator;
+    use crate::configuration::{Config, MockConfigurable};
+    use crate::randomness::PRandomizer;
+    use crate::selector::Selector;
+    use crate::tokenizer::binary::{Endianness, Field, IntEncoding, Layout};
+
+    const INPUT: &[u8] = &[1, 0, 5, b'h', b'e', b'l', b'l', b'o', 3, 1, b'x'];
+
+    fn layout() -> Layout {
+        Layout::new(vec![
+            Field::Int {
+                name: "version",
+                encoding: IntEncoding::Fixed(2, Endianness::Big),
+            },
+            Field::LengthPrefixed {
+                name: "payload",
+                length: IntEncoding::Varint,
+                content: Box::new(Field::Rest { name: "data" }),
+            },
+            Field::Tlv {
+                name: "options",
+                tag: IntEncoding::Fixed(1, Endianness::Big),
+                length: IntEncoding::Fixed(1, Endianness::Big),
+            },
+        ])
+    }
+
+    fn get_mutator_helper(seed: u64, input: &[u8]) -> Option<BinaryMutator<'_>> {
+        BinaryMutator::new(
+            Box::new(PRandomizer::new(seed)),
+            input,
+            &layout(),
+            Box::new(Config::new()),
+        )
+    }
+
+    /// Decodes the payload and the options, provided their lengths are
+    /// right
+    fn decode(bytes: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (length, size) = IntEncoding::Varint.decode(bytes.get(2..)?)?;
+        let payload_end = 2 + size + length as usize;
+        let payload = bytes.get(2 + size..payload_end)?.to_vec();
+        let options = bytes.get(payload_end..)?;
+        let value_length = *options.get(1)? as usize;
+        if options.len() == 2 + value_length {
+            Some((payload, options[2..].to_vec()))
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn mismatched_input_cannot_be_mutated() {
+        assert!(get_mutator_helper(1, &[1, 0, 9, b'h']).is_none());
+    }
+
+    #[test]
+    fn mutation_is_reproducible() {
+        let first: Vec<Vec<u8>> = get_mutator_helper(1, INPUT).unwrap().take(10).collect();
+        let sec: Vec<Vec<u8>> = get_mutator_helper(1, INPUT).unwrap().take(10).collect();
+        assert_eq!(first, sec);
+    }
+
+    #[test]
+    fn input_is_unchanged_if_no_token_is_chosen() {
+        let mut config = MockConfigurable::new();
+        config
+            .expect_get_horizontal_randomness_coef()
+            .return_const(0_u32);
+        let mut mutator = BinaryMutator::new(
+            Box::new(PRandomizer::new(1)),
+            INPUT,
+            &layout(),
+            Box::new(config),
+        )
+        .unwrap();
+        assert_eq!(mutator.next().unwrap(), INPUT.to_vec());
+    }
+
+    #[test]
+    fn lengths_are_reencoded() {
+        let mutator = get_mutator_helper(1, INPUT)
+            .unwrap()
+            .with_included_paths(Selector::new("/payload/data").unwrap());
+        let fuzzed: Vec<Vec<u8>> = mutator.take(50).collect();
+        assert!(fuzzed.iter().any(|bytes| bytes.len() != INPUT.len()));
+        assert!(fuzzed
+            .iter()
+            .all(|bytes| decode(bytes).map(|(_, options)| options) == Some(vec![b'x'])));
+    }
+
+    #[test]
+    fn tlv_records_are_mutated() {
+        let mutator = get_mutator_helper(1, INPUT)
+            .unwrap()
+            .with_included_paths(Selector::new("/options").unwrap());
+        assert!(mutator
+            .take(50)
+            .any(|bytes| bytes[..8] == INPUT[..8] && bytes[8..] != INPUT[8..]));
+    }
+
+    #[test]
+    fn excluded_fields_are_kept() {
+        let mutator = get_mutator_helper(1, INPUT)
+            .unwrap()
+            .with_excluded_paths(Selector::new("/version").unwrap());
+        for fuzzed in mutator.take(50) {
+            assert_eq!(fuzzed[..2], INPUT[..2]);
+        }
+    }
+
+    #[test]
+    fn fields_are_fuzzed() {
+        let fuzzed: Vec<Vec<u8>> = get_mutator_helper(2, INPUT).unwrap().take(100).collect();
+        assert!(fuzzed.iter().any(|bytes| decode(bytes).is_none()));
+        assert!(fuzzed.iter().any(|bytes| bytes[..2] != INPUT[..2]));
+    }
+}
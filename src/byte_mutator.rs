@@ -0,0 +1,207 @@
+use crate::configuration::Configurable;
+use crate::mutator::{choose_tokens, tokens_count, Mutator};
+use crate::randomness::Randomizer;
+use crate::state_machine::bytes::BYTES_AUTOMATON;
+use crate::tokenizer::tokenize_input;
+use crate::tokenizer::{AutomatonToken, LexerRule};
+use pest::Parser;
+use std::collections::BTreeMap;
+
+/// A mutation-based fuzzer working on raw bytes.
+///
+/// It is the byte-level counterpart of [`Mutator`]: if its input is valid
+/// UTF-8 and valid as per the protocol grammar, it is tokenized and mutated
+/// token by token, with tokens which have a byte-level automaton being fuzzed
+/// by it. On top of that, one of the mutated tokens gets corrupted on each
+/// mutation, e.g. by invalid UTF-8 sequences or NUL bytes. Any other input is
+/// corrupted as a whole.
+///
+/// Like [`Mutator`], it is reproducible for a given seed.
+pub struct ByteMutator<'a> {
+    seeder: Box<dyn Randomizer>,
+    tokens: Vec<AutomatonToken<'a>>,
+    input: &'a [u8],
+    config: Box<dyn Configurable>,
+}
+
+impl<'a> ByteMutator<'a> {
+    /// Creates a ByteMutator instance based on the following input:
+    /// - `seeder` - will be used for generating random mutations to the input
+    /// - `input` - raw input, preferably valid as per the protocol's specification
+    /// - `rule` - name of the top rule of the corresponding PEG, usually R::value,
+    ///   where R and P are protocol-specific types defined in [`crate::tokenizer`]
+    ///
+    /// Unlike [`Mutator::new`], it never fails: input which cannot be
+    /// tokenized is fuzzed as an opaque sequence of bytes.
+    pub(crate) fn new<P: Parser<R>, R: 'a + LexerRule>(
+        seeder: Box<dyn Randomizer>,
+        input: &'a [u8],
+        rule: R,
+        config: Box<dyn Configurable>,
+    ) -> Self {
+        let tokens = std::str::from_utf8(input)
+            .ok()
+            .and_then(|text| tokenize_input::<'a, P, R>(text, rule))
+            .unwrap_or_default();
+        Self {
+            seeder,
+            tokens,
+            input,
+            config,
+        }
+    }
+
+    /// Fuzzes the token at index `idx` using the `seed` value and
+    /// updates the offset table and result value after. If `corrupt` is set,
+    /// the fuzzed token is additionally corrupted at the byte level.
+    fn fuzz_token(
+        &self,
+        seed: u64,
+        idx: usize,
+        corrupt: bool,
+        offsets: &mut BTreeMap<usize, i64>,
+        result: &mut Vec<u8>,
+    ) {
+        let AutomatonToken {
+            from,
+            to,
+            automaton,
+            bytes,
+            ..
+        } = self.tokens[idx];
+
+        let new_from = Mutator::get_moved_index(offsets, from);
+        let new_to = Mutator::get_moved_index(offsets, to);
+
+        if let Some(to_fuzz) = result.get(new_from..new_to) {
+            let mut fuzzed = match (bytes, std::str::from_utf8(to_fuzz)) {
+                (Some(bytes), _) => bytes.traverse(to_fuzz.to_vec(), seed),
+                (None, Ok(text)) => automaton.traverse(String::from(text), seed).into_bytes(),
+                // a token within it has been fuzzed into invalid UTF-8, which
+                // a text automaton would replace, undoing the mutation
+                (None, Err(_)) => to_fuzz.to_vec(),
+            };
+            if corrupt {
+                fuzzed = BYTES_AUTOMATON.traverse(fuzzed, seed);
+            }
+            Mutator::move_index(
+                offsets,
+                to,
+                fuzzed.len() as i64 - (new_to - new_from) as i64,
+            );
+            result.splice(new_from..new_to, fuzzed);
+        } else {
+            panic!("Unreachable!");
+        }
+    }
+
+    /// Fuzzes the whole input
+    fn fuzz(&mut self) -> Vec<u8> {
+        let next_seed = self.seeder.get();
+        let mut result = self.input.to_vec();
+        if self.tokens.is_empty() {
+            return BYTES_AUTOMATON.traverse(result, next_seed);
+        }
+
+        let mut offsets = BTreeMap::<usize, i64>::new();
+        let chosen = choose_tokens(
+            next_seed,
+            self.tokens.len(),
+            tokens_count(self.config.as_ref(), self.tokens.len()),
+        );
+        // tokens come after the ones they contain, so corrupting the last
+        // chosen one ensures the corruption isn't fuzzed over
+        let corrupted = chosen.iter().next_back().copied();
+        for idx in chosen {
+            self.fuzz_token(
+                next_seed,
+                idx,
+                corrupted == Some(idx),
+                &mut offsets,
+                &mut result,
+            );
+        }
+        result
+    }
+}
+
+impl<'a> Iterator for ByteMutator<'a> {
+    type Item = Vec<u8>;
+
+    /// Computes a new fuzz value.
+    ///
+    /// Returns `None` if the input is empty.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            None
+        } else {
+            Some(self.fuzz())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ByteMutator;
+    use crate::configuration::Config;
+    use crate::randomness::PRandomizer;
+    use crate::tokenizer::json_lexer::{JsonLexer, Rule};
+    use std::collections::BTreeMap;
+
+    fn get_mutator_helper(seed: u64, input: &[u8]) -> ByteMutator<'_> {
+        ByteMutator::new::<JsonLexer, Rule>(
+            Box::new(PRandomizer::new(seed)),
+            input,
+            Rule::value,
+            Box::new(Config::new()),
+        )
+    }
+
+    #[test]
+    fn empty_input_cannot_be_mutated() {
+        assert_eq!(get_mutator_helper(1, b"").next(), None);
+    }
+
+    #[test]
+    fn mutation_is_reproducible() {
+        let first: Vec<Vec<u8>> = get_mutator_helper(1, b"[\"a\", 1]").take(10).collect();
+        let sec: Vec<Vec<u8>> = get_mutator_helper(1, b"[\"a\", 1]").take(10).collect();
+        assert_eq!(first, sec);
+    }
+
+    #[test]
+    fn valid_input_is_tokenized() {
+        assert_eq!(get_mutator_helper(1, b"[\"a\", 1]").tokens.len(), 3);
+    }
+
+    #[test]
+    fn output_contains_invalid_utf8() {
+        assert!(get_mutator_helper(1, b"{\"a\": \"b\"}")
+            .take(20)
+            .any(|bytes| std::str::from_utf8(&bytes).is_err()));
+    }
+
+    #[test]
+    fn invalid_utf8_within_tokens_is_preserved() {
+        let mutator = get_mutator_helper(1, b"[\"a\"]");
+        let array = mutator
+            .tokens
+            .iter()
+            .position(|token| token.from == 0 && token.to == 5)
+            .unwrap();
+        for seed in 0..100 {
+            // as if the string within the array was fuzzed into invalid UTF-8
+            let mut result = b"[\"\xff\"]".to_vec();
+            mutator.fuzz_token(seed, array, false, &mut BTreeMap::new(), &mut result);
+            assert!(result.contains(&0xff));
+        }
+    }
+
+    #[test]
+    fn invalid_input_is_fuzzed_as_bytes() {
+        let input = b"[\"a\xff\"]";
+        let mut mutator = get_mutator_helper(1, input);
+        assert!(mutator.tokens.is_empty());
+        assert_ne!(mutator.next().unwrap(), input.to_vec());
+    }
+}
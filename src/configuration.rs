@@ -11,6 +11,7 @@ use std::{
 struct ConfigFields {
     vertical_randomness_coef: u32,
     horizontal_randomness_coef: u32,
+    max_nesting_depth: u32,
 }
 
 /// Defines rules for interaction with the
@@ -56,14 +57,26 @@ pub trait Configurable {
     /// value and 100 being the max possible value.
     fn get_horizontal_randomness_coef(&self) -> u32;
 
+    /// Returns the max number of levels generated values can be nested in, e.g.
+    /// the number of brackets around a deeply nested JSON array.
+    ///
+    /// Deeply nested values target parsers which rely on recursion and may
+    /// exhaust their stack. The actual depth varies from value to value but
+    /// never exceeds this limit.
+    ///
+    /// Supported values are positive integers.
+    fn get_max_nesting_depth(&self) -> u32;
+
     /// Returns a list of the 1000 most commonly used words in the English language
     /// to be used by generators.
     fn get_common_words(&self) -> &Vec<String>;
 
     /// Saved for future use for updating configuration at runtime.
+    #[allow(dead_code)]
     fn set_horizontal_randomness_coef(&mut self, value: u32);
 
-    //. Saved for future use for updating configuration at runtime.
+    /// Saved for future use for updating configuration at runtime.
+    #[allow(dead_code)]
     fn set_vertical_randomness_coef(&mut self, value: u32);
 }
 
@@ -136,6 +149,19 @@ impl Configurable for Config {
         }
     }
 
+    fn get_max_nesting_depth(&self) -> u32 {
+        let value = self
+            .inner
+            .read()
+            .expect("Config is unattainable!")
+            .max_nesting_depth;
+        if value > 0 {
+            value
+        } else {
+            panic!("Max nesting depth must be positive");
+        }
+    }
+
     fn get_common_words(&self) -> &Vec<String> {
         &self.common_words
     }
@@ -170,6 +196,7 @@ mod tests {
         let config = Config::new();
         assert_eq!(config.get_horizontal_randomness_coef(), 50);
         assert_eq!(config.get_vertical_randomness_coef(), 50);
+        assert_eq!(config.get_max_nesting_depth(), 10000);
     }
 
     #[test]
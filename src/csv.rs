@@ -0,0 +1,97 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::csv::field::FIELD_AUTOMATON;
+use crate::state_machine::csv::formula::FORMULA_AUTOMATON;
+use crate::state_machine::csv::table::TABLE_AUTOMATON;
+use crate::tokenizer::csv_lexer::{CsvLexer, Rule};
+
+/// Returns a CSV field generator, which produces embedded and unterminated
+/// quotes, injected delimiters and line breaks, huge fields and formulas
+///
+/// # Examples
+/// ```rust
+/// use talasum::csv;
+///
+/// for fuzzed in csv::field(1234).take(10) {
+///     println!("New field of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn field(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&FIELD_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a spreadsheet formula generator, which produces fields starting
+/// with "=", "+", "-", "@" or a tab, for testing CSV injection defenses
+///
+/// # Examples
+/// ```rust
+/// use talasum::csv;
+///
+/// for fuzzed in csv::formula(1234).take(10) {
+///     println!("New formula: {:?}", fuzzed);
+/// }
+/// ```
+pub fn formula(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&FORMULA_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a CSV table generator, which produces BOMs, alternate delimiters
+/// and line breaks, ragged and very wide records
+///
+/// # Examples
+/// ```rust
+/// use talasum::csv;
+///
+/// for fuzzed in csv::table(1234).take(10) {
+///     println!("New table of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn table(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&TABLE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a CSV Mutator. Tables as per RFC 4180 are accepted, as well as
+/// ones delimited by ";", tabs or "|" and ones with LF or CR line breaks.
+/// On top of its fields being mutated, every other mutation also changes
+/// the table as a whole, e.g. its delimiter or the number of fields of a
+/// record.
+///
+/// # Examples
+/// ```rust
+/// use talasum::csv;
+///
+/// match csv::mutate("id,name\r\n1,\"a, b\"\r\n", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New table of length: {}", fuzzed.len());
+///         }
+///     },
+///     None => panic!("Your input could not be tokenized")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<CsvLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::csv,
+        Box::new(Config::new()),
+    )
+    .map(|mutator| mutator.with_records(&TABLE_AUTOMATON))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+
+    #[test]
+    fn tables_are_mutated() {
+        let table = "id,name\r\n1,alice\r\n";
+        let mutated: Vec<String> = mutate(table, 1234).unwrap().take(100).collect();
+        // the table as a whole is changed, as well as its fields
+        assert!(mutated.iter().any(|value| value.starts_with('\u{feff}')));
+        assert!(mutated.iter().any(|value| value.contains("=cmd|")));
+    }
+}
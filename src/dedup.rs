@@ -0,0 +1,221 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Describes how already emitted fuzz values are remembered.
+///
+/// - `Exact` keeps a copy of every emitted value - precise, but its memory
+///   footprint grows with the number and size of the values, so it is best
+///   suited for short runs
+/// - `Bloom` keeps a fixed-size bloom filter of `bits` bits using `hashes` hash
+///   functions per value - memory is bounded regardless of the run length at
+///   the cost of occasionally treating a new value as a duplicate
+pub enum DedupFilter {
+    Exact,
+    Bloom { bits: usize, hashes: u32 },
+}
+
+/// Internal state of the chosen [`DedupFilter`]
+enum Seen<T> {
+    Exact(HashSet<T>),
+    Bloom { bits: Vec<u64>, hashes: u32 },
+}
+
+impl<T: Eq + Hash + Clone> Seen<T> {
+    fn new(filter: DedupFilter) -> Self {
+        match filter {
+            DedupFilter::Exact => Seen::Exact(HashSet::new()),
+            DedupFilter::Bloom { bits, hashes } => Seen::Bloom {
+                bits: vec![0; bits.max(1).div_ceil(64)],
+                hashes: hashes.max(1),
+            },
+        }
+    }
+
+    /// Remembers the value. Returns false if it was (or, in case of a bloom
+    /// filter, might have been) seen before.
+    fn insert(&mut self, value: &T) -> bool {
+        match self {
+            Seen::Exact(set) => !set.contains(value) && set.insert(value.clone()),
+            Seen::Bloom { bits, hashes } => {
+                let hash = hash_of(value);
+                let size = (bits.len() * 64) as u64;
+                // double hashing - the i-th hash function is h1 + i * h2
+                let (h1, h2) = (hash, hash.rotate_left(32) | 1);
+                let mut is_new = false;
+                for i in 0..*hashes as u64 {
+                    let bit = h1.wrapping_add(i.wrapping_mul(h2)) % size;
+                    let (word, mask) = ((bit / 64) as usize, 1_u64 << (bit % 64));
+                    if bits[word] & mask == 0 {
+                        bits[word] |= mask;
+                        is_new = true;
+                    }
+                }
+                is_new
+            }
+        }
+    }
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// An iterator adapter which skips values that have already been
+/// produced by the underlying fuzzer.
+///
+/// Upon each call, the underlying fuzzer is queried at most `retries + 1`
+/// times for a value that hasn't been seen yet. If no such value is found,
+/// the fuzzer is considered exhausted and `None` is returned.
+pub struct Dedup<I: Iterator> {
+    inner: I,
+    seen: Seen<I::Item>,
+    retries: usize,
+    suppressed: u64,
+}
+
+impl<I: Iterator> Dedup<I>
+where
+    I::Item: Eq + Hash + Clone,
+{
+    /// Creates a Dedup instance based on the following input:
+    /// - `inner` - the fuzzer whose output is to be deduplicated
+    /// - `filter` - the way emitted values are remembered
+    /// - `retries` - how many consecutive duplicates can be skipped before
+    ///   giving up on the current iteration
+    pub fn new(inner: I, filter: DedupFilter, retries: usize) -> Self {
+        Self {
+            inner,
+            seen: Seen::new(filter),
+            retries,
+            suppressed: 0,
+        }
+    }
+
+    /// Returns the number of duplicates which were skipped so far
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed
+    }
+}
+
+impl<I: Iterator> Iterator for Dedup<I>
+where
+    I::Item: Eq + Hash + Clone,
+{
+    type Item = I::Item;
+
+    /// Computes a new fuzz value which hasn't been emitted before.
+    ///
+    /// Returns `None` if the underlying fuzzer is exhausted or if the
+    /// retry budget runs out.
+    fn next(&mut self) -> Option<Self::Item> {
+        for _ in 0..=self.retries {
+            let value = self.inner.next()?;
+            if self.seen.insert(&value) {
+                return Some(value);
+            }
+            self.suppressed += 1;
+        }
+        None
+    }
+}
+
+/// Opt-in deduplication for all fuzzers, see [`Dedup`]
+pub trait Deduplicate: Iterator + Sized
+where
+    Self::Item: Eq + Hash + Clone,
+{
+    /// Wraps the fuzzer so that it skips values that were already produced.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::dedup::{DedupFilter, Deduplicate};
+    /// use talasum::json;
+    ///
+    /// for fuzzed in json::boolean(1234).deduplicate(DedupFilter::Exact, 100) {
+    ///     println!("New unique boolean value: {}", fuzzed);
+    /// }
+    /// ```
+    fn deduplicate(self, filter: DedupFilter, retries: usize) -> Dedup<Self> {
+        Dedup::new(self, filter, retries)
+    }
+}
+
+impl<I: Iterator> Deduplicate for I where I::Item: Eq + Hash + Clone {}
+
+#[cfg(test)]
+mod tests {
+    use super::{DedupFilter, Deduplicate, Seen};
+    use crate::generator::Generator;
+    use crate::randomness::PRandomizer;
+    use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+    use crate::state_machine::json::number::NUMBER_AUTOMATON;
+    use itertools::Itertools;
+
+    #[test]
+    fn exact_filter_detects_duplicates() {
+        let mut seen = Seen::new(DedupFilter::Exact);
+        assert!(seen.insert(&1));
+        assert!(seen.insert(&2));
+        assert!(!seen.insert(&1));
+    }
+
+    #[test]
+    fn bloom_filter_detects_duplicates() {
+        let mut seen = Seen::new(DedupFilter::Bloom {
+            bits: 1024,
+            hashes: 3,
+        });
+        assert!(seen.insert(&1));
+        assert!(seen.insert(&2));
+        assert!(!seen.insert(&1));
+        assert!(!seen.insert(&2));
+    }
+
+    #[test]
+    fn deduplicated_output_is_unique() {
+        let generator = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)));
+        let values: Vec<String> = generator
+            .deduplicate(DedupFilter::Exact, 100)
+            .take(100)
+            .collect();
+        assert_eq!(values.len(), values.iter().unique().count());
+    }
+
+    #[test]
+    fn duplicates_are_counted() {
+        let mut dedup = Generator::new(&BOOL_AUTOMATON, Box::new(PRandomizer::new(1)))
+            .deduplicate(DedupFilter::Exact, 1000);
+        assert_eq!(dedup.by_ref().take(50).count(), 50);
+        assert!(dedup.suppressed() > 0);
+    }
+
+    #[test]
+    fn exhausted_retry_budget_ends_iteration() {
+        let mut dedup = vec![1, 1, 1, 2]
+            .into_iter()
+            .deduplicate(DedupFilter::Exact, 1);
+        assert_eq!(dedup.next(), Some(1));
+        assert_eq!(dedup.next(), None);
+        assert_eq!(dedup.suppressed(), 2);
+    }
+
+    #[test]
+    fn bloom_filter_memory_is_bounded() {
+        let mut dedup = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)))
+            .deduplicate(
+                DedupFilter::Bloom {
+                    bits: 1 << 16,
+                    hashes: 4,
+                },
+                100,
+            );
+        assert_eq!(dedup.by_ref().take(500).count(), 500);
+        match &dedup.seen {
+            Seen::Bloom { bits, .. } => assert_eq!(bits.len(), (1 << 16) / 64),
+            Seen::Exact(_) => panic!("Expected a Bloom filter"),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::randomness::Randomizer;
+use crate::state_machine::paths::Paths;
+use crate::state_machine::Automaton;
+use crate::tokenizer::AutomatonToken;
+
+pub use crate::state_machine::paths::PathBounds;
+
+/// An exhaustive generation-based fuzzer.
+///
+/// Unlike [`crate::generator::Generator`], which samples the paths through the
+/// automaton based on their weights, it systematically walks every distinct
+/// path through the automaton, shortest first, and yields one value per path.
+/// Paths are limited by [`PathBounds`], so the iterator is always finite.
+///
+/// This guarantees that low-weight edge cases are covered even in short runs.
+pub struct Enumerator<T: 'static + Eq + Clone + Sync> {
+    automaton: &'static Automaton<T>,
+    seeder: Box<dyn Randomizer>,
+    paths: Paths<'static, T>,
+}
+
+impl<T: Eq + Clone + Sync> Enumerator<T> {
+    /// Creates an Enumerator instance based on the following input:
+    /// - `automaton` - automaton static object, representing the protocol<->type pair
+    /// - `seeder` - will be used for seeding the transformations along each path
+    /// - `bounds` - limits on the enumerated paths
+    pub fn new(
+        automaton: &'static Automaton<T>,
+        seeder: Box<dyn Randomizer>,
+        bounds: PathBounds,
+    ) -> Self {
+        Self {
+            automaton,
+            seeder,
+            paths: automaton.paths(bounds),
+        }
+    }
+}
+
+impl<T: Eq + Clone + Sync> Iterator for Enumerator<T> {
+    type Item = T;
+
+    /// Computes the fuzz value for the next path.
+    ///
+    /// Returns `None` once all paths within the bounds are exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.paths.next()?;
+        Some(self.automaton.generate_path(self.seeder.get(), &path))
+    }
+}
+
+/// An exhaustive mutation-based fuzzer.
+///
+/// For each token of the input, it enumerates every distinct path through the
+/// token's automaton (see [`Enumerator`]) and yields the input with only
+/// that token fuzzed along that path.
+pub struct MutationEnumerator<'a> {
+    seeder: Box<dyn Randomizer>,
+    tokens: Vec<AutomatonToken<'a>>,
+    input: &'a str,
+    bounds: PathBounds,
+    current: usize,
+    paths: Option<Paths<'a, String>>,
+}
+
+impl<'a> MutationEnumerator<'a> {
+    pub(crate) fn new(
+        seeder: Box<dyn Randomizer>,
+        tokens: Vec<AutomatonToken<'a>>,
+        input: &'a str,
+        bounds: PathBounds,
+    ) -> Self {
+        Self {
+            seeder,
+            tokens,
+            input,
+            bounds,
+            current: 0,
+            paths: None,
+        }
+    }
+}
+
+impl<'a> Iterator for MutationEnumerator<'a> {
+    type Item = String;
+
+    /// Computes the fuzz value for the next token<->path pair.
+    ///
+    /// Returns `None` once all paths for all tokens are exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&AutomatonToken {
+            from,
+            to,
+            automaton,
+            ..
+        }) = self.tokens.get(self.current)
+        {
+            let bounds = self.bounds;
+            match self
+                .paths
+                .get_or_insert_with(|| automaton.paths(bounds))
+                .next()
+            {
+                Some(path) => {
+                    let fuzzed = automaton.traverse_path(
+                        String::from(&self.input[from..to]),
+                        self.seeder.get(),
+                        &path,
+                    );
+                    let mut result = String::from(self.input);
+                    result.replace_range(from..to, &fuzzed);
+                    return Some(result);
+                }
+                None => {
+                    self.current += 1;
+                    self.paths = None;
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Enumerator, PathBounds};
+    use crate::configuration::Config;
+    use crate::mutator::Mutator;
+    use crate::randomness::PRandomizer;
+    use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+    use crate::state_machine::json::number::NUMBER_AUTOMATON;
+    use crate::tokenizer::json_lexer::{JsonLexer, Rule};
+
+    #[test]
+    fn enumeration_is_finite() {
+        let enumerator = Enumerator::new(
+            &BOOL_AUTOMATON,
+            Box::new(PRandomizer::new(1)),
+            PathBounds::default(),
+        );
+        assert!(enumerator.count() > 0);
+    }
+
+    #[test]
+    fn enumeration_covers_low_weight_paths() {
+        let values: Vec<String> = Enumerator::new(
+            &NUMBER_AUTOMATON,
+            Box::new(PRandomizer::new(1)),
+            PathBounds::default(),
+        )
+        .collect();
+        assert!(values.contains(&String::from("∞")));
+        assert!(values.contains(&String::from("NaN")));
+    }
+
+    #[test]
+    fn enumeration_is_reproducible() {
+        let first = Enumerator::new(
+            &BOOL_AUTOMATON,
+            Box::new(PRandomizer::new(1)),
+            PathBounds::default(),
+        );
+        let sec = Enumerator::new(
+            &BOOL_AUTOMATON,
+            Box::new(PRandomizer::new(1)),
+            PathBounds::default(),
+        );
+        assert!(first.eq(sec));
+    }
+
+    #[test]
+    fn mutation_enumeration_covers_every_token() {
+        let bounds = PathBounds::default();
+        let input = "[true, null]";
+        let mutator = Mutator::new::<JsonLexer, Rule>(
+            Box::new(PRandomizer::new(1)),
+            input,
+            Rule::value,
+            Box::new(Config::new()),
+        )
+        .unwrap();
+        let values: Vec<String> = mutator.exhaustive(bounds).collect();
+
+        // every value differs from the input in at most one token
+        assert!(values.iter().any(|value| value.ends_with("null]")));
+        assert!(values.iter().any(|value| value.starts_with("[true")));
+        assert!(values.iter().any(|value| value.starts_with("[false")));
+    }
+}
@@ -1,5 +1,8 @@
+use crate::adaptive::{AdaptiveWeights, EdgeId};
+use crate::enumerator::{Enumerator, PathBounds};
 use crate::randomness::Randomizer;
 use crate::state_machine::Automaton;
+use crate::statistics::Statistics;
 
 /// A generation-based fuzzer.
 ///
@@ -17,15 +20,101 @@ use crate::state_machine::Automaton;
 pub struct Generator<T: 'static + Eq + Clone + Sync> {
     automaton: &'static Automaton<T>,
     seeder: Box<dyn Randomizer>,
+    adaptive: Option<AdaptiveWeights>,
+    trace: Vec<EdgeId>,
+    statistics: Option<Statistics>,
+    // measures the length of a value in bytes, set along with `statistics`
+    output_length: fn(&T) -> usize,
 }
 
 impl<T: Eq + Clone + Sync> Generator<T> {
     /// Creates a Generator instance based on the following input:
     /// - `automaton` - automaton static object, representing the protocol<->type pair
     /// - `seeder` - will be used for generating random mutations to the input
-    /// where R and P are protocol-specific types defined in [`crate::tokenizer`]
+    ///   where R and P are protocol-specific types defined in [`crate::tokenizer`]
     pub fn new(automaton: &'static Automaton<T>, seeder: Box<dyn Randomizer>) -> Self {
-        Self { automaton, seeder }
+        Self {
+            automaton,
+            seeder,
+            adaptive: None,
+            trace: vec![],
+            statistics: None,
+            output_length: |_| 0,
+        }
+    }
+
+    /// Enables adaptive weighting: transitions are chosen based on their
+    /// predefined weights adjusted by `weights`, which are in turn updated
+    /// through [`Generator::report`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::adaptive::AdaptiveWeights;
+    /// use talasum::json;
+    ///
+    /// let mut generator = json::number(1234).with_adaptive_weights(AdaptiveWeights::default());
+    /// for _ in 0..10 {
+    ///     let fuzzed = generator.next().unwrap();
+    ///     generator.report(fuzzed.contains('e'));
+    /// }
+    /// ```
+    pub fn with_adaptive_weights(mut self, weights: AdaptiveWeights) -> Self {
+        self.adaptive = Some(weights);
+        self
+    }
+
+    /// Reports whether the last generated value was interesting, e.g. it
+    /// triggered new behavior in the target. Has no effect unless adaptive
+    /// weighting is enabled.
+    pub fn report(&mut self, interesting: bool) {
+        if let Some(weights) = self.adaptive.as_mut() {
+            weights.update(&self.trace, interesting);
+        }
+        self.trace.clear();
+    }
+
+    /// Returns the learned weights if adaptive weighting is enabled
+    pub fn adaptive_weights(&self) -> Option<&AdaptiveWeights> {
+        self.adaptive.as_ref()
+    }
+
+    /// Returns the statistics collected so far if collection is enabled
+    pub fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    /// Turns the generator into one that yields a single value for each
+    /// distinct path through the automaton, see [`Enumerator`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::enumerator::PathBounds;
+    /// use talasum::json;
+    ///
+    /// for fuzzed in json::number(1234).exhaustive(PathBounds::default()) {
+    ///     println!("New number value: {}", fuzzed);
+    /// }
+    /// ```
+    pub fn exhaustive(self, bounds: PathBounds) -> Enumerator<T> {
+        Enumerator::new(self.automaton, self.seeder, bounds)
+    }
+}
+
+impl<T: Eq + Clone + Sync + AsRef<[u8]>> Generator<T> {
+    /// Enables collection of [`Statistics`] about the generated values
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::json;
+    ///
+    /// let mut generator = json::number(1234).with_statistics();
+    /// generator.by_ref().take(100).for_each(drop);
+    /// println!("{}", generator.statistics().unwrap());
+    /// ```
+    pub fn with_statistics(mut self) -> Self {
+        self.statistics = Some(Statistics::default());
+        self.output_length = |value| value.as_ref().len();
+        self
     }
 }
 
@@ -36,13 +125,29 @@ impl<T: Eq + Clone + Sync> Iterator for Generator<T> {
     ///
     /// Should never return `None`
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.automaton.generate(self.seeder.get()))
+        let seed = self.seeder.get();
+        if self.adaptive.is_none() && self.statistics.is_none() {
+            return Some(self.automaton.generate(seed));
+        }
+
+        self.trace.clear();
+        let value = self.automaton.generate_instrumented(
+            seed,
+            self.adaptive.as_ref(),
+            &mut self.trace,
+            self.statistics.as_mut(),
+        );
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.record_output((self.output_length)(&value));
+        }
+        Some(value)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Generator;
+    use crate::adaptive::AdaptiveWeights;
     use crate::randomness::PRandomizer;
     use crate::state_machine::json::number::NUMBER_AUTOMATON;
 
@@ -55,6 +160,64 @@ mod tests {
         assert_eq!(first.next().unwrap(), sec.next().unwrap());
     }
 
+    #[test]
+    fn adaptive_generation_is_reproducible() {
+        let mut first = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)))
+            .with_adaptive_weights(AdaptiveWeights::default());
+        let mut sec = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)))
+            .with_adaptive_weights(AdaptiveWeights::default());
+        for _ in 0..10 {
+            assert_eq!(first.next().unwrap(), sec.next().unwrap());
+            first.report(true);
+            sec.report(true);
+        }
+    }
+
+    #[test]
+    fn reports_update_adaptive_weights() {
+        let mut generator = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)))
+            .with_adaptive_weights(AdaptiveWeights::default());
+        generator.next();
+        generator.report(true);
+
+        // exactly one of the edges of the start state has been chosen
+        let weights = generator.adaptive_weights().unwrap();
+        let rewarded = (0..6)
+            .filter(|edge| weights.multiplier("json::number", 0, *edge) > 1.0)
+            .count();
+        assert_eq!(rewarded, 1);
+    }
+
+    #[test]
+    fn statistics_are_collected_upon_request() {
+        let mut generator = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)));
+        generator.next();
+        assert!(generator.statistics().is_none());
+
+        let mut generator = generator.with_statistics();
+        generator.by_ref().take(100).for_each(drop);
+        let statistics = generator.statistics().unwrap();
+        assert_eq!(statistics.outputs(), 100);
+        assert_eq!(statistics.node_visits()[&("json::number", 0)], 100);
+        assert_eq!(
+            statistics
+                .edge_choices()
+                .iter()
+                .filter(|((_, node, _), _)| *node == 0)
+                .map(|(_, cnt)| cnt)
+                .sum::<u64>(),
+            100
+        );
+    }
+
+    #[test]
+    fn collecting_statistics_does_not_change_output() {
+        let first = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)));
+        let sec =
+            Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1))).with_statistics();
+        assert!(first.take(100).eq(sec.take(100)));
+    }
+
     #[test]
     fn generation_is_seedable() {
         let mut first = Generator::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(1)));
@@ -0,0 +1,230 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::http::body::BODY_AUTOMATON;
+use crate::state_machine::http::header_name::HEADER_NAME_AUTOMATON;
+use crate::state_machine::http::header_value::HEADER_VALUE_AUTOMATON;
+use crate::state_machine::http::json_response::JSON_RESPONSE_AUTOMATON;
+use crate::state_machine::http::method::METHOD_AUTOMATON;
+use crate::state_machine::http::reason::REASON_AUTOMATON;
+use crate::state_machine::http::status_code::STATUS_CODE_AUTOMATON;
+use crate::state_machine::http::target::TARGET_AUTOMATON;
+use crate::state_machine::http::version::VERSION_AUTOMATON;
+use crate::tokenizer::http_lexer::{HttpLexer, Rule};
+
+/// Returns an HTTP method generator, which produces standard, unknown,
+/// oddly cased and malformed methods
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::method(1234).take(10) {
+///     println!("New method: {}", fuzzed);
+/// }
+/// ```
+pub fn method(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&METHOD_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP request target generator, which produces path traversals,
+/// encoded delimiters and targets in every form
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::target(1234).take(10) {
+///     println!("New request target: {}", fuzzed);
+/// }
+/// ```
+pub fn target(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&TARGET_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP version generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::version(1234).take(10) {
+///     println!("New version: {}", fuzzed);
+/// }
+/// ```
+pub fn version(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&VERSION_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP header name generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::header_name(1234).take(10) {
+///     println!("New header name: {}", fuzzed);
+/// }
+/// ```
+pub fn header_name(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&HEADER_NAME_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP header value generator, which produces folded values,
+/// injected headers and bare line endings among others
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::header_value(1234).take(10) {
+///     println!("New header value: {}", fuzzed);
+/// }
+/// ```
+pub fn header_value(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&HEADER_VALUE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP message body generator, which produces chunked bodies
+/// with invalid chunk sizes, chunk extensions and trailers, as well as
+/// smuggled requests
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::body(1234).take(10) {
+///     println!("New body: {}", fuzzed);
+/// }
+/// ```
+pub fn body(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&BODY_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP status code generator, which produces codes that change
+/// how the rest of a response is read, unassigned and malformed ones
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::status_code(1234).take(10) {
+///     println!("New status code: {}", fuzzed);
+/// }
+/// ```
+pub fn status_code(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&STATUS_CODE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP reason phrase generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::reason(1234).take(10) {
+///     println!("New reason phrase: {:?}", fuzzed);
+/// }
+/// ```
+pub fn reason(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&REASON_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a generator of complete `application/json` responses. Their
+/// bodies are fuzzed by the JSON automata, while their framing is either
+/// kept valid or broken, e.g. by a mismatched `Content-Length` or an early
+/// close of the connection.
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// for fuzzed in http::json_response(1234).take(10) {
+///     println!("New response: {:?}", fuzzed);
+/// }
+/// ```
+pub fn json_response(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&JSON_RESPONSE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an HTTP/1.1 request Mutator. Lines may end in CRLF or a bare LF.
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// let request = "POST /login HTTP/1.1\r\nHost: localhost\r\nContent-Length: 7\r\n\r\na=b&c=d";
+/// match http::mutate(request, 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New request: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid HTTP/1.1 request")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<HttpLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::request,
+        Box::new(Config::new()),
+    )
+}
+
+/// Returns an HTTP/1.1 response Mutator, for fuzzing clients. Bodies which
+/// are JSON documents are mutated as such.
+///
+/// # Examples
+/// ```rust
+/// use talasum::http;
+///
+/// let response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 8\r\n\r\n{\"id\":1}";
+/// match http::mutate_response(response, 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New response: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid HTTP/1.1 response")
+/// }
+///
+/// ```
+pub fn mutate_response(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<HttpLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::response,
+        Box::new(Config::new()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mutate, mutate_response};
+
+    #[test]
+    fn requests_are_mutated() {
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let mutated: Vec<String> = mutate(request, 1234).unwrap().take(200).collect();
+        assert!(mutated.iter().any(|value| value != request));
+        assert!(mutated
+            .iter()
+            .any(|value| value.contains("Transfer-Encoding")));
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        assert!(mutate("GET / HTTP/1.1\r\n", 1234).is_none());
+    }
+
+    #[test]
+    fn responses_are_mutated() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}";
+        let mutated: Vec<String> = mutate_response(response, 1234).unwrap().take(200).collect();
+        assert!(mutated.iter().any(|value| value.contains("Set-Cookie")));
+        assert!(mutate_response("GET / HTTP/1.1\r\n\r\n", 1234).is_none());
+    }
+}
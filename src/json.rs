@@ -1,13 +1,17 @@
+use crate::byte_mutator::ByteMutator;
 use crate::configuration::Config;
 use crate::generator::Generator;
 use crate::mutator::Mutator;
 use crate::randomness::PRandomizer;
+use crate::schema::SchemaGenerator;
 use crate::state_machine::json::array::ARRAY_AUTOMATON;
 use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+use crate::state_machine::json::ndjson::NDJSON_AUTOMATON;
+use crate::state_machine::json::nesting::NESTING_AUTOMATON;
 use crate::state_machine::json::null::NULL_AUTOMATON;
 use crate::state_machine::json::number::NUMBER_AUTOMATON;
 use crate::state_machine::json::object::OBJECT_AUTOMATON;
-use crate::state_machine::json::string::STRING_AUTOMATON;
+use crate::state_machine::json::string::{STRING_AUTOMATON, STRING_BYTES_AUTOMATON};
 use crate::tokenizer::json_lexer::{JsonLexer, Rule};
 
 /// Returns a JSON Number generator
@@ -66,6 +70,21 @@ pub fn string(seed: u64) -> Generator<String> {
     Generator::<String>::new(&STRING_AUTOMATON, Box::new(PRandomizer::new(seed)))
 }
 
+/// Returns a generator of raw JSON String bytes, which on top of the usual
+/// string mutations may contain invalid UTF-8 or switch encodings midway
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// for fuzzed in json::string_bytes(1234).take(10) {
+///     println!("New string value: {:?}", String::from_utf8_lossy(&fuzzed));
+/// }
+/// ```
+pub fn string_bytes(seed: u64) -> Generator<Vec<u8>> {
+    Generator::<Vec<u8>>::new(&STRING_BYTES_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
 /// Returns a JSON Array generator
 ///
 /// # Examples
@@ -94,6 +113,23 @@ pub fn object(seed: u64) -> Generator<String> {
     Generator::<String>::new(&OBJECT_AUTOMATON, Box::new(PRandomizer::new(seed)))
 }
 
+/// Returns a generator of deeply nested JSON values, meant for exhausting the
+/// stack of recursive parsers. Values are nested in arrays, objects or both,
+/// sometimes with unbalanced closing brackets. The max depth can be configured
+/// through the TALASUM_MAX_NESTING_DEPTH environment variable.
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// for fuzzed in json::deep_nesting(1234).take(10) {
+///     println!("New nested value of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn deep_nesting(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&NESTING_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
 /// Returns a JSON Mutator
 ///
 /// # Examples
@@ -110,7 +146,7 @@ pub fn object(seed: u64) -> Generator<String> {
 /// }
 ///
 /// ```
-pub fn mutate(input: &str, seed: u64) -> Option<Mutator> {
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
     Mutator::new::<JsonLexer, Rule>(
         Box::new(PRandomizer::new(seed)),
         input,
@@ -118,3 +154,128 @@ pub fn mutate(input: &str, seed: u64) -> Option<Mutator> {
         Box::new(Config::new()),
     )
 }
+
+/// Returns a Mutator for JSON Lines (NDJSON) streams, where each line is a
+/// separate JSON document. Documents are mutated independently, and every
+/// other mutation also drops, duplicates, reorders, joins or splits records,
+/// or inserts blank and CRLF-terminated lines.
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// match json::mutate_lines("{\"a\": 1}\n{\"b\": [true]}\n", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New stream: {}", fuzzed);
+///         }
+///     },
+///     None => panic!("One of the lines was not a valid JSON document")
+/// }
+///
+/// ```
+pub fn mutate_lines(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new_line_delimited::<JsonLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::record,
+        &NDJSON_AUTOMATON,
+        Box::new(Config::new()),
+    )
+}
+
+/// Returns a generator of JSON documents described by a JSON Schema. Every
+/// other document satisfies the schema, the rest violate exactly one of its
+/// constraints, e.g. a string one character longer than its `maxLength`.
+///
+/// Result will be [`std::option::Option::None`] if `schema` is not a valid
+/// JSON Schema document.
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// let schema = r#"{"properties": {"id": {"type": "integer", "minimum": 1}}, "required": ["id"]}"#;
+/// match json::from_schema(schema, 1234) {
+///     Some(mut generator) => {
+///         for _ in 0..10 {
+///             let fuzzed = generator.next().unwrap();
+///             println!("New document: {} violating {:?}", fuzzed, generator.last_violation());
+///         }
+///     },
+///     None => panic!("Your schema was not a valid JSON document")
+/// }
+/// ```
+pub fn from_schema(schema: &str, seed: u64) -> Option<SchemaGenerator> {
+    SchemaGenerator::new(schema, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON Mutator which, on top of the usual mutations, nests one of
+/// the mutated values in a large number of arrays and objects
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// match json::mutate_with_deep_nesting("{\"a\": 123, \"b\": [null, true]}", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New value of length: {}", fuzzed.len());
+///         }
+///     },
+///     None => panic!("Your input was not a valid JSON document")
+/// }
+///
+/// ```
+pub fn mutate_with_deep_nesting(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    mutate(input, seed).map(|mutator| mutator.with_nesting(&NESTING_AUTOMATON))
+}
+
+/// Returns a JSON Mutator which also mutates the separators between values,
+/// e.g. drops or doubles commas, replaces colons with `=`, adds trailing
+/// commas and exotic whitespace or unbalances brackets
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// match json::mutate_with_separators("{\"a\": 123, \"b\": [null, true]}", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New value: {}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid JSON document")
+/// }
+///
+/// ```
+pub fn mutate_with_separators(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new_with_separators::<JsonLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::value,
+        Box::new(Config::new()),
+    )
+}
+
+/// Returns a JSON Mutator working on raw bytes. Its output is not guaranteed
+/// to be valid UTF-8 - invalid sequences, NUL bytes and encoding switches are
+/// injected on purpose. Input which isn't a valid JSON document is still
+/// mutated, though only at the byte level.
+///
+/// # Examples
+/// ```rust
+/// use talasum::json;
+///
+/// for fuzzed in json::mutate_bytes(b"{\"a\": \"b\"}", 1234).take(10) {
+///     println!("New value: {:?}", fuzzed);
+/// }
+/// ```
+pub fn mutate_bytes(input: &[u8], seed: u64) -> ByteMutator<'_> {
+    ByteMutator::new::<JsonLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::value,
+        Box::new(Config::new()),
+    )
+}
@@ -0,0 +1,123 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::json5::array::ARRAY_AUTOMATON;
+use crate::state_machine::json5::comment::COMMENT_AUTOMATON;
+use crate::state_machine::json5::key::KEY_AUTOMATON;
+use crate::state_machine::json5::number::NUMBER_AUTOMATON;
+use crate::state_machine::json5::object::OBJECT_AUTOMATON;
+use crate::state_machine::json5::string::STRING_AUTOMATON;
+use crate::tokenizer::json5_lexer::{Json5Lexer, Rule};
+
+/// Returns a JSON5 Number generator, which on top of JSON numbers produces
+/// hexadecimal ones, `Infinity`, `NaN` and explicitly signed numbers
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::number(1234).take(10) {
+///     println!("New number value: {}", fuzzed);
+/// }
+/// ```
+pub fn number(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&NUMBER_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON5 String generator, which on top of JSON strings produces
+/// single-quoted and multi-line ones
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::string(1234).take(10) {
+///     println!("New string value: {}", fuzzed);
+/// }
+/// ```
+pub fn string(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&STRING_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a generator of JSON5 object keys - quoted, unquoted or escaped
+/// identifiers
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::key(1234).take(10) {
+///     println!("New key: {}", fuzzed);
+/// }
+/// ```
+pub fn key(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&KEY_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON5/JSONC comment generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::comment(1234).take(10) {
+///     println!("New comment: {}", fuzzed);
+/// }
+/// ```
+pub fn comment(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&COMMENT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON5 Array generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::array(1234).take(10) {
+///     println!("New array value: {}", fuzzed);
+/// }
+/// ```
+pub fn array(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&ARRAY_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON5 Object generator
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// for fuzzed in json5::object(1234).take(10) {
+///     println!("New object value: {}", fuzzed);
+/// }
+/// ```
+pub fn object(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&OBJECT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a JSON5 Mutator. JSONC documents are accepted as well.
+///
+/// # Examples
+/// ```rust
+/// use talasum::json5;
+///
+/// match json5::mutate("{a: 0x1F, /* comment */ b: ['c',],}", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New value: {}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid JSON5 document")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<Json5Lexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::json5,
+        Box::new(Config::new()),
+    )
+}
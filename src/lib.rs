@@ -1,5 +1,7 @@
 //! talasum is a library for protocol fuzzing. Currently the supported protocols
-//! are JSON and YAML.
+//! are JSON, JSON5 (including JSONC), YAML, HTTP/1.1, URIs, Unicode text,
+//! Markdown, XML, CSV, TOML, the protobuf wire format, with or without
+//! `.proto` definitions, and binary formats described by a [`binary::Layout`].
 //! It supports both generation-based and mutation-based fuzzing.
 //! For generation-based fuzzing one needs to specify a protocol <-> type
 //! pair, e.g. the number type of the JSON protocol.
@@ -14,13 +16,33 @@
 //! be disabled by TODO.
 //!
 
+pub mod adaptive;
+pub mod binary;
+mod binary_mutator;
+mod byte_mutator;
 mod configuration;
+pub mod csv;
+pub mod dedup;
+pub mod enumerator;
 mod generator;
+pub mod http;
 pub mod json;
+pub mod json5;
+pub mod markdown;
 mod mutator;
+pub mod proto;
+pub mod protobuf;
 mod randomness;
+pub mod schema;
+pub mod selector;
 mod state_machine;
+pub mod statistics;
 mod tokenizer;
+pub mod toml;
+pub mod unicode;
+pub mod uri;
+pub mod xml;
+pub mod yaml;
 
 extern crate pest;
 #[macro_use]
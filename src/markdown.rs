@@ -0,0 +1,128 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::markdown::code::CODE_FENCE_AUTOMATON;
+use crate::state_machine::markdown::emphasis::EMPHASIS_AUTOMATON;
+use crate::state_machine::markdown::html::HTML_AUTOMATON;
+use crate::state_machine::markdown::link::LINK_AUTOMATON;
+use crate::state_machine::markdown::table::TABLE_AUTOMATON;
+use crate::tokenizer::markdown_lexer::{MarkdownLexer, Rule};
+
+/// Returns a Markdown emphasis generator, which produces unbalanced
+/// delimiter runs and nested emphasis known to make parsers go quadratic
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// for fuzzed in markdown::emphasis(1234).take(10) {
+///     println!("New emphasis of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn emphasis(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&EMPHASIS_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a Markdown link generator, which produces script destinations,
+/// deeply nested brackets and titles breaking out of their attribute
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// for fuzzed in markdown::link(1234).take(10) {
+///     println!("New link of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn link(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&LINK_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a Markdown code fence generator, which produces fences of
+/// mismatched lengths or characters and unclosed ones
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// for fuzzed in markdown::code_fence(1234).take(10) {
+///     println!("New code fence: {}", fuzzed);
+/// }
+/// ```
+pub fn code_fence(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&CODE_FENCE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a GFM table generator, which produces ragged rows, escaped pipes
+/// and tables with many rows or columns
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// for fuzzed in markdown::table(1234).take(10) {
+///     println!("New table of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn table(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&TABLE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a raw HTML generator, which produces scripts, event handlers and
+/// constructs making HTML blocks swallow the rest of the document
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// for fuzzed in markdown::html(1234).take(10) {
+///     println!("New HTML: {}", fuzzed);
+/// }
+/// ```
+pub fn html(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&HTML_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a Markdown (CommonMark with GFM tables) Mutator. Any text is
+/// valid Markdown, but only its emphasis, links, code, raw HTML, entities,
+/// tables and block markers are mutated.
+///
+/// # Examples
+/// ```rust
+/// use talasum::markdown;
+///
+/// match markdown::mutate("# Title\n\n- *a* [b](https://example.com)\n", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New document of length: {}", fuzzed.len());
+///         }
+///     },
+///     None => panic!("Your input could not be tokenized")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<MarkdownLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::markdown,
+        Box::new(Config::new()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+
+    #[test]
+    fn documents_are_mutated() {
+        let document = "# Title\n\n> *a* [b](https://example.com)\n";
+        let mutated: Vec<String> = mutate(document, 1234).unwrap().take(100).collect();
+        assert!(mutated.iter().any(|value| value.contains("javascript:")
+            || value.contains(">>>>")
+            || value.contains("#######")));
+        // text between tokens is kept as is
+        assert!(mutated.iter().all(|value| value.contains(" Title\n\n")));
+    }
+}
@@ -1,6 +1,12 @@
+use crate::adaptive::{AdaptiveWeights, EdgeId};
 use crate::configuration::Configurable;
+use crate::enumerator::{MutationEnumerator, PathBounds};
 use crate::randomness::Randomizer;
-use crate::tokenizer::tokenize_input;
+use crate::selector::{select, Selector};
+use crate::state_machine::weights::CONFIG;
+use crate::state_machine::Automaton;
+use crate::statistics::Statistics;
+use crate::tokenizer::{tokenize_input, tokenize_input_with_separators, tokenize_lines};
 use crate::tokenizer::{AutomatonToken, LexerRule};
 use pest::Parser;
 use std::collections::{BTreeMap, BTreeSet};
@@ -19,6 +25,14 @@ pub struct Mutator<'a> {
     tokens: Vec<AutomatonToken<'a>>,
     input: &'a str,
     config: Box<dyn Configurable>,
+    adaptive: Option<AdaptiveWeights>,
+    trace: Vec<EdgeId>,
+    statistics: Option<Statistics>,
+    nesting: Option<&'static Automaton<String>>,
+    records: Option<&'static Automaton<String>>,
+    included: Vec<Selector>,
+    excluded: Vec<Selector>,
+    selected: Vec<usize>,
 }
 
 impl<'a> Mutator<'a> {
@@ -26,7 +40,7 @@ impl<'a> Mutator<'a> {
     /// - `seeder` - will be used for generating random mutations to the input
     /// - `input` - valid input as per the protocol's specification
     /// - `rule` - name of the top rule of the corresponding PEG, usually R::value,
-    /// where R and P are protocol-specific types defined in [`crate::tokenizer`]
+    ///   where R and P are protocol-specific types defined in [`crate::tokenizer`]
     ///
     /// Result will be [`std::option::Option::None`] if the input is invalid as per the underlying
     /// protocol grammar, e.g. "{1}" is not a valid JSON input, hence cannot
@@ -37,13 +51,197 @@ impl<'a> Mutator<'a> {
         rule: R,
         config: Box<dyn Configurable>,
     ) -> Option<Self> {
-        tokenize_input::<'a, P, R>(input, rule).map(|tokens| Self {
+        tokenize_input::<'a, P, R>(input, rule)
+            .map(|tokens| Self::from_tokens(seeder, tokens, input, config))
+        // TODO ERROR log in case of invalid input
+    }
+
+    /// Creates a Mutator instance which, unlike [`Mutator::new`], also
+    /// mutates separators such as commas and colons, as per
+    /// [`LexerRule::pest_to_separator_automaton`]. As they are tokens on
+    /// their own, fewer values are mutated at a time.
+    pub(crate) fn new_with_separators<P: Parser<R>, R: 'a + LexerRule>(
+        seeder: Box<dyn Randomizer>,
+        input: &'a str,
+        rule: R,
+        config: Box<dyn Configurable>,
+    ) -> Option<Self> {
+        tokenize_input_with_separators::<'a, P, R>(input, rule)
+            .map(|tokens| Self::from_tokens(seeder, tokens, input, config))
+    }
+
+    /// Creates a Mutator instance for newline-delimited input, e.g. JSON
+    /// Lines, where each line is a separate document as per `rule`. On top of
+    /// mutating the documents independently, the lines themselves are
+    /// changed by `records`, e.g. dropped or reordered.
+    ///
+    /// Result will be [`std::option::Option::None`] if any of the lines is
+    /// invalid as per the underlying protocol grammar.
+    pub(crate) fn new_line_delimited<P: Parser<R>, R: 'a + LexerRule>(
+        seeder: Box<dyn Randomizer>,
+        input: &'a str,
+        rule: R,
+        records: &'static Automaton<String>,
+        config: Box<dyn Configurable>,
+    ) -> Option<Self> {
+        tokenize_lines::<'a, P, R>(input, rule)
+            .map(|tokens| Self::from_tokens(seeder, tokens, input, config).with_records(records))
+    }
+
+    fn from_tokens(
+        seeder: Box<dyn Randomizer>,
+        tokens: Vec<AutomatonToken<'a>>,
+        input: &'a str,
+        config: Box<dyn Configurable>,
+    ) -> Self {
+        Self {
             seeder,
+            selected: (0..tokens.len()).collect(),
             tokens,
             input,
             config,
-        })
-        // TODO ERROR log in case of invalid input
+            adaptive: None,
+            trace: vec![],
+            statistics: None,
+            nesting: None,
+            records: None,
+            included: vec![],
+            excluded: vec![],
+        }
+    }
+
+    /// Enables adaptive weighting: transitions are chosen based on their
+    /// predefined weights adjusted by `weights`, which are in turn updated
+    /// through [`Mutator::report`].
+    pub fn with_adaptive_weights(mut self, weights: AdaptiveWeights) -> Self {
+        self.adaptive = Some(weights);
+        self
+    }
+
+    /// Reports whether the last mutated value was interesting, e.g. it
+    /// triggered new behavior in the target. Has no effect unless adaptive
+    /// weighting is enabled.
+    pub fn report(&mut self, interesting: bool) {
+        if let Some(weights) = self.adaptive.as_mut() {
+            weights.update(&self.trace, interesting);
+        }
+        self.trace.clear();
+    }
+
+    /// Returns the learned weights if adaptive weighting is enabled
+    pub fn adaptive_weights(&self) -> Option<&AdaptiveWeights> {
+        self.adaptive.as_ref()
+    }
+
+    /// Enables collection of [`Statistics`] about the mutated values
+    pub fn with_statistics(mut self) -> Self {
+        self.statistics = Some(Statistics::default());
+        self
+    }
+
+    /// Returns the statistics collected so far if collection is enabled
+    pub fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    /// Enables injection of deep nesting: on each mutation, one of the chosen
+    /// tokens is nested by `nesting` instead of being fuzzed by its own
+    /// automaton
+    pub(crate) fn with_nesting(mut self, nesting: &'static Automaton<String>) -> Self {
+        self.nesting = Some(nesting);
+        self
+    }
+
+    /// Enables changes to the input as a whole, e.g. to its records or line
+    /// breaks: on every other mutation, the result is moved through `records`
+    /// once its tokens are fuzzed
+    pub(crate) fn with_records(mut self, records: &'static Automaton<String>) -> Self {
+        self.records = Some(records);
+        self
+    }
+
+    /// Restricts mutation to the values matched by `selector`, along with
+    /// their members and elements. When called multiple times, values matched
+    /// by any of the selectors are mutated.
+    ///
+    /// Paths are tracked for JSON and JSON5 documents. In JSON Lines streams
+    /// paths are relative to each line.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::json;
+    /// use talasum::selector::Selector;
+    ///
+    /// let mutator = json::mutate("{\"items\": [{\"price\": 1, \"name\": \"a\"}]}", 1234)
+    ///     .unwrap()
+    ///     .with_included_paths(Selector::new("/items/*/price").unwrap());
+    /// for fuzzed in mutator.take(10) {
+    ///     println!("New value: {}", fuzzed);
+    /// }
+    /// ```
+    pub fn with_included_paths(mut self, selector: Selector) -> Self {
+        self.included.push(selector);
+        self.select();
+        self
+    }
+
+    /// Locks the values matched by `selector`, e.g. auth tokens, so that they
+    /// are kept intact. Neither they nor the values they are nested in are
+    /// mutated. Locking takes precedence over
+    /// [`Mutator::with_included_paths`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::json;
+    /// use talasum::selector::Selector;
+    ///
+    /// let mutator = json::mutate("{\"token\": \"abc\", \"query\": [1, 2]}", 1234)
+    ///     .unwrap()
+    ///     .with_excluded_paths(Selector::new("/token").unwrap());
+    /// for fuzzed in mutator.take(10) {
+    ///     assert!(fuzzed.contains("\"abc\""));
+    /// }
+    /// ```
+    pub fn with_excluded_paths(mut self, selector: Selector) -> Self {
+        self.excluded.push(selector);
+        self.select();
+        self
+    }
+
+    /// Computes the indices of the tokens which can be chosen for mutation
+    /// as per the included and excluded paths
+    fn select(&mut self) {
+        let parts: Vec<(usize, usize, &str)> = self
+            .tokens
+            .iter()
+            .map(|token| (token.from, token.to, token.path.as_str()))
+            .collect();
+        self.selected = select(&parts, &self.included, &self.excluded);
+    }
+
+    /// Turns the mutator into one that yields a single value for each
+    /// token<->path pair, see [`MutationEnumerator`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::enumerator::PathBounds;
+    /// use talasum::json;
+    ///
+    /// let mutator = json::mutate("[1, true]", 1234).unwrap();
+    /// for fuzzed in mutator.exhaustive(PathBounds::default()) {
+    ///     println!("New value: {}", fuzzed);
+    /// }
+    /// ```
+    pub fn exhaustive(self, bounds: PathBounds) -> MutationEnumerator<'a> {
+        let selected = self.selected;
+        let tokens = self
+            .tokens
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| selected.binary_search(idx).is_ok())
+            .map(|(_, token)| token)
+            .collect();
+        MutationEnumerator::new(self.seeder, tokens, self.input, bounds)
     }
 
     /// Calculates the new index of the element at `original`
@@ -53,7 +251,7 @@ impl<'a> Mutator<'a> {
     ///
     /// For example, the pair 5 -> -2 means that the 5th element was, at some point,
     /// moved to index 3.
-    fn get_moved_index(offset_table: &BTreeMap<usize, i64>, original: usize) -> usize {
+    pub(crate) fn get_moved_index(offset_table: &BTreeMap<usize, i64>, original: usize) -> usize {
         offset_table
             .range(0..original)
             .fold(original, |acc, (_, offset)| (acc as i64 + offset) as usize)
@@ -65,7 +263,11 @@ impl<'a> Mutator<'a> {
     ///
     /// For example, the pair 5 -> -2 means that the 5th element was, at some point,
     /// moved to index 3.
-    fn move_index(offset_table: &mut BTreeMap<usize, i64>, original: usize, offset: i64) {
+    pub(crate) fn move_index(
+        offset_table: &mut BTreeMap<usize, i64>,
+        original: usize,
+        offset: i64,
+    ) {
         if offset != 0 {
             offset_table
                 .entry(original)
@@ -74,24 +276,13 @@ impl<'a> Mutator<'a> {
         }
     }
 
-    /// Chooses a set of tokens to be fuzzed.
+    /// Chooses a set of tokens to be fuzzed out of the selected ones.
     /// Outputs the indices of the tokens to be fuzzed in ascending order.
     fn choose_for_mutation(&self, seed: u64) -> BTreeSet<usize> {
-        if self.tokens.is_empty() {
-            BTreeSet::<usize>::new()
-        } else {
-            let final_cnt = self.get_tokens_count();
-            let mut curr_idx = seed as usize % self.tokens.len();
-
-            let mut chosen = BTreeSet::<usize>::new();
-            for _ in 0..final_cnt {
-                chosen.insert(curr_idx);
-
-                // + 1 in order to avoid cycles
-                curr_idx = (curr_idx + 1) % self.tokens.len();
-            }
-            chosen
-        }
+        choose_tokens(seed, self.selected.len(), self.get_tokens_count())
+            .into_iter()
+            .map(|idx| self.selected[idx])
+            .collect()
     }
 
     /// Computes the number of tokens to be fuzzed based on config.
@@ -99,17 +290,17 @@ impl<'a> Mutator<'a> {
     /// If the H coefficient is set to max, every single token in the input
     /// will be fuzzed, effectively getting the behavior of a [`crate::Generator`].
     fn get_tokens_count(&self) -> usize {
-        ((self.config.get_horizontal_randomness_coef() as f32 / 100_f32)
-            * (self.tokens.len() as f32))
-            .ceil() as usize
+        tokens_count(self.config.as_ref(), self.selected.len())
     }
 
     /// Fuzzes the token at index `idx` using the `seed` value and
-    /// updates the offset table and result value after.
+    /// updates the offset table and result value after. If `nest` is set,
+    /// the token is nested rather than fuzzed.
     fn fuzz_token(
-        &self,
+        &mut self,
         seed: u64,
         idx: usize,
+        nest: bool,
         offsets: &mut BTreeMap<usize, i64>,
         result: &mut String,
     ) {
@@ -117,13 +308,18 @@ impl<'a> Mutator<'a> {
             from,
             to,
             automaton,
+            ..
         } = self.tokens[idx];
+        let automaton = match self.nesting {
+            Some(nesting) if nest => nesting,
+            _ => automaton,
+        };
 
         let new_from = Self::get_moved_index(offsets, from);
         let new_to = Self::get_moved_index(offsets, to);
 
         if let Some(to_fuzz) = result.get(new_from..new_to) {
-            let fuzzed = &automaton.traverse(String::from(to_fuzz), seed);
+            let fuzzed = &self.traverse(automaton, String::from(to_fuzz), seed);
             result.replace_range(new_from..new_to, fuzzed);
             Self::move_index(
                 offsets,
@@ -135,28 +331,134 @@ impl<'a> Mutator<'a> {
         }
     }
 
+    /// Moves `input` through `automaton`, recording the path taken if
+    /// adaptive weighting or statistics are enabled
+    fn traverse(&mut self, automaton: &Automaton<String>, input: String, seed: u64) -> String {
+        if self.adaptive.is_none() && self.statistics.is_none() {
+            automaton.traverse(input, seed)
+        } else {
+            automaton.traverse_instrumented(
+                input,
+                seed,
+                self.adaptive.as_ref(),
+                &mut self.trace,
+                self.statistics.as_mut(),
+            )
+        }
+    }
+
     /// Fuzzes the whole input
     fn fuzz(&mut self) -> String {
         let next_seed = self.seeder.get();
         let mut offsets = BTreeMap::<usize, i64>::new();
         let mut result = String::from(self.input);
-
-        for idx in self.choose_for_mutation(next_seed) {
-            self.fuzz_token(next_seed, idx, &mut offsets, &mut result);
+        self.trace.clear();
+
+        let chosen = self.choose_for_mutation(next_seed);
+        let nested = match self.nesting {
+            Some(_) if !chosen.is_empty() => chosen
+                .iter()
+                .nth(next_seed as usize % chosen.len())
+                .copied(),
+            _ => None,
+        };
+        for idx in chosen {
+            if let Some(statistics) = self.statistics.as_mut() {
+                statistics.record_token(self.tokens[idx].automaton.name());
+            }
+            self.fuzz_token(
+                next_seed,
+                idx,
+                nested == Some(idx),
+                &mut offsets,
+                &mut result,
+            );
+        }
+        // the lines are changed on every other mutation, after their contents
+        // so that the token offsets still apply
+        if let Some(records) = self.records {
+            if (next_seed >> 32).is_multiple_of(2) {
+                if let Some(statistics) = self.statistics.as_mut() {
+                    statistics.record_token(records.name());
+                }
+                result = self.traverse(records, result, next_seed);
+            }
+        }
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.record_output(result.len());
         }
         result
     }
 }
 
+/// Chooses `cnt` consecutive indices out of `len` tokens, starting from a
+/// seed-based one and wrapping around. Outputs the indices in ascending order.
+pub(crate) fn choose_tokens(seed: u64, len: usize, cnt: usize) -> BTreeSet<usize> {
+    if len == 0 {
+        BTreeSet::<usize>::new()
+    } else {
+        let mut curr_idx = seed as usize % len;
+
+        let mut chosen = BTreeSet::<usize>::new();
+        for _ in 0..cnt {
+            chosen.insert(curr_idx);
+
+            // + 1 in order to avoid cycles
+            curr_idx = (curr_idx + 1) % len;
+        }
+        chosen
+    }
+}
+
+/// Computes the number of tokens out of `len` to be fuzzed, proportionally to
+/// the horizontal fuzzing coefficient
+pub(crate) fn tokens_count(config: &dyn Configurable, len: usize) -> usize {
+    ((config.get_horizontal_randomness_coef() as f32 / 100_f32) * (len as f32)).ceil() as usize
+}
+
+/// Mutates `input` once as per the grammar of `P`, e.g. a JSON document
+/// embedded in an HTTP body. Unlike a [`Mutator`], it relies on the shared
+/// [`CONFIG`], which makes it cheap enough to be called by automata.
+///
+/// Result will be [`std::option::Option::None`] if the input is invalid as
+/// per the underlying protocol grammar.
+pub(crate) fn mutate_once<P: Parser<R>, R: LexerRule>(
+    input: &str,
+    rule: R,
+    seed: u64,
+) -> Option<String> {
+    let tokens = tokenize_input::<P, R>(input, rule)?;
+    let mut offsets = BTreeMap::<usize, i64>::new();
+    let mut result = String::from(input);
+    for idx in choose_tokens(seed, tokens.len(), tokens_count(&*CONFIG, tokens.len())) {
+        let AutomatonToken {
+            from,
+            to,
+            automaton,
+            ..
+        } = tokens[idx];
+        let new_from = Mutator::get_moved_index(&offsets, from);
+        let new_to = Mutator::get_moved_index(&offsets, to);
+        let fuzzed = automaton.traverse(String::from(&result[new_from..new_to]), seed);
+        Mutator::move_index(
+            &mut offsets,
+            to,
+            fuzzed.len() as i64 - (new_to - new_from) as i64,
+        );
+        result.replace_range(new_from..new_to, &fuzzed);
+    }
+    Some(result)
+}
+
 impl<'a> Iterator for Mutator<'a> {
     type Item = String;
 
     /// Computes a new fuzz value.
     ///
     /// Returns `None` if the input doesn't contain
-    /// any tokens, e.g. an empty string.
+    /// any tokens, e.g. an empty string, or none of them is selected.
     fn next(&mut self) -> Option<Self::Item> {
-        if self.tokens.is_empty() {
+        if self.selected.is_empty() {
             None
         } else {
             Some(self.fuzz())
@@ -166,13 +468,15 @@ impl<'a> Iterator for Mutator<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::Mutator;
+    use super::{mutate_once, Mutator};
+    use crate::adaptive::AdaptiveWeights;
     use crate::configuration::{Config, Configurable, MockConfigurable};
     use crate::randomness::PRandomizer;
+    use crate::selector::Selector;
     use crate::tokenizer::json_lexer::{JsonLexer, Rule};
     use std::collections::BTreeMap;
 
-    fn get_mutator_helper(seed: u64, input: &str) -> Mutator {
+    fn get_mutator_helper(seed: u64, input: &str) -> Mutator<'_> {
         Mutator::new::<JsonLexer, Rule>(
             Box::new(PRandomizer::new(seed)),
             input,
@@ -182,7 +486,11 @@ mod tests {
         .unwrap()
     }
 
-    fn get_mocked_mutator_helper(seed: u64, input: &str, config: Box<dyn Configurable>) -> Mutator {
+    fn get_mocked_mutator_helper(
+        seed: u64,
+        input: &str,
+        config: Box<dyn Configurable>,
+    ) -> Mutator<'_> {
         Mutator::new::<JsonLexer, Rule>(
             Box::new(PRandomizer::new(seed)),
             input,
@@ -329,7 +637,7 @@ mod tests {
     fn automata_are_chosen_in_asc_order() {
         let mutator = get_mutator_helper(123, "1234");
         let chosen = mutator.choose_for_mutation(100);
-        assert!(chosen.len() > 0);
+        assert!(!chosen.is_empty());
         for el_idx in 1..chosen.len() {
             assert!(chosen.get(&el_idx).unwrap() > chosen.get(&(el_idx - 1)).unwrap());
         }
@@ -356,11 +664,113 @@ mod tests {
         get_mutator_helper(123, "[1,2,3]").fuzz_token(
             123,
             10000,
+            false,
             &mut BTreeMap::<usize, i64>::new(),
             &mut String::new(),
         );
     }
 
+    #[test]
+    fn nesting_is_injected_at_a_chosen_token() {
+        let mut mutator = get_mutator_helper(123, "{\"a\": [1, 2]}")
+            .with_nesting(&crate::state_machine::json::nesting::NESTING_AUTOMATON);
+        let deepest = (0..20)
+            .map(|_| mutator.next().unwrap())
+            .map(|value| value.matches(['[', '{']).count())
+            .max()
+            .unwrap();
+        assert!(deepest > 100);
+    }
+
+    #[test]
+    fn line_delimited_records_are_mutated() {
+        let input = "{\"a\": 1}\r\n\n[true, null]\n";
+        let mut mutator = Mutator::new_line_delimited::<JsonLexer, Rule>(
+            Box::new(PRandomizer::new(1)),
+            input,
+            Rule::record,
+            &crate::state_machine::json::ndjson::NDJSON_AUTOMATON,
+            Box::new(Config::new()),
+        )
+        .unwrap();
+        assert_eq!(mutator.tokens.len(), 6);
+        assert!(mutator.by_ref().take(20).any(|value| value
+            .lines()
+            .filter(|line| !line.is_empty())
+            .count()
+            != 2));
+    }
+
+    #[test]
+    fn line_delimited_input_requires_valid_lines() {
+        assert!(Mutator::new_line_delimited::<JsonLexer, Rule>(
+            Box::new(PRandomizer::new(1)),
+            "1\n2 3\n",
+            Rule::record,
+            &crate::state_machine::json::ndjson::NDJSON_AUTOMATON,
+            Box::new(Config::new()),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn separators_are_mutated_on_request() {
+        let mut mutator = Mutator::new_with_separators::<JsonLexer, Rule>(
+            Box::new(PRandomizer::new(1)),
+            "[1, 2]",
+            Rule::value,
+            Box::new(Config::new()),
+        )
+        .unwrap()
+        .with_statistics();
+        // two gaps and a comma on top of the array and its elements
+        assert_eq!(mutator.tokens.len(), 6);
+        assert_eq!(get_mutator_helper(1, "[1, 2]").tokens.len(), 3);
+        mutator.by_ref().take(10).for_each(drop);
+        assert!(mutator.statistics().unwrap().tokens_chosen()["json::comma"] > 0);
+    }
+
+    #[test]
+    fn included_paths_restrict_mutation() {
+        let mutator = get_mutator_helper(1, "{\"a\": [1, 2], \"b\": {\"c\": 3}}")
+            .with_included_paths(Selector::new("/b").unwrap())
+            .with_included_paths(Selector::new("/a/1").unwrap());
+        let paths: Vec<&str> = mutator
+            .selected
+            .iter()
+            .map(|idx| mutator.tokens[*idx].path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["/b/c", "/b/c", "/b", "/b", "/a/1"]);
+        for seed in 0..10 {
+            for idx in mutator.choose_for_mutation(seed) {
+                assert!(mutator.selected.contains(&idx));
+            }
+        }
+    }
+
+    #[test]
+    fn excluded_paths_are_kept_intact() {
+        let input = "{\"id\": \"abc\", \"items\": [1, true]}";
+        let mutator =
+            get_mutator_helper(1, input).with_excluded_paths(Selector::new("/id").unwrap());
+        let selected: Vec<&str> = mutator
+            .selected
+            .iter()
+            .map(|idx| &input[mutator.tokens[*idx].from..mutator.tokens[*idx].to])
+            .collect();
+        assert_eq!(selected, vec!["true", "1", "[1, true]", "\"items\""]);
+        for fuzzed in mutator.take(50) {
+            assert!(fuzzed.starts_with("{\"id\": \"abc\", "), "{}", fuzzed);
+        }
+    }
+
+    #[test]
+    fn nothing_is_mutated_when_no_path_is_selected() {
+        let mut mutator =
+            get_mutator_helper(1, "[1]").with_included_paths(Selector::new("/a").unwrap());
+        assert_eq!(mutator.next(), None);
+    }
+
     #[test]
     fn there_is_always_at_least_one_token_to_be_fuzzed() {
         let mut mocked: MockConfigurable = MockConfigurable::new();
@@ -376,7 +786,7 @@ mod tests {
 
     #[test]
     fn number_of_fuzzed_tokens_is_proportional_to_h_coef() {
-        for (cnt, coef) in vec![
+        for (cnt, coef) in [
             (1, 20_u32),
             (2, 40_u32),
             (3, 60_u32),
@@ -395,6 +805,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reports_update_adaptive_weights() {
+        let mut mutator =
+            get_mutator_helper(1, "true").with_adaptive_weights(AdaptiveWeights::new(0.5, 0.5));
+        mutator.next();
+        mutator.report(false);
+
+        // the start state of the boolean automaton has two edges
+        let weights = mutator.adaptive_weights().unwrap();
+        let punished = (0..2)
+            .filter(|edge| weights.multiplier("json::boolean", 0, *edge) < 1.0)
+            .count();
+        assert_eq!(punished, 1);
+    }
+
+    #[test]
+    fn statistics_count_tokens_chosen_for_mutation() {
+        let mut mocked: MockConfigurable = MockConfigurable::new();
+        mocked
+            .expect_get_horizontal_randomness_coef()
+            .return_const(100_u32);
+
+        let mut mutator =
+            get_mocked_mutator_helper(123, "[1, true]", Box::new(mocked)).with_statistics();
+        mutator.by_ref().take(10).for_each(drop);
+        let statistics = mutator.statistics().unwrap();
+        assert_eq!(statistics.outputs(), 10);
+        assert_eq!(statistics.tokens_chosen()["json::array"], 10);
+        assert_eq!(statistics.tokens_chosen()["json::number"], 10);
+        assert_eq!(statistics.tokens_chosen()["json::boolean"], 10);
+    }
+
+    #[test]
+    fn embedded_documents_are_mutated_once() {
+        assert!(mutate_once::<JsonLexer, Rule>("{", Rule::value, 1).is_none());
+        assert!((0..10).any(|seed| {
+            mutate_once::<JsonLexer, Rule>("[1, true]", Rule::value, seed).unwrap() != "[1, true]"
+        }));
+    }
+
     #[test]
     fn mutating_different_inputs_produces_different_result() {
         let mut first = get_mutator_helper(1, "123");
@@ -0,0 +1,408 @@
+use super::parse::{FieldDef, FieldType, Label, ProtoFile, Scalar};
+use super::MAX_DEPTH;
+use crate::randomness::{PRandomizer, Randomizer};
+use crate::schema::Violation;
+use crate::selector::child_path;
+use crate::state_machine::binary::encode_varint;
+use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+use crate::state_machine::json::number::NUMBER_AUTOMATON;
+use crate::state_machine::json::string::STRING_AUTOMATON;
+use crate::state_machine::protobuf::{encode_tag, zigzag, WireType};
+
+/// Numbers of elements of repeated fields which are unusually large
+const EXTREME_COUNTS: [usize; 2] = [1000, 10000];
+
+impl ProtoFile {
+    /// Collects the constraints of `message` which can be violated, pointed
+    /// to by their paths, e.g. "/items/0/kind". Repeated messages are
+    /// violated through their first element.
+    pub(super) fn sites(
+        &self,
+        message: &str,
+        path: &str,
+        depth: usize,
+        sites: &mut Vec<Violation>,
+    ) {
+        let message = match self.messages.get(message) {
+            Some(message) if depth <= MAX_DEPTH => message,
+            _ => return,
+        };
+        let mut site = |path: &str, keyword| {
+            sites.push(Violation {
+                path: String::from(path),
+                keyword,
+            })
+        };
+        for (idx, oneof) in message.oneofs.iter().enumerate() {
+            let members = message
+                .fields
+                .iter()
+                .filter(|field| field.oneof == Some(idx))
+                .count();
+            if members > 1 {
+                site(&child_path(path, oneof), "oneof");
+            }
+        }
+        let mut nested = vec![];
+        for field in &message.fields {
+            let path = child_path(path, &field.name);
+            site(&path, "type");
+            match field.label {
+                Label::Required => site(&path, "required"),
+                Label::Repeated if matches!(field.kind, FieldType::Map(..)) => site(&path, "map"),
+                Label::Repeated => site(&path, "repeated"),
+                _ => {}
+            }
+            match &field.kind {
+                FieldType::Enum(_) => site(&path, "enum"),
+                FieldType::Scalar(scalar) if out_of_range(*scalar).is_some() => {
+                    site(&path, "range")
+                }
+                FieldType::Message(name) if field.label == Label::Repeated => {
+                    nested.push((name, child_path(&path, "0")))
+                }
+                FieldType::Message(name) => nested.push((name, path)),
+                _ => {}
+            }
+        }
+        for (name, path) in nested {
+            self.sites(name, &path, depth + 1, sites);
+        }
+    }
+
+    /// Encodes a `message` at `path`, violating the constraint at `site` if
+    /// it's within the message
+    pub(super) fn message(
+        &self,
+        message: &str,
+        seed: u64,
+        path: &str,
+        depth: usize,
+        site: Option<&Violation>,
+    ) -> Vec<u8> {
+        let message = match self.messages.get(message) {
+            // only reachable through required recursive fields
+            Some(message) if depth <= 2 * MAX_DEPTH => message,
+            _ => return vec![],
+        };
+        let targets = |path: &str| {
+            site.is_some_and(|site| {
+                site.path == path || site.path.starts_with(&format!("{}/", path))
+            })
+        };
+
+        // the members of each oneof which are set, usually one or none
+        let mut set = vec![];
+        for (idx, oneof) in message.oneofs.iter().enumerate() {
+            let members: Vec<&FieldDef> = message
+                .fields
+                .iter()
+                .filter(|field| field.oneof == Some(idx))
+                .collect();
+            let oneof_path = child_path(path, oneof);
+            let chosen = derive(seed, idx as u64) as usize % (members.len() + 1);
+            if targets(&oneof_path) && members.len() > 1 {
+                set.push(members[chosen % members.len()].number);
+                set.push(members[(chosen + 1) % members.len()].number);
+            } else if let Some(member) = members
+                .iter()
+                .find(|member| targets(&child_path(path, &member.name)))
+            {
+                set.push(member.number);
+            } else if chosen < members.len() && depth < MAX_DEPTH {
+                set.push(members[chosen].number);
+            }
+        }
+
+        let mut encoded = vec![];
+        for (idx, field) in message.fields.iter().enumerate() {
+            let seed = derive(seed, 1000 + idx as u64);
+            let path = child_path(path, &field.name);
+            let site = site.filter(|_| targets(&path));
+            let present = match field.label {
+                _ if field.oneof.is_some() => set.contains(&field.number),
+                _ if site.is_some_and(|site| site.path == path && site.keyword == "required") => {
+                    false
+                }
+                Label::Required => true,
+                _ if site.is_some() => true,
+                _ => depth < MAX_DEPTH && !seed.is_multiple_of(3),
+            };
+            if present {
+                encoded.extend(self.field(field, seed, &path, depth, site));
+            }
+        }
+        encoded
+    }
+
+    /// Encodes all occurrences of a present field
+    fn field(
+        &self,
+        field: &FieldDef,
+        seed: u64,
+        path: &str,
+        depth: usize,
+        site: Option<&Violation>,
+    ) -> Vec<u8> {
+        let at_field = site.filter(|site| site.path == path);
+        let keyword = at_field.map(|site| site.keyword);
+        if field.label != Label::Repeated {
+            return match keyword {
+                Some("type") => mismatched(field.number, self.wire_type(&field.kind), seed),
+                _ => self.encode(field.number, &field.kind, seed, path, depth, site),
+            };
+        }
+
+        let count = match keyword {
+            Some("repeated") => EXTREME_COUNTS[(seed % EXTREME_COUNTS.len() as u64) as usize],
+            _ if site.is_some() => 1 + (seed % 3) as usize,
+            _ if depth >= MAX_DEPTH => 0,
+            _ => (seed % 4) as usize,
+        };
+        // extreme counts are made up of the simplest elements
+        let depth = if count > 3 { MAX_DEPTH } else { depth };
+        let element_path = child_path(path, "0");
+        let values: Vec<(WireType, Vec<u8>)> = match &field.kind {
+            FieldType::Map(key, value) => {
+                let mut keys = vec![];
+                for idx in 0..count {
+                    let key = scalar_value(*key, derive(seed, idx as u64));
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+                if keyword == Some("map") {
+                    keys.push(keys[0].clone());
+                }
+                keys.into_iter()
+                    .enumerate()
+                    .map(|(idx, (wire_type, key))| {
+                        let mut entry = tagged(1, wire_type, key);
+                        let seed = derive(seed, 1000 + idx as u64);
+                        entry.extend(self.encode(2, value, seed, &element_path, depth, None));
+                        (WireType::LengthDelimited, entry)
+                    })
+                    .collect()
+            }
+            kind => (0..count)
+                .map(|idx| {
+                    let seed = derive(seed, idx as u64);
+                    let site = site.filter(|_| idx == 0);
+                    self.value(kind, seed, &element_path, depth + 1, site)
+                })
+                .collect(),
+        };
+
+        let wire_type = self.wire_type(&field.kind);
+        let mut encoded = vec![];
+        if keyword == Some("type") {
+            encoded.extend(mismatched(field.number, wire_type, seed));
+        }
+        if field.packed && packable(wire_type) && !values.is_empty() {
+            let payload: Vec<u8> = values.into_iter().flat_map(|(_, value)| value).collect();
+            encoded.extend(tagged(field.number, WireType::LengthDelimited, payload));
+        } else {
+            for (wire_type, value) in values {
+                encoded.extend(tagged(field.number, wire_type, value));
+            }
+        }
+        encoded
+    }
+
+    /// Encodes a single occurrence of a field
+    fn encode(
+        &self,
+        number: u64,
+        kind: &FieldType,
+        seed: u64,
+        path: &str,
+        depth: usize,
+        site: Option<&Violation>,
+    ) -> Vec<u8> {
+        let (wire_type, value) = self.value(kind, seed, path, depth + 1, site);
+        tagged(number, wire_type, value)
+    }
+
+    /// Encodes a value of the given type, which is either valid or violates
+    /// the constraint at `site`. Length-delimited values are output without
+    /// their length.
+    fn value(
+        &self,
+        kind: &FieldType,
+        seed: u64,
+        path: &str,
+        depth: usize,
+        site: Option<&Violation>,
+    ) -> (WireType, Vec<u8>) {
+        let keyword = site
+            .filter(|site| site.path == path || site.path == parent(path))
+            .map(|site| site.keyword);
+        match kind {
+            FieldType::Scalar(scalar) => match keyword.and_then(|_| out_of_range(*scalar)) {
+                Some(value) if keyword == Some("range") => (WireType::Varint, encode_varint(value)),
+                _ => scalar_value(*scalar, seed),
+            },
+            FieldType::Enum(name) => {
+                let values = self.enums.get(name).cloned().unwrap_or_default();
+                let value = match keyword {
+                    // one past the highest defined value
+                    Some("enum") => values.iter().max().map_or(1, |max| max + 1),
+                    _ if values.is_empty() => 0,
+                    _ => values[(seed % values.len() as u64) as usize],
+                };
+                (WireType::Varint, encode_varint(value as u64))
+            }
+            FieldType::Message(name) => (
+                WireType::LengthDelimited,
+                self.message(name, seed, path, depth, site),
+            ),
+            // map entries are encoded along with the map
+            FieldType::Map(..) => (WireType::LengthDelimited, vec![]),
+        }
+    }
+
+    fn wire_type(&self, kind: &FieldType) -> WireType {
+        match kind {
+            FieldType::Scalar(scalar) => scalar_value(*scalar, 0).0,
+            FieldType::Enum(_) => WireType::Varint,
+            FieldType::Message(_) | FieldType::Map(..) => WireType::LengthDelimited,
+        }
+    }
+}
+
+/// Derives the seed of a part of a message, so that its parts vary
+/// independently
+fn derive(seed: u64, idx: u64) -> u64 {
+    PRandomizer::new(seed ^ idx.rotate_left(32)).get()
+}
+
+/// Returns the path a value is an element of
+fn parent(path: &str) -> &str {
+    path.rfind('/').map_or("", |pos| &path[..pos])
+}
+
+/// Prepends the tag, as well as the length if it's length-delimited
+fn tagged(number: u64, wire_type: WireType, value: Vec<u8>) -> Vec<u8> {
+    let mut encoded = encode_tag(number, wire_type);
+    if wire_type == WireType::LengthDelimited {
+        encoded.extend(encode_varint(value.len() as u64));
+    }
+    encoded.extend(value);
+    encoded
+}
+
+/// Encodes a field with a value of another wire type than its own
+fn mismatched(number: u64, wire_type: WireType, seed: u64) -> Vec<u8> {
+    match wire_type {
+        WireType::LengthDelimited => tagged(number, WireType::Varint, encode_varint(seed % 128)),
+        WireType::Varint if seed.is_multiple_of(2) => {
+            tagged(number, WireType::Fixed64, seed.to_le_bytes().to_vec())
+        }
+        _ => tagged(number, WireType::LengthDelimited, b"talasum".to_vec()),
+    }
+}
+
+/// Returns whether values of the wire type may be packed
+fn packable(wire_type: WireType) -> bool {
+    matches!(
+        wire_type,
+        WireType::Varint | WireType::Fixed32 | WireType::Fixed64
+    )
+}
+
+/// Returns the value one past the range of a 32 bit scalar or a bool, as
+/// encoded in a varint
+fn out_of_range(scalar: Scalar) -> Option<u64> {
+    match scalar {
+        Scalar::Int32 => Some(i64::from(i32::MAX) as u64 + 1),
+        Scalar::Uint32 => Some(u64::from(u32::MAX) + 1),
+        Scalar::Sint32 => Some(zigzag(i64::from(i32::MIN) - 1)),
+        Scalar::Bool => Some(2),
+        _ => None,
+    }
+}
+
+/// Generates a number using the JSON number automaton, which is within
+/// `min` and `max`
+fn number(seed: u64, min: f64, max: f64) -> f64 {
+    let generated = NUMBER_AUTOMATON.generate(seed).parse::<f64>().ok();
+    match generated {
+        Some(num) if num.is_finite() && num >= min && num <= max => num,
+        _ => NUMBER_AUTOMATON
+            .init_value(seed % 1000)
+            .parse::<f64>()
+            .unwrap_or_default()
+            .clamp(min, max),
+    }
+}
+
+/// Generates an integer within the given bounds using the JSON number
+/// automaton
+fn integer(seed: u64, min: i64, max: u64) -> u64 {
+    let num = number(seed, min as f64, max as f64).trunc();
+    if num < 0.0 {
+        num as i64 as u64
+    } else {
+        (num as u64).min(max)
+    }
+}
+
+/// Generates a string using the JSON string automaton
+fn string(seed: u64) -> String {
+    serde_json::from_str::<String>(&STRING_AUTOMATON.generate(seed))
+        .or_else(|_| serde_json::from_str::<String>(&STRING_AUTOMATON.init_value(seed)))
+        .unwrap_or_default()
+}
+
+/// Encodes a valid value of a scalar type along with its wire type
+fn scalar_value(scalar: Scalar, seed: u64) -> (WireType, Vec<u8>) {
+    let signed = |max: i64| integer(seed, -max - 1, max as u64);
+    match scalar {
+        Scalar::Int32 => (WireType::Varint, encode_varint(signed(i32::MAX.into()))),
+        Scalar::Int64 => (WireType::Varint, encode_varint(signed(i64::MAX))),
+        Scalar::Uint32 => (
+            WireType::Varint,
+            encode_varint(integer(seed, 0, u32::MAX.into())),
+        ),
+        Scalar::Uint64 => (WireType::Varint, encode_varint(integer(seed, 0, u64::MAX))),
+        Scalar::Sint32 => (
+            WireType::Varint,
+            encode_varint(zigzag(signed(i32::MAX.into()) as i64)),
+        ),
+        Scalar::Sint64 => (
+            WireType::Varint,
+            encode_varint(zigzag(signed(i64::MAX) as i64)),
+        ),
+        Scalar::Bool => (
+            WireType::Varint,
+            encode_varint(u64::from(BOOL_AUTOMATON.init_value(seed) == "true")),
+        ),
+        Scalar::Fixed32 => (
+            WireType::Fixed32,
+            (integer(seed, 0, u32::MAX.into()) as u32)
+                .to_le_bytes()
+                .to_vec(),
+        ),
+        Scalar::Sfixed32 => (
+            WireType::Fixed32,
+            (signed(i32::MAX.into()) as i32).to_le_bytes().to_vec(),
+        ),
+        Scalar::Float => (
+            WireType::Fixed32,
+            (number(seed, f32::MIN.into(), f32::MAX.into()) as f32)
+                .to_le_bytes()
+                .to_vec(),
+        ),
+        Scalar::Fixed64 => (
+            WireType::Fixed64,
+            integer(seed, 0, u64::MAX).to_le_bytes().to_vec(),
+        ),
+        Scalar::Sfixed64 => (WireType::Fixed64, signed(i64::MAX).to_le_bytes().to_vec()),
+        Scalar::Double => (
+            WireType::Fixed64,
+            number(seed, f64::MIN, f64::MAX).to_le_bytes().to_vec(),
+        ),
+        Scalar::String => (WireType::LengthDelimited, string(seed).into_bytes()),
+        Scalar::Bytes => (WireType::LengthDelimited, string(seed).into_bytes()),
+    }
+}
@@ -0,0 +1,363 @@
+//! Generation of protobuf messages from `.proto` definitions.
+//!
+//! Messages either match their definition or violate exactly one of its
+//! constraints, see [`crate::schema::Violation`]. The paths of violations
+//! are made of field and oneof names, e.g. "/items/0/kind", and their
+//! keywords are:
+//! - `enum`: an enum field holds a value outside of the defined ones
+//! - `oneof`: two members of a oneof are set
+//! - `required`: a proto2 required field is missing
+//! - `map`: a map holds the same key twice
+//! - `repeated`: a repeated field holds an extreme number of elements
+//! - `range`: a 32 bit integer or a bool holds a value out of its range
+//! - `type`: a field holds a value of the wrong wire type
+//!
+//! Imports aren't followed, so all types have to be defined in one file.
+mod generate;
+mod parse;
+
+use crate::randomness::Randomizer;
+use crate::schema::Violation;
+use parse::ProtoFile;
+
+/// Levels of nested messages past which optional fields are no longer
+/// generated, so that recursive messages terminate
+const MAX_DEPTH: usize = 6;
+
+/// A generation-based fuzzer driven by `.proto` definitions.
+///
+/// It is a seedable iterator which alternates between messages matching
+/// their definition and messages violating exactly one of its constraints.
+/// Scalar values are generated by the JSON number and string automata. The
+/// violated constraint, if any, is available through
+/// [`ProtoGenerator::last_violation`].
+pub struct ProtoGenerator {
+    seeder: Box<dyn Randomizer>,
+    file: ProtoFile,
+    message: String,
+    grpc: bool,
+    last_violation: Option<Violation>,
+}
+
+impl ProtoGenerator {
+    /// Creates a ProtoGenerator instance producing `message`, which is
+    /// either its fully qualified name, e.g. "pkg.Outer.Inner", or an
+    /// unambiguous suffix of it, e.g. "Inner".
+    ///
+    /// Result will be [`std::option::Option::None`] if `proto` is not valid,
+    /// refers to types it doesn't define or doesn't define `message`.
+    pub(crate) fn new(proto: &str, message: &str, seeder: Box<dyn Randomizer>) -> Option<Self> {
+        let file = ProtoFile::parse(proto)?;
+        let suffix = format!(".{}", message);
+        let mut candidates = file
+            .messages
+            .keys()
+            .filter(|name| *name == message || name.ends_with(&suffix));
+        let message = match (candidates.next(), candidates.next()) {
+            (Some(name), None) => name.clone(),
+            _ if file.messages.contains_key(message) => String::from(message),
+            _ => return None,
+        };
+        Some(Self {
+            seeder,
+            file,
+            message,
+            grpc: false,
+            last_violation: None,
+        })
+    }
+
+    /// Wraps messages in the length-prefixed framing of gRPC, i.e. an
+    /// uncompressed flag followed by the big-endian length of the message
+    pub fn with_grpc_framing(mut self) -> Self {
+        self.grpc = true;
+        self
+    }
+
+    /// Returns the constraint violated by the last generated message, or
+    /// `None` if it matches its definition
+    pub fn last_violation(&self) -> Option<&Violation> {
+        self.last_violation.as_ref()
+    }
+
+    /// Generates a message and the constraint it violates, if any. Odd
+    /// seeds lead to a violation, unless the message cannot be violated.
+    fn generate(&self, seed: u64) -> (Vec<u8>, Option<Violation>) {
+        let file = &self.file;
+        let mut sites = vec![];
+        if !seed.is_multiple_of(2) {
+            file.sites(&self.message, "", 0, &mut sites);
+        }
+        let site = match sites.len() {
+            0 => None,
+            len => Some(sites.swap_remove((seed / 2 % len as u64) as usize)),
+        };
+        let message = file.message(&self.message, seed, "", 0, site.as_ref());
+        (message, site)
+    }
+}
+
+impl Iterator for ProtoGenerator {
+    type Item = Vec<u8>;
+
+    /// Computes a new message
+    fn next(&mut self) -> Option<Self::Item> {
+        let seed = self.seeder.get();
+        let (message, violation) = self.generate(seed);
+        self.last_violation = violation;
+        Some(if self.grpc {
+            grpc_frame(message)
+        } else {
+            message
+        })
+    }
+}
+
+/// Prepends the gRPC message prefix, without compression
+fn grpc_frame(message: Vec<u8>) -> Vec<u8> {
+    let mut framed = vec![0];
+    framed.extend((message.len() as u32).to_be_bytes());
+    framed.extend(message);
+    framed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse::{FieldType, Label, ProtoFile, Scalar};
+    use super::ProtoGenerator;
+    use crate::randomness::PRandomizer;
+    use crate::schema::Violation;
+    use crate::state_machine::binary::decode_varint;
+    use crate::state_machine::protobuf::{decode_tag, WireType};
+    use crate::tokenizer::binary::BinaryTokenizer;
+    use crate::tokenizer::protobuf::ProtobufTokenizer;
+
+    const PROTO2: &str = r#"
+        syntax = "proto2";
+        package shop;
+        import "google/protobuf/any.proto";
+
+        // an order placed by a customer
+        message Order {
+            required int32 id = 1;
+            optional Status status = 2 [default = PENDING];
+            repeated Item items = 3;
+            map<string, int64> quantities = 4;
+            oneof payment {
+                string card = 5;
+                bytes voucher = 6;
+            }
+            reserved 7, 8 to 10;
+
+            message Item {
+                required string name = 1;
+                optional uint32 count = 2;
+                repeated sint32 codes = 3 [packed = true];
+            }
+        }
+
+        enum Status {
+            PENDING = 0;
+            SHIPPED = 1;
+            DELIVERED = 0x2;
+        }
+    "#;
+
+    const PROTO3: &str = r#"
+        syntax = "proto3";
+        message Tree {
+            bool leaf = 1;
+            repeated Tree children = 2;
+            map<bool, .Tree> named = 3;
+        }
+    "#;
+
+    fn generated(seeds: std::ops::Range<u64>) -> Vec<(Vec<u8>, Option<Violation>)> {
+        seeds
+            .map(|seed| {
+                let mut generator =
+                    ProtoGenerator::new(PROTO2, "Order", Box::new(PRandomizer::new(seed))).unwrap();
+                let message = generator.next().unwrap();
+                (message, generator.last_violation().cloned())
+            })
+            .collect()
+    }
+
+    /// Decodes the top-level fields of a message into their numbers and
+    /// values, including the lengths of length-delimited ones
+    fn fields(mut message: &[u8]) -> Vec<(u64, WireType, Vec<u8>)> {
+        let mut fields = vec![];
+        while let Some((number, wire_type, size)) = decode_tag(message) {
+            let size = size
+                + match wire_type {
+                    WireType::Varint => decode_varint(&message[size..]).unwrap().1,
+                    WireType::Fixed64 => 8,
+                    WireType::Fixed32 => 4,
+                    _ => {
+                        let (length, prefix) = decode_varint(&message[size..]).unwrap();
+                        prefix + length as usize
+                    }
+                };
+            fields.push((number, wire_type, message[..size].to_vec()));
+            message = &message[size..];
+        }
+        fields
+    }
+
+    #[test]
+    fn definitions_are_parsed() {
+        let file = ProtoFile::parse(PROTO2).unwrap();
+        let order = &file.messages["shop.Order"];
+        assert_eq!(order.oneofs, vec!["payment"]);
+        assert_eq!(order.fields[0].label, Label::Required);
+        assert_eq!(
+            order.fields[1].kind,
+            FieldType::Enum(String::from("shop.Status"))
+        );
+        assert_eq!(
+            order.fields[2].kind,
+            FieldType::Message(String::from("shop.Order.Item"))
+        );
+        assert_eq!(
+            order.fields[3].kind,
+            FieldType::Map(Scalar::String, Box::new(FieldType::Scalar(Scalar::Int64)))
+        );
+        assert_eq!(order.fields[5].oneof, Some(0));
+        assert!(file.messages["shop.Order.Item"].fields[2].packed);
+        assert_eq!(file.enums["shop.Status"], vec![0, 1, 2]);
+
+        let tree = &ProtoFile::parse(PROTO3).unwrap().messages["Tree"];
+        assert_eq!(tree.fields[0].label, Label::Singular);
+        assert!(tree.fields[1].packed);
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let seeder = || Box::new(PRandomizer::new(1));
+        assert!(ProtoGenerator::new("message {", "A", seeder()).is_none());
+        assert!(ProtoGenerator::new("message A { B b = 1; }", "A", seeder()).is_none());
+        assert!(ProtoGenerator::new(PROTO2, "Missing", seeder()).is_none());
+        assert!(ProtoGenerator::new(PROTO2, "shop.Order.Item", seeder()).is_some());
+    }
+
+    #[test]
+    fn valid_messages_match_their_definition() {
+        let valid: Vec<Vec<u8>> = generated(0..100)
+            .into_iter()
+            .filter(|(_, violation)| violation.is_none())
+            .map(|(message, _)| message)
+            .collect();
+        assert!(valid.len() > 30);
+        for message in valid {
+            assert!(ProtobufTokenizer.tokenize(&message).is_some());
+            let fields = fields(&message);
+            assert_eq!(fields.iter().filter(|field| field.0 == 1).count(), 1);
+            assert!(
+                fields
+                    .iter()
+                    .filter(|field| [5, 6].contains(&field.0))
+                    .count()
+                    <= 1
+            );
+            for (number, _, value) in fields {
+                if number == 2 {
+                    assert!([0, 1, 2].contains(&value[1]), "{:?}", message);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn each_constraint_is_violated() {
+        let violated: Vec<&str> = generated(0..400)
+            .into_iter()
+            .filter_map(|(_, violation)| violation.map(|violation| violation.keyword))
+            .collect();
+        for keyword in [
+            "enum", "oneof", "required", "map", "repeated", "range", "type",
+        ] {
+            assert!(
+                violated.contains(&keyword),
+                "{} was never violated",
+                keyword
+            );
+        }
+    }
+
+    #[test]
+    fn constraints_are_violated_by_one() {
+        for (message, violation) in generated(0..400) {
+            let fields = fields(&message);
+            let count = |number| fields.iter().filter(|field| field.0 == number).count();
+            match violation {
+                Some(Violation {
+                    path,
+                    keyword: "required",
+                }) if path == "/id" => assert_eq!(count(1), 0),
+                Some(Violation {
+                    keyword: "oneof", ..
+                }) => assert_eq!(count(5) + count(6), 2),
+                Some(Violation {
+                    keyword: "enum", ..
+                }) => assert!(fields.contains(&(2, WireType::Varint, vec![0x10, 3]))),
+                Some(Violation {
+                    path,
+                    keyword: "repeated",
+                }) if path == "/items" => assert!(count(3) >= 1000),
+                Some(Violation { keyword: "map", .. }) => {
+                    let mut entries: Vec<Vec<u8>> = fields
+                        .iter()
+                        .filter(|field| field.0 == 4)
+                        .map(|field| fields_key(&field.2))
+                        .collect();
+                    let len = entries.len();
+                    entries.sort();
+                    entries.dedup();
+                    assert_eq!(entries.len() + 1, len);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the key of an encoded map entry field
+    fn fields_key(field: &[u8]) -> Vec<u8> {
+        let (length, prefix) = decode_varint(&field[1..]).unwrap();
+        let entry = &field[1 + prefix..1 + prefix + length as usize];
+        fields(entry).remove(0).2
+    }
+
+    #[test]
+    fn recursive_messages_terminate() {
+        let generator = ProtoGenerator::new(PROTO3, "Tree", Box::new(PRandomizer::new(3))).unwrap();
+        for message in generator.take(50) {
+            assert!(ProtobufTokenizer.tokenize(&message).is_some());
+        }
+    }
+
+    #[test]
+    fn messages_are_framed_for_grpc() {
+        let generator = ProtoGenerator::new(PROTO2, "Order", Box::new(PRandomizer::new(2)))
+            .unwrap()
+            .with_grpc_framing();
+        for framed in generator.take(20) {
+            assert_eq!(framed[0], 0);
+            let length = u32::from_be_bytes([framed[1], framed[2], framed[3], framed[4]]);
+            assert_eq!(length as usize, framed.len() - 5);
+        }
+    }
+
+    #[test]
+    fn generation_is_reproducible() {
+        let first: Vec<Vec<u8>> =
+            ProtoGenerator::new(PROTO2, "Order", Box::new(PRandomizer::new(7)))
+                .unwrap()
+                .take(10)
+                .collect();
+        let sec: Vec<Vec<u8>> = ProtoGenerator::new(PROTO2, "Order", Box::new(PRandomizer::new(7)))
+            .unwrap()
+            .take(10)
+            .collect();
+        assert_eq!(first, sec);
+    }
+}
@@ -0,0 +1,319 @@
+use pest::iterators::Pair;
+use pest::Parser;
+use std::collections::BTreeMap;
+
+#[derive(Parser)]
+#[grammar = "../resources/proto.pest"]
+struct ProtoParser;
+
+/// Types of scalar fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Self::Double,
+            "float" => Self::Float,
+            "int32" => Self::Int32,
+            "int64" => Self::Int64,
+            "uint32" => Self::Uint32,
+            "uint64" => Self::Uint64,
+            "sint32" => Self::Sint32,
+            "sint64" => Self::Sint64,
+            "fixed32" => Self::Fixed32,
+            "fixed64" => Self::Fixed64,
+            "sfixed32" => Self::Sfixed32,
+            "sfixed64" => Self::Sfixed64,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            "bytes" => Self::Bytes,
+            _ => return None,
+        })
+    }
+}
+
+/// Type of a field, with message and enum types referred to by their fully
+/// qualified names, e.g. "pkg.Outer.Inner"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum FieldType {
+    Scalar(Scalar),
+    Enum(String),
+    Message(String),
+    Map(Scalar, Box<FieldType>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Label {
+    /// A proto3 field without a label, which is omitted if it has the
+    /// default value
+    Singular,
+    Optional,
+    Required,
+    Repeated,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct FieldDef {
+    pub name: String,
+    pub number: u64,
+    pub label: Label,
+    pub kind: FieldType,
+    /// Index of the oneof the field is a member of, if any
+    pub oneof: Option<usize>,
+    pub packed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct MessageType {
+    pub fields: Vec<FieldDef>,
+    /// Names of the oneofs of the message
+    pub oneofs: Vec<String>,
+}
+
+/// The definitions of a .proto file, with nested types flattened
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct ProtoFile {
+    pub messages: BTreeMap<String, MessageType>,
+    /// Values of each enum, in order of definition
+    pub enums: BTreeMap<String, Vec<i64>>,
+}
+
+/// A field whose type is yet to be resolved, along with the scope it was
+/// defined in
+struct UnresolvedField {
+    message: String,
+    field: usize,
+    type_name: String,
+    key: Option<Scalar>,
+}
+
+impl ProtoFile {
+    /// Parses the definitions of a .proto file. Result will be
+    /// [`std::option::Option::None`] if it isn't valid or refers to types it
+    /// doesn't define, e.g. imported ones.
+    pub(super) fn parse(text: &str) -> Option<Self> {
+        let mut file = Self::default();
+        let mut unresolved = vec![];
+        let mut package = String::new();
+        let mut proto3 = false;
+        for pair in ProtoParser::parse(Rule::proto, text).ok()? {
+            match pair.as_rule() {
+                Rule::syntax => proto3 = !pair.as_str().contains("proto2"),
+                Rule::package => package = inner_str(pair, Rule::full_ident)?,
+                Rule::message | Rule::enum_def => {
+                    file.define(pair, &package, proto3, &mut unresolved)?
+                }
+                _ => {}
+            }
+        }
+
+        for UnresolvedField {
+            message,
+            field,
+            type_name,
+            key,
+        } in unresolved
+        {
+            let kind = file.resolve(&message, &type_name)?;
+            let field = &mut file.messages.get_mut(&message)?.fields[field];
+            field.kind = match key {
+                Some(key) => FieldType::Map(key, Box::new(kind)),
+                None => kind,
+            };
+        }
+        Some(file)
+    }
+
+    /// Adds a message or enum definition, along with those nested in it
+    fn define(
+        &mut self,
+        pair: Pair<Rule>,
+        scope: &str,
+        proto3: bool,
+        unresolved: &mut Vec<UnresolvedField>,
+    ) -> Option<()> {
+        let is_enum = pair.as_rule() == Rule::enum_def;
+        let mut inner = pair.into_inner();
+        let name = qualified(scope, inner.next()?.as_str());
+        if is_enum {
+            let values = inner
+                .filter(|pair| pair.as_rule() == Rule::enum_value)
+                .filter_map(|pair| parse_int(&inner_str(pair, Rule::enum_number)?))
+                .collect();
+            self.enums.insert(name, values);
+            return Some(());
+        }
+
+        let mut message = MessageType::default();
+        for pair in inner {
+            match pair.as_rule() {
+                Rule::message | Rule::enum_def => self.define(pair, &name, proto3, unresolved)?,
+                Rule::field | Rule::map_field => {
+                    let idx = message.fields.len();
+                    let field = field(pair, (&name, idx), proto3, None, unresolved)?;
+                    message.fields.push(field);
+                }
+                Rule::oneof => {
+                    let mut members = pair.into_inner();
+                    message.oneofs.push(String::from(members.next()?.as_str()));
+                    for member in members.filter(|pair| pair.as_rule() == Rule::field) {
+                        let oneof = Some(message.oneofs.len() - 1);
+                        let idx = message.fields.len();
+                        let mut member = field(member, (&name, idx), proto3, oneof, unresolved)?;
+                        member.label = Label::Optional;
+                        message.fields.push(member);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.messages.insert(name, message);
+        Some(())
+    }
+
+    /// Resolves a type name referred to from within `scope` as per the
+    /// protobuf scoping rules, i.e. starting from the innermost scope
+    fn resolve(&self, scope: &str, type_name: &str) -> Option<FieldType> {
+        if let Some(scalar) = Scalar::from_name(type_name) {
+            return Some(FieldType::Scalar(scalar));
+        }
+        let candidates: Vec<String> = match type_name.strip_prefix('.') {
+            Some(absolute) => vec![String::from(absolute)],
+            None => {
+                let mut scope = String::from(scope);
+                let mut candidates = vec![];
+                loop {
+                    candidates.push(qualified(&scope, type_name));
+                    match scope.rfind('.') {
+                        Some(pos) => scope.truncate(pos),
+                        None if scope.is_empty() => break,
+                        None => scope.clear(),
+                    }
+                }
+                candidates
+            }
+        };
+        candidates.into_iter().find_map(|name| {
+            if self.messages.contains_key(&name) {
+                Some(FieldType::Message(name))
+            } else if self.enums.contains_key(&name) {
+                Some(FieldType::Enum(name))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Converts a field or a map field at index `idx` of the `scope` message,
+/// recording its type to be resolved once all types are defined
+fn field(
+    pair: Pair<Rule>,
+    (scope, idx): (&str, usize),
+    proto3: bool,
+    oneof: Option<usize>,
+    unresolved: &mut Vec<UnresolvedField>,
+) -> Option<FieldDef> {
+    let is_map = pair.as_rule() == Rule::map_field;
+    let mut label = if proto3 {
+        Label::Singular
+    } else {
+        Label::Optional
+    };
+    let mut types = vec![];
+    let mut name = String::new();
+    let mut number = 0;
+    let mut packed = None;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::label => {
+                label = match inner.as_str() {
+                    "required" => Label::Required,
+                    "repeated" => Label::Repeated,
+                    _ => Label::Optional,
+                }
+            }
+            Rule::type_name => types.push(String::from(inner.as_str())),
+            Rule::ident => name = String::from(inner.as_str()),
+            Rule::field_number => number = parse_int(inner.as_str())? as u64,
+            Rule::field_option => {
+                let option = inner.as_str().replace(char::is_whitespace, "");
+                if let Some(value) = option.strip_prefix("packed=") {
+                    packed = Some(value == "true");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (key, type_name) = match (is_map, types.as_slice()) {
+        (true, [key, value]) => (Some(Scalar::from_name(key)?), value.clone()),
+        (false, [type_name]) => (None, type_name.clone()),
+        _ => return None,
+    };
+    if is_map {
+        label = Label::Repeated;
+    }
+    unresolved.push(UnresolvedField {
+        message: String::from(scope),
+        field: idx,
+        type_name,
+        key,
+    });
+    Some(FieldDef {
+        name,
+        number,
+        label,
+        kind: FieldType::Scalar(Scalar::Bytes),
+        oneof,
+        packed: packed.unwrap_or(proto3),
+    })
+}
+
+/// Returns the text of the first inner pair of the given rule
+fn inner_str(pair: Pair<Rule>, rule: Rule) -> Option<String> {
+    pair.into_inner()
+        .find(|inner| inner.as_rule() == rule)
+        .map(|inner| String::from(inner.as_str()))
+}
+
+fn qualified(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        String::from(name)
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+/// Parses a decimal or hexadecimal integer, possibly negative
+fn parse_int(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let value = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
@@ -0,0 +1,153 @@
+//! Fuzzing of protobuf messages in the wire format, which needs no schema.
+
+use crate::binary_mutator::BinaryMutator;
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::proto::ProtoGenerator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::protobuf::message::MESSAGE_AUTOMATON;
+use crate::state_machine::protobuf::scalar::VARINT_AUTOMATON;
+use crate::tokenizer::protobuf::ProtobufTokenizer;
+
+/// Returns a protobuf varint generator, which produces zig-zag extremes,
+/// sign-extended negative numbers and overlong encodings
+///
+/// # Examples
+/// ```rust
+/// use talasum::protobuf;
+///
+/// for fuzzed in protobuf::varint(1234).take(10) {
+///     println!("New varint: {:?}", fuzzed);
+/// }
+/// ```
+pub fn varint(seed: u64) -> Generator<Vec<u8>> {
+    Generator::<Vec<u8>>::new(&VARINT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a protobuf message generator, which produces messages with
+/// unknown or conflicting fields and deeply nested ones
+///
+/// # Examples
+/// ```rust
+/// use talasum::protobuf;
+///
+/// for fuzzed in protobuf::message(1234).take(10) {
+///     println!("New message: {:?}", fuzzed);
+/// }
+/// ```
+pub fn message(seed: u64) -> Generator<Vec<u8>> {
+    Generator::<Vec<u8>>::new(&MESSAGE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a protobuf Mutator, which decodes its input into fields without
+/// a schema and fuzzes their tags, values and lengths, as well as the
+/// messages they're in. The paths of the fields are their numbers, e.g.
+/// "/2/1" for field 1 of the message in field 2.
+///
+/// Lengths are re-encoded to match the values they precede unless they're
+/// fuzzed themselves, in which case they may be truncated or reach past the
+/// end of the message.
+///
+/// # Examples
+/// ```rust
+/// use talasum::protobuf;
+///
+/// match protobuf::mutate(&[0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i'], 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New message: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid protobuf message")
+/// }
+/// ```
+pub fn mutate(input: &[u8], seed: u64) -> Option<BinaryMutator<'_>> {
+    BinaryMutator::new(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        &ProtobufTokenizer,
+        Box::new(Config::new()),
+    )
+}
+
+/// Returns a generator of `message`, as defined in the `.proto` definitions
+/// of `proto`. Every other message matches its definition, the rest violate
+/// exactly one of its constraints, e.g. with an enum value outside of the
+/// defined ones. Messages can be framed for gRPC with
+/// [`ProtoGenerator::with_grpc_framing`].
+///
+/// Result will be [`std::option::Option::None`] if `proto` is not valid,
+/// refers to types it doesn't define or doesn't define `message`.
+///
+/// # Examples
+/// ```rust
+/// use talasum::protobuf;
+///
+/// let proto = r#"
+///     syntax = "proto3";
+///     enum Kind { A = 0; B = 1; }
+///     message Event { Kind kind = 1; repeated string tags = 2; }
+/// "#;
+/// match protobuf::from_proto(proto, "Event", 1234) {
+///     Some(generator) => {
+///         let mut generator = generator.with_grpc_framing();
+///         for _ in 0..10 {
+///             let fuzzed = generator.next().unwrap();
+///             println!("New message: {:?} violating {:?}", fuzzed, generator.last_violation());
+///         }
+///     },
+///     None => panic!("Your definitions did not define the message")
+/// }
+/// ```
+pub fn from_proto(proto: &str, message: &str, seed: u64) -> Option<ProtoGenerator> {
+    ProtoGenerator::new(proto, message, Box::new(PRandomizer::new(seed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+    use crate::selector::Selector;
+    use crate::tokenizer::binary::BinaryTokenizer;
+    use crate::tokenizer::protobuf::ProtobufTokenizer;
+
+    /// Field 1 is the varint 150 and field 2 a message with field 1 being
+    /// the string "hello"
+    const MESSAGE: &[u8] = &[
+        0x08, 0x96, 0x01, 0x12, 0x07, 0x0a, 0x05, b'h', b'e', b'l', b'l', b'o',
+    ];
+
+    #[test]
+    fn invalid_input_is_not_mutated() {
+        assert!(mutate(&[0x08], 1).is_none());
+    }
+
+    #[test]
+    fn excluded_fields_are_kept() {
+        let mutator = mutate(MESSAGE, 1)
+            .unwrap()
+            .with_excluded_paths(Selector::new("/2").unwrap());
+        for fuzzed in mutator.take(20) {
+            assert!(fuzzed.windows(9).any(|window| window == &MESSAGE[3..]));
+        }
+    }
+
+    #[test]
+    fn nested_lengths_are_reencoded() {
+        let fuzzed: Vec<Vec<u8>> = mutate(MESSAGE, 1)
+            .unwrap()
+            .with_included_paths(Selector::new("/2/1").unwrap())
+            .take(100)
+            .collect();
+        assert!(fuzzed.iter().any(|fuzzed| fuzzed.len() != MESSAGE.len()
+            && fuzzed.starts_with(&MESSAGE[..3])
+            && ProtobufTokenizer.tokenize(fuzzed).is_some()));
+    }
+
+    #[test]
+    fn messages_are_malformed() {
+        assert!(mutate(MESSAGE, 1)
+            .unwrap()
+            .take(100)
+            .any(|fuzzed| ProtobufTokenizer.tokenize(&fuzzed).is_none()));
+    }
+}
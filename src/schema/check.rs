@@ -0,0 +1,235 @@
+use super::{child_path, count, lower_bound, type_of, types, upper_bound, Schema, Violation};
+use serde_json::Value;
+
+impl Schema {
+    /// Returns the constraints of `schema` violated by `value`
+    pub(super) fn errors(&self, schema: &Value, value: &Value) -> Vec<Violation> {
+        let mut errors = vec![];
+        self.check(schema, value, "", &mut errors);
+        errors
+    }
+
+    /// Pushes the constraints of `schema` violated by the value at `path`
+    fn check(&self, schema: &Value, value: &Value, path: &str, errors: &mut Vec<Violation>) {
+        let mut error = |keyword: &'static str, path: String| {
+            errors.push(Violation { path, keyword });
+        };
+        let schema = self.resolve(schema);
+        if schema == &Value::Bool(false) {
+            return error("false", String::from(path));
+        }
+
+        let allowed = types(schema);
+        if !allowed.is_empty() && !allowed.iter().any(|name| has_type(value, name)) {
+            // the other keywords are meaningless for a value of the wrong type
+            return error("type", String::from(path));
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.contains(value) {
+                error("enum", String::from(path));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                error("const", String::from(path));
+            }
+        }
+
+        match value {
+            Value::String(string) => {
+                let len = string.chars().count();
+                if count(schema, "minLength").is_some_and(|min| len < min) {
+                    error("minLength", String::from(path));
+                }
+                if count(schema, "maxLength").is_some_and(|max| len > max) {
+                    error("maxLength", String::from(path));
+                }
+                let pattern = schema
+                    .get("pattern")
+                    .and_then(Value::as_str)
+                    .and_then(|pattern| regex::Regex::new(pattern).ok());
+                if pattern.is_some_and(|pattern| !pattern.is_match(string)) {
+                    error("pattern", String::from(path));
+                }
+            }
+            Value::Number(num) => {
+                let num = num.as_f64().unwrap_or_default();
+                match lower_bound(schema) {
+                    Some((bound, true)) if num <= bound => {
+                        error("exclusiveMinimum", String::from(path))
+                    }
+                    Some((bound, false)) if num < bound => error("minimum", String::from(path)),
+                    _ => {}
+                }
+                match upper_bound(schema) {
+                    Some((bound, true)) if num >= bound => {
+                        error("exclusiveMaximum", String::from(path))
+                    }
+                    Some((bound, false)) if num > bound => error("maximum", String::from(path)),
+                    _ => {}
+                }
+            }
+            Value::Array(elements) => {
+                if count(schema, "minItems").is_some_and(|min| elements.len() < min) {
+                    error("minItems", String::from(path));
+                }
+                if count(schema, "maxItems").is_some_and(|max| elements.len() > max) {
+                    error("maxItems", String::from(path));
+                }
+                for (idx, element) in elements.iter().enumerate() {
+                    if let Some(items) = items(schema, idx) {
+                        self.check(items, element, &child_path(path, &idx.to_string()), errors);
+                    }
+                }
+            }
+            Value::Object(members) => {
+                let required = schema.get("required").and_then(Value::as_array);
+                for key in required.into_iter().flatten().filter_map(Value::as_str) {
+                    if !members.contains_key(key) {
+                        error("required", child_path(path, key));
+                    }
+                }
+                for (key, member) in members {
+                    let member_path = child_path(path, key);
+                    match schema.get("properties").and_then(|props| props.get(key)) {
+                        Some(property) => self.check(property, member, &member_path, errors),
+                        None => match schema.get("additionalProperties") {
+                            Some(Value::Bool(false)) => errors.push(Violation {
+                                path: member_path,
+                                keyword: "additionalProperties",
+                            }),
+                            Some(additional) => {
+                                self.check(additional, member, &member_path, errors)
+                            }
+                            None => {}
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for branch in branches(schema, "allOf") {
+            self.check(branch, value, path, errors);
+        }
+        let matching = |keyword| {
+            branches(schema, keyword)
+                .filter(|branch| self.errors(branch, value).is_empty())
+                .count()
+        };
+        if schema.get("anyOf").is_some() && matching("anyOf") == 0 {
+            errors.push(Violation {
+                path: String::from(path),
+                keyword: "anyOf",
+            });
+        }
+        if schema.get("oneOf").is_some() && matching("oneOf") != 1 {
+            errors.push(Violation {
+                path: String::from(path),
+                keyword: "oneOf",
+            });
+        }
+    }
+}
+
+/// Returns whether a value is of a JSON Schema type, integers being numbers
+pub(super) fn has_type(value: &Value, name: &str) -> bool {
+    let actual = type_of(value);
+    actual == name || (name == "number" && actual == "integer")
+}
+
+/// Returns the schema of the `idx`th element of an array, `items` being
+/// either a single schema or a list of them
+pub(super) fn items(schema: &Value, idx: usize) -> Option<&Value> {
+    match schema.get("items") {
+        Some(Value::Array(items)) => items.get(idx),
+        items => items,
+    }
+}
+
+/// Iterates over the subschemas of a keyword such as `anyOf`
+pub(super) fn branches<'a>(schema: &'a Value, keyword: &str) -> impl Iterator<Item = &'a Value> {
+    schema
+        .get(keyword)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Schema, Violation};
+    use serde_json::json;
+
+    fn errors(schema: serde_json::Value, value: serde_json::Value) -> Vec<(String, &'static str)> {
+        Schema {
+            root: schema.clone(),
+        }
+        .errors(&schema, &value)
+        .into_iter()
+        .map(|Violation { path, keyword }| (path, keyword))
+        .collect()
+    }
+
+    #[test]
+    fn types_are_checked() {
+        assert!(errors(json!({"type": "number"}), json!(1)).is_empty());
+        assert!(errors(json!({"type": "integer"}), json!(1.0)).is_empty());
+        assert!(errors(json!({"type": ["string", "null"]}), json!(null)).is_empty());
+        assert_eq!(
+            errors(json!({"type": "integer", "minimum": 3}), json!(1.5)),
+            vec![(String::new(), "type")]
+        );
+    }
+
+    #[test]
+    fn keywords_only_apply_to_their_type() {
+        assert!(errors(json!({"maxLength": 1, "maxItems": 1}), json!(12)).is_empty());
+    }
+
+    #[test]
+    fn nested_violations_have_a_path() {
+        let schema = json!({
+            "properties": {"a/b": {"items": {"$ref": "#/$defs/short"}}},
+            "required": ["c"],
+            "additionalProperties": false,
+            "$defs": {"short": {"maxLength": 1}}
+        });
+        assert_eq!(
+            errors(schema, json!({"a/b": ["x", "yy"], "d": 1})),
+            vec![
+                (String::from("/c"), "required"),
+                (String::from("/a~1b/1"), "maxLength"),
+                (String::from("/d"), "additionalProperties")
+            ]
+        );
+    }
+
+    #[test]
+    fn bounds_can_be_exclusive() {
+        assert_eq!(
+            errors(json!({"exclusiveMinimum": 1}), json!(1)),
+            vec![(String::new(), "exclusiveMinimum")]
+        );
+        assert_eq!(
+            errors(json!({"maximum": 1, "exclusiveMaximum": true}), json!(1)),
+            vec![(String::new(), "exclusiveMaximum")]
+        );
+        assert!(errors(json!({"minimum": 1, "maximum": 1}), json!(1)).is_empty());
+    }
+
+    #[test]
+    fn branches_are_counted() {
+        let schema = json!({"oneOf": [{"type": "integer"}, {"type": "number"}]});
+        assert_eq!(
+            errors(schema.clone(), json!(1)),
+            vec![(String::new(), "oneOf")]
+        );
+        assert!(errors(schema, json!(1.5)).is_empty());
+        assert!(errors(
+            json!({"anyOf": [{"type": "integer"}, {"type": "number"}]}),
+            json!(1)
+        )
+        .is_empty());
+    }
+}
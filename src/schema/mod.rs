@@ -0,0 +1,327 @@
+//! Generation of JSON documents from a JSON Schema.
+//!
+//! Documents either satisfy the schema or violate exactly one of its
+//! constraints, see [`Violation`]. The supported keywords are `type`, `enum`,
+//! `const`, `minLength`, `maxLength`, `pattern`, `minimum`, `maximum`,
+//! `exclusiveMinimum`, `exclusiveMaximum`, `items`, `minItems`, `maxItems`,
+//! `properties`, `required`, `additionalProperties`, `anyOf`, `oneOf`,
+//! `allOf` and local `$ref`s. Other keywords are ignored.
+mod check;
+mod valid;
+mod violate;
+
+use crate::randomness::Randomizer;
+use crate::selector::child_path;
+use serde_json::Value;
+
+/// Max number of `$ref`s followed in a row
+const MAX_REFS: usize = 32;
+
+/// Levels of nested arrays and objects past which optional members and
+/// elements are no longer generated, so that recursive schemas terminate
+const MAX_DEPTH: usize = 8;
+
+/// Number of seeds tried when looking for a document with the expected
+/// number of violations
+const MAX_ATTEMPTS: u64 = 16;
+
+/// A schema constraint violated by a generated document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON Pointer to the value violating the constraint. For `required`
+    /// and `additionalProperties` it points to the missing or extra member.
+    pub path: String,
+    /// The schema keyword whose constraint is violated, e.g. `maxLength`
+    pub keyword: &'static str,
+}
+
+/// A generation-based fuzzer driven by a JSON Schema.
+///
+/// It is a seedable iterator which alternates between documents satisfying
+/// the schema and documents violating exactly one of its constraints, the
+/// latter focusing on boundaries, e.g. a string one character longer than
+/// `maxLength`. The violated constraint, if any, is available through
+/// [`SchemaGenerator::last_violation`].
+pub struct SchemaGenerator {
+    seeder: Box<dyn Randomizer>,
+    schema: Schema,
+    last_violation: Option<Violation>,
+}
+
+impl SchemaGenerator {
+    /// Creates a SchemaGenerator instance based on a JSON Schema document.
+    ///
+    /// Result will be [`std::option::Option::None`] if `schema` is not a
+    /// valid JSON object or boolean.
+    pub(crate) fn new(schema: &str, seeder: Box<dyn Randomizer>) -> Option<Self> {
+        match serde_json::from_str::<Value>(schema) {
+            Ok(root @ Value::Object(_)) | Ok(root @ Value::Bool(_)) => Some(Self {
+                seeder,
+                schema: Schema { root },
+                last_violation: None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the constraint violated by the last generated document, or
+    /// `None` if it satisfies the schema
+    pub fn last_violation(&self) -> Option<&Violation> {
+        self.last_violation.as_ref()
+    }
+
+    /// Generates a document and the constraint it violates, if any. Odd
+    /// seeds lead to a violation, unless the schema cannot be violated.
+    fn generate(&self, seed: u64) -> (Value, Option<Violation>) {
+        let schema = &self.schema;
+        let root = &schema.root;
+        let document = (0..MAX_ATTEMPTS)
+            .map(|attempt| schema.valid(root, seed.wrapping_add(attempt), 0))
+            .find(|document| schema.errors(root, document).is_empty())
+            .unwrap_or_else(|| schema.valid(root, seed, 0));
+        if seed.is_multiple_of(2) {
+            return (document, None);
+        }
+
+        let mut sites = vec![];
+        schema.sites(root, &document, "", &mut sites);
+        let first = (seed / 2 % sites.len().max(1) as u64) as usize;
+        sites.rotate_left(first);
+        for site in &sites {
+            for attempt in 0..MAX_ATTEMPTS {
+                let violated = schema.violate(&document, site, seed.wrapping_add(attempt));
+                if let Some((violating, violation)) = violated {
+                    if schema.errors(root, &violating) == [violation.clone()] {
+                        return (violating, Some(violation));
+                    }
+                }
+            }
+        }
+        (document, None)
+    }
+}
+
+impl Iterator for SchemaGenerator {
+    type Item = String;
+
+    /// Computes a new document
+    fn next(&mut self) -> Option<Self::Item> {
+        let seed = self.seeder.get();
+        let (document, violation) = self.generate(seed);
+        self.last_violation = violation;
+        Some(document.to_string())
+    }
+}
+
+/// A JSON Schema, which `$ref`s are resolved against
+struct Schema {
+    root: Value,
+}
+
+impl Schema {
+    /// Follows `$ref`s pointing within the schema, e.g. "#/$defs/a"
+    fn resolve<'a>(&'a self, mut schema: &'a Value) -> &'a Value {
+        for _ in 0..MAX_REFS {
+            match schema.get("$ref").and_then(Value::as_str) {
+                Some(reference) if reference.starts_with('#') => {
+                    match self.root.pointer(&reference[1..]) {
+                        Some(target) => schema = target,
+                        None => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+        schema
+    }
+}
+
+/// Returns the numeric value of a keyword
+fn number(schema: &Value, keyword: &str) -> Option<f64> {
+    schema.get(keyword).and_then(Value::as_f64)
+}
+
+/// Returns the value of a keyword which is a non-negative integer
+fn count(schema: &Value, keyword: &str) -> Option<usize> {
+    schema
+        .get(keyword)
+        .and_then(Value::as_u64)
+        .map(|count| count as usize)
+}
+
+/// Returns the (lower, exclusive) bound of numbers, supporting both the
+/// numeric `exclusiveMinimum` of recent drafts and the boolean one of draft 4
+fn lower_bound(schema: &Value) -> Option<(f64, bool)> {
+    match (number(schema, "minimum"), schema.get("exclusiveMinimum")) {
+        (_, Some(Value::Number(bound))) => bound.as_f64().map(|bound| (bound, true)),
+        (Some(bound), Some(Value::Bool(exclusive))) => Some((bound, *exclusive)),
+        (bound, _) => bound.map(|bound| (bound, false)),
+    }
+}
+
+/// Returns the (upper, exclusive) bound of numbers, see [`lower_bound`]
+fn upper_bound(schema: &Value) -> Option<(f64, bool)> {
+    match (number(schema, "maximum"), schema.get("exclusiveMaximum")) {
+        (_, Some(Value::Number(bound))) => bound.as_f64().map(|bound| (bound, true)),
+        (Some(bound), Some(Value::Bool(exclusive))) => Some((bound, *exclusive)),
+        (bound, _) => bound.map(|bound| (bound, false)),
+    }
+}
+
+/// Returns the types a schema allows. An empty list means any type.
+fn types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the name of the type of a value, "integer" for whole numbers
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(num) if num.is_i64() || num.is_u64() => "integer",
+        Value::Number(num) if num.as_f64().is_some_and(|num| num.fract() == 0.0) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SchemaGenerator, Violation};
+    use crate::randomness::PRandomizer;
+    use serde_json::Value;
+
+    const SCHEMA: &str = r##"{
+        "type": "object",
+        "properties": {
+            "name": {"type": "string", "minLength": 2, "maxLength": 8},
+            "kind": {"enum": ["a", "b"]},
+            "age": {"type": "integer", "minimum": 0, "exclusiveMaximum": 150},
+            "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}, "maxItems": 3},
+            "id": {"type": "string", "pattern": "^[A-Z]{3}-[0-9]{2,4}$"}
+        },
+        "required": ["name", "kind", "id"],
+        "additionalProperties": false,
+        "$defs": {"tag": {"type": "string", "maxLength": 4}}
+    }"##;
+
+    fn generated(seeds: std::ops::Range<u64>) -> Vec<(Value, Option<Violation>)> {
+        seeds
+            .map(|seed| {
+                let mut generator =
+                    SchemaGenerator::new(SCHEMA, Box::new(PRandomizer::new(seed))).unwrap();
+                let document = generator.next().unwrap();
+                (
+                    serde_json::from_str(&document).unwrap(),
+                    generator.last_violation().cloned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn invalid_schemas_are_rejected() {
+        assert!(SchemaGenerator::new("{", Box::new(PRandomizer::new(1))).is_none());
+        assert!(SchemaGenerator::new("1", Box::new(PRandomizer::new(1))).is_none());
+        assert!(SchemaGenerator::new("true", Box::new(PRandomizer::new(1))).is_some());
+    }
+
+    #[test]
+    fn valid_documents_satisfy_the_schema() {
+        let pattern = regex::Regex::new("^[A-Z]{3}-[0-9]{2,4}$").unwrap();
+        let valid: Vec<Value> = generated(0..100)
+            .into_iter()
+            .filter(|(_, violation)| violation.is_none())
+            .map(|(document, _)| document)
+            .collect();
+        assert!(valid.len() > 30);
+        for document in valid {
+            let name = document["name"].as_str().unwrap().chars().count();
+            assert!((2..=8).contains(&name), "{}", document);
+            assert!(["a", "b"].contains(&document["kind"].as_str().unwrap()));
+            assert!(
+                pattern.is_match(document["id"].as_str().unwrap()),
+                "{}",
+                document
+            );
+            if let Some(age) = document.get("age") {
+                assert!((0..150).contains(&age.as_i64().unwrap()), "{}", document);
+            }
+        }
+    }
+
+    #[test]
+    fn each_requested_constraint_is_violated() {
+        let violated: Vec<&str> = generated(0..400)
+            .into_iter()
+            .filter_map(|(_, violation)| violation.map(|violation| violation.keyword))
+            .collect();
+        for keyword in [
+            "minLength",
+            "maxLength",
+            "enum",
+            "required",
+            "additionalProperties",
+            "type",
+            "pattern",
+            "exclusiveMaximum",
+            "minimum",
+            "maxItems",
+        ] {
+            assert!(
+                violated.contains(&keyword),
+                "{} was never violated",
+                keyword
+            );
+        }
+    }
+
+    #[test]
+    fn boundaries_are_violated_by_one() {
+        for (document, violation) in generated(0..400) {
+            match violation {
+                Some(Violation {
+                    path,
+                    keyword: "maxLength",
+                }) if path == "/name" => {
+                    assert_eq!(document["name"].as_str().unwrap().chars().count(), 9)
+                }
+                Some(Violation {
+                    path,
+                    keyword: "minLength",
+                }) => {
+                    assert_eq!(document.pointer(&path).unwrap().as_str().unwrap().len(), 1)
+                }
+                Some(Violation {
+                    keyword: "exclusiveMaximum",
+                    ..
+                }) => {
+                    assert_eq!(document["age"], 150)
+                }
+                Some(Violation {
+                    keyword: "minimum", ..
+                }) => assert_eq!(document["age"], -1),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn generation_is_reproducible() {
+        let first: Vec<String> = SchemaGenerator::new(SCHEMA, Box::new(PRandomizer::new(7)))
+            .unwrap()
+            .take(10)
+            .collect();
+        let sec: Vec<String> = SchemaGenerator::new(SCHEMA, Box::new(PRandomizer::new(7)))
+            .unwrap()
+            .take(10)
+            .collect();
+        assert_eq!(first, sec);
+    }
+}
@@ -0,0 +1,392 @@
+use super::check::{branches, items};
+use super::{count, lower_bound, types, upper_bound, Schema, MAX_DEPTH, MAX_REFS};
+use crate::randomness::{PRandomizer, Randomizer};
+use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+use crate::state_machine::json::null::NULL_AUTOMATON;
+use crate::state_machine::json::number::NUMBER_AUTOMATON;
+use crate::state_machine::json::string::STRING_AUTOMATON;
+use crate::state_machine::Automaton;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+
+/// Types generated for schemas which don't restrict them
+const ANY_TYPES: [&str; 4] = ["null", "boolean", "integer", "string"];
+
+/// Max number of repetitions beyond the minimum of a pattern repetition
+const MAX_EXTRA_REPETITIONS: u64 = 4;
+
+impl Schema {
+    /// Generates a value which is meant to satisfy `schema`. It may not, e.g.
+    /// when a pattern conflicts with length constraints, so callers should
+    /// check the result.
+    pub(super) fn valid(&self, schema: &Value, seed: u64, depth: usize) -> Value {
+        if depth > 2 * MAX_DEPTH {
+            // only reachable through required recursive members
+            return Value::Null;
+        }
+        let schema = self.flatten(schema, seed, 0);
+        if let Some(constant) = schema.get("const") {
+            return constant.clone();
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.is_empty() {
+                return values[(seed % values.len() as u64) as usize].clone();
+            }
+        }
+
+        let allowed = inferred_types(&schema);
+        let allowed = if allowed.is_empty() {
+            ANY_TYPES.to_vec()
+        } else {
+            allowed
+        };
+        match allowed[(seed % allowed.len() as u64) as usize] {
+            "boolean" => generated(&BOOL_AUTOMATON, seed, Value::is_boolean),
+            "integer" => integer(&schema, seed),
+            "number" => number(&schema, seed),
+            "string" => Value::String(string(&schema, seed)),
+            "array" => Value::Array(self.array(&schema, seed, depth)),
+            "object" => Value::Object(self.object(&schema, seed, depth)),
+            _ => generated(&NULL_AUTOMATON, seed, Value::is_null),
+        }
+    }
+
+    /// Generates the elements of an array
+    fn array(&self, schema: &Value, seed: u64, depth: usize) -> Vec<Value> {
+        let min = count(schema, "minItems").unwrap_or_default();
+        let max = count(schema, "maxItems").unwrap_or(usize::MAX);
+        let len = match schema.get("items") {
+            Some(Value::Array(items)) => items.len(),
+            _ if depth >= MAX_DEPTH => min,
+            _ => min + (seed % 3) as usize,
+        };
+        (0..len.max(min).min(max))
+            .map(|idx| {
+                let seed = derive(seed, idx as u64);
+                match items(schema, idx) {
+                    Some(items) => self.valid(items, seed, depth + 1),
+                    None => self.valid(&Value::Bool(true), seed, MAX_DEPTH),
+                }
+            })
+            .collect()
+    }
+
+    /// Generates the members of an object: all the required ones and some of
+    /// the optional properties
+    fn object(&self, schema: &Value, seed: u64, depth: usize) -> Map<String, Value> {
+        let mut members = Map::new();
+        let required = schema.get("required").and_then(Value::as_array);
+        for (idx, key) in required.into_iter().flatten().enumerate() {
+            if let Some(key) = key.as_str() {
+                let property = property(schema, key);
+                let value = self.valid(&property, derive(seed, idx as u64), depth + 1);
+                members.insert(String::from(key), value);
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (idx, (key, property)) in properties.into_iter().flatten().enumerate() {
+            let seed = derive(seed, !(idx as u64));
+            if depth < MAX_DEPTH && seed.is_multiple_of(2) && !members.contains_key(key) {
+                let value = self.valid(property, seed / 2, depth + 1);
+                members.insert(key.clone(), value);
+            }
+        }
+        members
+    }
+
+    /// Merges `allOf` subschemas and a branch of `anyOf` and `oneOf` into a
+    /// single schema, with `$ref`s resolved
+    fn flatten(&self, schema: &Value, seed: u64, level: usize) -> Value {
+        let schema = self.resolve(schema);
+        let mut merged = match schema {
+            Value::Object(keywords) => keywords.clone(),
+            _ => return schema.clone(),
+        };
+        if level >= MAX_REFS {
+            return Value::Object(merged);
+        }
+        let mut subschemas: Vec<&Value> = branches(schema, "allOf").collect();
+        for keyword in ["anyOf", "oneOf"] {
+            let options: Vec<&Value> = branches(schema, keyword).collect();
+            if !options.is_empty() {
+                subschemas.push(options[(seed % options.len() as u64) as usize]);
+            }
+        }
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            merged.remove(keyword);
+        }
+        for (idx, subschema) in subschemas.into_iter().enumerate() {
+            if let Value::Object(keywords) =
+                self.flatten(subschema, derive(seed, idx as u64), level + 1)
+            {
+                merge(&mut merged, keywords);
+            }
+        }
+        Value::Object(merged)
+    }
+}
+
+/// Merges the keywords of a subschema into a schema, keeping the existing
+/// ones except for properties and required members which are combined
+fn merge(schema: &mut Map<String, Value>, subschema: Map<String, Value>) {
+    for (keyword, value) in subschema {
+        match (schema.get_mut(&keyword), value) {
+            (Some(Value::Object(properties)), Value::Object(more)) if keyword == "properties" => {
+                for (key, property) in more {
+                    properties.entry(key).or_insert(property);
+                }
+            }
+            (Some(Value::Array(required)), Value::Array(more)) if keyword == "required" => {
+                required.extend(more)
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                schema.insert(keyword, value);
+            }
+        }
+    }
+}
+
+/// Returns the schema of a member, from `properties` or
+/// `additionalProperties`
+pub(super) fn property(schema: &Value, key: &str) -> Value {
+    match schema.get("properties").and_then(|props| props.get(key)) {
+        Some(property) => property.clone(),
+        None => match schema.get("additionalProperties") {
+            Some(additional @ Value::Object(_)) => additional.clone(),
+            _ => Value::Bool(true),
+        },
+    }
+}
+
+/// Returns the types a schema allows, inferring them from its keywords when
+/// they aren't explicit
+fn inferred_types(schema: &Value) -> Vec<&str> {
+    let explicit = types(schema);
+    if !explicit.is_empty() {
+        return explicit;
+    }
+    let has = |keywords: &[&str]| keywords.iter().any(|key| schema.get(key).is_some());
+    if has(&["properties", "required", "additionalProperties"]) {
+        vec!["object"]
+    } else if has(&["items", "minItems", "maxItems"]) {
+        vec!["array"]
+    } else if has(&["minLength", "maxLength", "pattern"]) {
+        vec!["string"]
+    } else if has(&["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"]) {
+        vec!["number"]
+    } else {
+        vec![]
+    }
+}
+
+/// Derives the seed of a part of a value, e.g. an element of an array, from
+/// the seed of the whole value
+pub(super) fn derive(seed: u64, idx: u64) -> u64 {
+    PRandomizer::new(seed ^ idx.rotate_left(32)).get()
+}
+
+/// Generates a value with a JSON automaton, falling back to the unfuzzed
+/// initial value when the fuzzed one isn't valid JSON or doesn't `fit`
+pub(super) fn generated(
+    automaton: &Automaton<String>,
+    seed: u64,
+    fit: impl Fn(&Value) -> bool,
+) -> Value {
+    match serde_json::from_str(&automaton.generate(seed)) {
+        Ok(value) if fit(&value) => value,
+        _ => serde_json::from_str(&automaton.init_value(seed)).unwrap_or(Value::Null),
+    }
+}
+
+/// Generates an integer within the bounds of a schema, small if unbounded
+fn integer(schema: &Value, seed: u64) -> Value {
+    let lower = lower_bound(schema).map(|(bound, exclusive)| {
+        let min = bound.ceil();
+        if exclusive && min == bound {
+            min + 1.0
+        } else {
+            min
+        }
+    });
+    let upper = upper_bound(schema).map(|(bound, exclusive)| {
+        let max = bound.floor();
+        if exclusive && max == bound {
+            max - 1.0
+        } else {
+            max
+        }
+    });
+    // negative or fractional offsets would cross the bounds
+    let small = generated(&NUMBER_AUTOMATON, seed % 1000, |value| {
+        value
+            .as_f64()
+            .is_some_and(|num| num >= 0.0 && num.fract() == 0.0)
+    })
+    .as_f64()
+    .unwrap_or_default();
+    let generated = match (lower, upper) {
+        (Some(min), Some(max)) if max >= min => min + (small % (max - min + 1.0)).floor(),
+        (Some(min), _) => min + small,
+        (None, Some(max)) => max - small,
+        (None, None) => small,
+    };
+    Value::from(generated as i64)
+}
+
+/// Generates a number within the bounds of a schema, an integer if there is
+/// one within them
+fn number(schema: &Value, seed: u64) -> Value {
+    let generated = integer(schema, seed);
+    let num = generated.as_f64().unwrap_or_default();
+    let fits = |bound: Option<(f64, bool)>, above: bool| {
+        bound.is_none_or(|(bound, exclusive)| match (above, exclusive) {
+            (true, true) => num > bound,
+            (true, false) => num >= bound,
+            (false, true) => num < bound,
+            (false, false) => num <= bound,
+        })
+    };
+    if fits(lower_bound(schema), true) && fits(upper_bound(schema), false) {
+        return generated;
+    }
+    match (lower_bound(schema), upper_bound(schema)) {
+        (Some((min, _)), Some((max, _))) => Value::from((min + max) / 2.0),
+        _ => generated,
+    }
+}
+
+/// Generates a string matching the pattern of a schema if there is one, or a
+/// word otherwise, padded or truncated to fit its length constraints
+fn string(schema: &Value, seed: u64) -> String {
+    let pattern = schema.get("pattern").and_then(Value::as_str);
+    if let Some(hir) = pattern.and_then(|pattern| regex_syntax::Parser::new().parse(pattern).ok()) {
+        let mut generated = String::new();
+        matching(&hir, seed, &mut generated);
+        return generated;
+    }
+
+    let word = match generated(&STRING_AUTOMATON, seed, Value::is_string) {
+        Value::String(word) => word,
+        _ => String::new(),
+    };
+    let min = count(schema, "minLength").unwrap_or_default();
+    let max = count(schema, "maxLength").unwrap_or(usize::MAX);
+    let padding = word.chars().last().unwrap_or('a');
+    word.chars()
+        .chain(std::iter::repeat(padding))
+        .take(word.chars().count().max(min).min(max))
+        .collect()
+}
+
+/// Appends a string matching a regular expression to `generated`
+fn matching(hir: &Hir, seed: u64, generated: &mut String) {
+    match hir.kind() {
+        HirKind::Literal(literal) => generated.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges = class.ranges();
+            if !ranges.is_empty() {
+                let range = ranges[(seed % ranges.len() as u64) as usize];
+                let span = range.end() as u64 - range.start() as u64 + 1;
+                let code = range.start() as u64 + derive(seed, 0) % span;
+                generated.push(char::from_u32(code as u32).unwrap_or_else(|| range.start()));
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ranges = class.ranges();
+            if !ranges.is_empty() {
+                let range = ranges[(seed % ranges.len() as u64) as usize];
+                let span = range.end() as u64 - range.start() as u64 + 1;
+                generated.push((range.start() as u64 + derive(seed, 0) % span) as u8 as char);
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .map_or(u64::MAX, u64::from)
+                .min(u64::from(repetition.min) + MAX_EXTRA_REPETITIONS);
+            let times = u64::from(repetition.min) + seed % (max - u64::from(repetition.min) + 1);
+            for idx in 0..times {
+                matching(&repetition.sub, derive(seed, idx), generated);
+            }
+        }
+        HirKind::Capture(capture) => matching(&capture.sub, seed, generated),
+        HirKind::Concat(hirs) => {
+            for (idx, hir) in hirs.iter().enumerate() {
+                matching(hir, derive(seed, idx as u64), generated);
+            }
+        }
+        HirKind::Alternation(hirs) => {
+            let hir = &hirs[(seed % hirs.len() as u64) as usize];
+            matching(hir, derive(seed, 0), generated);
+        }
+        HirKind::Empty | HirKind::Look(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Schema;
+    use regex::Regex;
+    use serde_json::json;
+
+    fn valid(schema: serde_json::Value, seed: u64) -> serde_json::Value {
+        Schema {
+            root: schema.clone(),
+        }
+        .valid(&schema, seed, 0)
+    }
+
+    #[test]
+    fn patterns_are_matched() {
+        let pattern = "^(ab|c)[x-z]{2,}\\d?\\.$";
+        for seed in 0..100 {
+            let generated = valid(json!({ "pattern": pattern }), seed);
+            assert!(
+                Regex::new(pattern)
+                    .unwrap()
+                    .is_match(generated.as_str().unwrap()),
+                "{}",
+                generated
+            );
+        }
+    }
+
+    #[test]
+    fn numbers_are_within_bounds() {
+        for seed in 0..100 {
+            let generated = valid(
+                json!({"type": "integer", "minimum": -3, "maximum": 4}),
+                seed,
+            );
+            assert!((-3..=4).contains(&generated.as_i64().unwrap()));
+            let generated = valid(json!({"exclusiveMinimum": 0.1, "maximum": 0.2}), seed);
+            assert_eq!(generated.as_f64(), Some(0.15000000000000002));
+        }
+    }
+
+    #[test]
+    fn branches_are_merged() {
+        let schema = json!({
+            "allOf": [{"properties": {"a": {"const": 1}}, "required": ["a"]}],
+            "oneOf": [{"required": ["b"]}, {"required": ["c"]}]
+        });
+        for seed in 0..20 {
+            let generated = valid(schema.clone(), seed);
+            assert_eq!(generated["a"], 1);
+            assert!(generated.get("b").is_some() || generated.get("c").is_some());
+        }
+    }
+
+    #[test]
+    fn recursive_schemas_terminate() {
+        let schema = json!({
+            "properties": {"children": {"items": {"$ref": "#"}, "minItems": 1}},
+            "required": ["children"]
+        });
+        for seed in 0..10 {
+            let _ = valid(schema.clone(), seed);
+        }
+    }
+}
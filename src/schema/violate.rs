@@ -0,0 +1,315 @@
+use super::check::{has_type, items};
+use super::valid::{derive, generated, property};
+use super::{child_path, count, lower_bound, types, upper_bound, Schema, Violation, MAX_DEPTH};
+use crate::state_machine::json::{
+    array::ARRAY_AUTOMATON, boolean::BOOL_AUTOMATON, null::NULL_AUTOMATON,
+    number::NUMBER_AUTOMATON, object::OBJECT_AUTOMATON, string::STRING_AUTOMATON,
+};
+use crate::state_machine::Automaton;
+use serde_json::Value;
+
+/// Characters replacing one of a string so that it no longer matches a pattern
+const MISMATCHING_CHARS: &str = "!~ _aZ0\u{e9}\n";
+
+/// A constraint which a document could violate, along with the (sub)schema
+/// holding it
+pub(super) struct Site<'a> {
+    violation: Violation,
+    schema: &'a Value,
+}
+
+impl Schema {
+    /// Pushes the constraints of `schema` which could be violated by changing
+    /// the value at `path`, or one of its members or elements
+    pub(super) fn sites<'a>(
+        &'a self,
+        schema: &'a Value,
+        value: &Value,
+        path: &str,
+        sites: &mut Vec<Site<'a>>,
+    ) {
+        let schema = self.resolve(schema);
+        let mut site = |keyword: &'static str| {
+            sites.push(Site {
+                violation: Violation {
+                    path: String::from(path),
+                    keyword,
+                },
+                schema,
+            })
+        };
+        let has = |keyword| schema.get(keyword).is_some();
+
+        for keyword in ["type", "enum", "const"] {
+            if has(keyword) {
+                site(keyword);
+            }
+        }
+        match value {
+            Value::String(_) => {
+                if count(schema, "minLength").is_some_and(|min| min > 0) {
+                    site("minLength");
+                }
+                for keyword in ["maxLength", "pattern"] {
+                    if has(keyword) {
+                        site(keyword);
+                    }
+                }
+            }
+            Value::Number(_) => {
+                if let Some((_, exclusive)) = lower_bound(schema) {
+                    site(if exclusive {
+                        "exclusiveMinimum"
+                    } else {
+                        "minimum"
+                    });
+                }
+                if let Some((_, exclusive)) = upper_bound(schema) {
+                    site(if exclusive {
+                        "exclusiveMaximum"
+                    } else {
+                        "maximum"
+                    });
+                }
+            }
+            Value::Array(elements) => {
+                if count(schema, "minItems").is_some_and(|min| min > 0) {
+                    site("minItems");
+                }
+                if has("maxItems") {
+                    site("maxItems");
+                }
+                for (idx, element) in elements.iter().enumerate() {
+                    if let Some(items) = items(schema, idx) {
+                        self.sites(items, element, &child_path(path, &idx.to_string()), sites);
+                    }
+                }
+            }
+            Value::Object(members) => {
+                if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                    site("additionalProperties");
+                }
+                let required = schema.get("required").and_then(Value::as_array);
+                for key in required.into_iter().flatten().filter_map(Value::as_str) {
+                    sites.push(Site {
+                        violation: Violation {
+                            path: child_path(path, key),
+                            keyword: "required",
+                        },
+                        schema,
+                    });
+                }
+                for (key, member) in members {
+                    let property = schema
+                        .get("properties")
+                        .and_then(|props| props.get(key))
+                        .or_else(|| {
+                            schema
+                                .get("additionalProperties")
+                                .filter(|additional| additional.is_object())
+                        });
+                    if let Some(property) = property {
+                        self.sites(property, member, &child_path(path, key), sites);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Changes `document` so that it violates the constraint of a site,
+    /// returning the violating document and the violation. Other constraints
+    /// may be violated as well, so callers should check the result.
+    pub(super) fn violate(
+        &self,
+        document: &Value,
+        site: &Site,
+        seed: u64,
+    ) -> Option<(Value, Violation)> {
+        let mut document = document.clone();
+        let Site { violation, schema } = site;
+        let path = &violation.path;
+        let violation = match violation.keyword {
+            "required" => {
+                let (parent, key) = split_path(path);
+                document
+                    .pointer_mut(&parent)?
+                    .as_object_mut()?
+                    .remove(&key)?;
+                (*violation).clone()
+            }
+            "additionalProperties" => {
+                let members = document.pointer_mut(path)?.as_object_mut()?;
+                let key = (0..)
+                    .map(
+                        |idx| match generated(&STRING_AUTOMATON, seed, Value::is_string) {
+                            Value::String(word) if idx == 0 => word,
+                            _ => format!("extra{}", idx),
+                        },
+                    )
+                    .find(|key| {
+                        !members.contains_key(key)
+                            && schema
+                                .get("properties")
+                                .and_then(|props| props.get(key))
+                                .is_none()
+                    })?;
+                let value = self.valid(&property(schema, &key), seed, MAX_DEPTH);
+                members.insert(key.clone(), value);
+                Violation {
+                    path: child_path(path, &key),
+                    keyword: "additionalProperties",
+                }
+            }
+            keyword => {
+                let value = document.pointer_mut(path)?;
+                *value = self.violating(schema, keyword, value, seed)?;
+                (*violation).clone()
+            }
+        };
+        Some((document, violation))
+    }
+
+    /// Returns a value violating a constraint of `schema` on a value, as close
+    /// to the boundary as can be
+    fn violating(&self, schema: &Value, keyword: &str, value: &Value, seed: u64) -> Option<Value> {
+        let integers = types(schema).contains(&"integer");
+        match (keyword, value) {
+            ("type", _) => {
+                let mistyped =
+                    |other: &Value| !types(schema).iter().any(|name| has_type(other, name));
+                let automata: [&Automaton<String>; 6] = [
+                    &NULL_AUTOMATON,
+                    &BOOL_AUTOMATON,
+                    &NUMBER_AUTOMATON,
+                    &STRING_AUTOMATON,
+                    &ARRAY_AUTOMATON,
+                    &OBJECT_AUTOMATON,
+                ];
+                let others: Vec<Value> = automata
+                    .iter()
+                    .map(|automaton| generated(automaton, seed, mistyped))
+                    .filter(mistyped)
+                    .collect();
+                others
+                    .get((seed % others.len().max(1) as u64) as usize)
+                    .cloned()
+            }
+            ("enum", _) | ("const", _) => {
+                let mut unconstrained = schema.as_object()?.clone();
+                unconstrained.remove("enum");
+                unconstrained.remove("const");
+                let generated = self.valid(&Value::Object(unconstrained), seed, MAX_DEPTH);
+                let values = match schema.get("enum") {
+                    Some(Value::Array(values)) => values.clone(),
+                    _ => schema.get("const").cloned().into_iter().collect(),
+                };
+                if values.contains(&generated) {
+                    None
+                } else {
+                    Some(generated)
+                }
+            }
+            ("minLength", Value::String(string)) => {
+                let len = count(schema, "minLength")? - 1;
+                Some(Value::String(string.chars().cycle().take(len).collect()))
+            }
+            ("maxLength", Value::String(string)) => {
+                let len = count(schema, "maxLength")? + 1;
+                let padding = string.chars().last().unwrap_or('a');
+                let longer = string.chars().chain(std::iter::repeat(padding));
+                Some(Value::String(longer.take(len).collect()))
+            }
+            ("pattern", Value::String(string)) => {
+                let chars: Vec<char> = string.chars().collect();
+                let replacement = MISMATCHING_CHARS.chars().nth((seed % 9) as usize)?;
+                let mismatching: String = match chars.len() {
+                    0 => String::from(replacement),
+                    len => {
+                        let pos = (seed / 9 % len as u64) as usize;
+                        let mut chars = chars;
+                        chars[pos] = replacement;
+                        chars.into_iter().collect()
+                    }
+                };
+                Some(Value::String(mismatching))
+            }
+            ("minimum", _) | ("exclusiveMinimum", _) => {
+                let (bound, exclusive) = lower_bound(schema)?;
+                Some(match (exclusive, integers && bound.fract() == 0.0) {
+                    (true, _) => number(bound),
+                    (false, true) => number(bound - 1.0),
+                    (false, false) => number(bound.next_down()),
+                })
+            }
+            ("maximum", _) | ("exclusiveMaximum", _) => {
+                let (bound, exclusive) = upper_bound(schema)?;
+                Some(match (exclusive, integers && bound.fract() == 0.0) {
+                    (true, _) => number(bound),
+                    (false, true) => number(bound + 1.0),
+                    (false, false) => number(bound.next_up()),
+                })
+            }
+            ("minItems", Value::Array(elements)) => {
+                let len = count(schema, "minItems")? - 1;
+                Some(Value::Array(elements[..len.min(elements.len())].to_vec()))
+            }
+            ("maxItems", Value::Array(elements)) => {
+                let len = count(schema, "maxItems")? + 1;
+                let mut elements = elements.clone();
+                while elements.len() < len {
+                    let element = match items(schema, elements.len()) {
+                        Some(items) => {
+                            self.valid(items, derive(seed, elements.len() as u64), MAX_DEPTH)
+                        }
+                        None => Value::Null,
+                    };
+                    elements.push(element);
+                }
+                Some(Value::Array(elements))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns a JSON number, an integer if it has no fractional part
+fn number(num: f64) -> Value {
+    if num.fract() == 0.0 && num.abs() < i64::MAX as f64 {
+        Value::from(num as i64)
+    } else {
+        Value::from(num)
+    }
+}
+
+/// Splits a JSON Pointer into the pointer to the parent and the unescaped
+/// key of the last member
+fn split_path(path: &str) -> (String, String) {
+    match path.rfind('/') {
+        Some(pos) => (
+            String::from(&path[..pos]),
+            path[pos + 1..].replace("~1", "/").replace("~0", "~"),
+        ),
+        None => (String::new(), String::from(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number, split_path};
+
+    #[test]
+    fn paths_are_split() {
+        assert_eq!(
+            split_path("/a/b~1c"),
+            (String::from("/a"), String::from("b/c"))
+        );
+        assert_eq!(split_path("/a"), (String::new(), String::from("a")));
+    }
+
+    #[test]
+    fn whole_numbers_are_integers() {
+        assert!(number(-1.0).is_i64());
+        assert!(number(1.5).is_f64());
+    }
+}
@@ -0,0 +1,178 @@
+//! Selection of the parts of a document to be mutated.
+//!
+//! A [`Selector`] is a JSON Pointer, e.g. "/items/0/price", which may contain
+//! glob-style wildcards:
+//! - `*` matches any (part of a) single segment, e.g. "/items/*/price" or
+//!   "/*_token"
+//! - `**` as a whole segment matches any number of segments, e.g.
+//!   "/**/signature"
+//!
+//! A selector matches the value it points to along with all of its members
+//! and elements.
+
+/// A JSON Pointer pattern, see the [module](self) docs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    segments: Vec<String>,
+}
+
+impl Selector {
+    /// Creates a Selector out of a JSON Pointer pattern.
+    ///
+    /// Result will be [`std::option::Option::None`] if `pattern` is neither
+    /// empty, which selects the whole document, nor starts with a '/'.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use talasum::selector::Selector;
+    ///
+    /// let selector = Selector::new("/items/*/price").unwrap();
+    /// assert!(selector.matches("/items/3/price"));
+    /// assert!(!selector.matches("/items/3/name"));
+    /// ```
+    pub fn new(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() {
+            return Some(Self { segments: vec![] });
+        }
+        pattern.strip_prefix('/').map(|pattern| Self {
+            segments: pattern.split('/').map(unescape).collect(),
+        })
+    }
+
+    /// Returns whether the value at `path`, a JSON Pointer, is selected, i.e.
+    /// it or one of the values it is nested in is pointed to by the pattern
+    pub fn matches(&self, path: &str) -> bool {
+        let segments: Vec<String> = match path.strip_prefix('/') {
+            Some(path) => path.split('/').map(unescape).collect(),
+            None => vec![],
+        };
+        matches_prefix(&self.segments, &segments)
+    }
+}
+
+/// Returns whether `pattern` matches the first segments of `segments`
+fn matches_prefix(pattern: &[String], segments: &[String]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((first, rest)) if first == "**" => {
+            (0..=segments.len()).any(|skipped| matches_prefix(rest, &segments[skipped..]))
+        }
+        Some((first, rest)) => match segments.split_first() {
+            Some((segment, segments)) => {
+                matches_glob(first, segment) && matches_prefix(rest, segments)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Returns whether a segment matches a pattern where `*` stands for any
+/// number of characters
+fn matches_glob(pattern: &str, segment: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == segment,
+        Some((prefix, rest)) => match segment.strip_prefix(prefix) {
+            Some(segment) => segment
+                .char_indices()
+                .map(|(idx, _)| idx)
+                .chain(std::iter::once(segment.len()))
+                .any(|idx| matches_glob(rest, &segment[idx..])),
+            None => false,
+        },
+    }
+}
+
+/// Outputs the indices of the parts of a document, given by their `(from,
+/// to, path)` spans, which can be chosen for mutation: the ones matched by
+/// any of the `included` selectors, or all of them if there are none, unless
+/// they overlap a part matched by any of the `excluded` ones
+pub(crate) fn select(
+    parts: &[(usize, usize, &str)],
+    included: &[Selector],
+    excluded: &[Selector],
+) -> Vec<usize> {
+    let locked: Vec<(usize, usize)> = parts
+        .iter()
+        .filter(|(_, _, path)| excluded.iter().any(|excluded| excluded.matches(path)))
+        .map(|&(from, to, _)| (from, to))
+        .collect();
+    parts
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, path))| {
+            included.is_empty() || included.iter().any(|included| included.matches(path))
+        })
+        .filter(|(_, &(from, to, _))| {
+            !locked
+                .iter()
+                .any(|&(locked_from, locked_to)| from < locked_to && locked_from < to)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Appends a member or element to a JSON Pointer, escaping it
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Unescapes a segment of a JSON Pointer
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{child_path, select, Selector};
+
+    #[test]
+    fn pointers_are_matched_with_their_descendants() {
+        let selector = Selector::new("/a/0").unwrap();
+        assert!(selector.matches("/a/0"));
+        assert!(selector.matches("/a/0/b"));
+        assert!(!selector.matches("/a"));
+        assert!(!selector.matches("/a/01"));
+        assert!(Selector::new("").unwrap().matches(""));
+    }
+
+    #[test]
+    fn excluded_parts_and_their_parents_are_not_selected() {
+        let parts = [(0, 10, ""), (1, 4, "/a"), (5, 9, "/b"), (6, 7, "/b/0")];
+        let excluded = [Selector::new("/b/0").unwrap()];
+        assert_eq!(select(&parts, &[], &excluded), vec![1]);
+        let included = [Selector::new("/b").unwrap()];
+        assert_eq!(select(&parts, &included, &[]), vec![2, 3]);
+    }
+
+    #[test]
+    fn pointers_must_be_absolute() {
+        assert_eq!(Selector::new("a/b"), None);
+    }
+
+    #[test]
+    fn wildcards_match_segments() {
+        let selector = Selector::new("/items/*/price").unwrap();
+        assert!(selector.matches("/items/12/price"));
+        assert!(!selector.matches("/items/price"));
+
+        let selector = Selector::new("/*_token").unwrap();
+        assert!(selector.matches("/auth_token"));
+        assert!(selector.matches("/_token"));
+        assert!(!selector.matches("/token"));
+    }
+
+    #[test]
+    fn double_wildcards_match_any_number_of_segments() {
+        let selector = Selector::new("/**/id").unwrap();
+        assert!(selector.matches("/id"));
+        assert!(selector.matches("/a/0/id"));
+        assert!(!selector.matches("/a/0/ids"));
+    }
+
+    #[test]
+    fn segments_are_escaped() {
+        let path = child_path(&child_path("", "a/b"), "~c");
+        assert_eq!(path, "/a~1b/~0c");
+        assert!(Selector::new("/a~1b").unwrap().matches(&path));
+    }
+}
@@ -0,0 +1,319 @@
+use super::bytes::insert_bytes;
+use super::helper::FINAL_BYTES;
+use super::{Automaton, AutomatonNode};
+
+lazy_static! {
+    // fixed-width integers, whose byte order isn't known to the automaton
+    pub static ref START_INT: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &BOUNDARY_INT),
+            (2, &INCREMENT_INT),
+            (2, &DECREMENT_INT),
+            (1, &SWAP_BYTES),
+            (1, &FLIP_BIT),
+            (1, &RESIZE_INT),
+        ]);
+    static ref BOUNDARY_INT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| boundary_int(seed, bytes.len()));
+    static ref INCREMENT_INT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| add_one(bytes, seed.is_multiple_of(2), false));
+    static ref DECREMENT_INT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| add_one(bytes, seed.is_multiple_of(2), true));
+    // the other byte order, as if sender and receiver disagreed on it
+    static ref SWAP_BYTES: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|_, bytes| bytes.into_iter().rev().collect());
+    static ref RESIZE_INT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, mut bytes| {
+            if seed.is_multiple_of(2) {
+                bytes.pop();
+            } else {
+                bytes.push(0);
+            }
+            bytes
+        });
+    static ref FLIP_BIT: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_func(flip_bit);
+    pub static ref INT_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "binary::int",
+        initial_node: &START_INT,
+        generator: |seed| seed.to_be_bytes().to_vec(),
+    };
+
+    // base 128 varints, as used by protobuf
+    pub static ref START_VARINT: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &BOUNDARY_VARINT),
+            (2, &SHIFT_VARINT),
+            (2, &OVERLONG_VARINT),
+            (1, &UNTERMINATED_VARINT),
+            (1, &OVERFLOWING_VARINT),
+        ]);
+    static ref BOUNDARY_VARINT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| encode_varint(VARINT_BOUNDARIES[(seed % VARINT_BOUNDARIES.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL_BYTES), (1, &OVERLONG_VARINT)]);
+    static ref SHIFT_VARINT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| match decode_varint(&bytes) {
+            Some((value, _)) if seed.is_multiple_of(2) => encode_varint(value.wrapping_add(1)),
+            Some((value, _)) => encode_varint(value.wrapping_sub(1)),
+            None => bytes,
+        });
+    // the same value with redundant zero groups, which some decoders reject
+    pub static ref OVERLONG_VARINT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, mut bytes| {
+            for _ in 0..1 + seed % 4 {
+                if let Some(last) = bytes.last_mut() {
+                    *last |= 0x80;
+                }
+                bytes.push(0);
+            }
+            bytes
+        });
+    static ref UNTERMINATED_VARINT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|_, mut bytes| {
+            match bytes.last_mut() {
+                Some(last) => *last |= 0x80,
+                None => bytes.push(0x80),
+            }
+            bytes
+        });
+    // more than 64 bits of value, in up to 11 bytes
+    static ref OVERFLOWING_VARINT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| {
+            let mut bytes = vec![0xff; 9 + (seed % 2) as usize];
+            bytes.push([0x02, 0x7f, 0x01][(seed % 3) as usize]);
+            bytes
+        });
+    pub static ref VARINT_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "binary::varint",
+        initial_node: &START_VARINT,
+        generator: encode_varint,
+    };
+
+    // flags and other values packed into bits
+    static ref START_BITFIELD: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &FLIP_BIT),
+            (1, &SET_ALL_BITS),
+            (1, &CLEAR_ALL_BITS),
+            (1, &INVERT_BITS),
+        ]);
+    static ref SET_ALL_BITS: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|_, bytes| vec![0xff; bytes.len()]);
+    static ref CLEAR_ALL_BITS: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|_, bytes| vec![0; bytes.len()]);
+    static ref INVERT_BITS: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|_, bytes| bytes.into_iter().map(|byte| !byte).collect());
+    pub static ref BITFIELD_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "binary::bitfield",
+        initial_node: &START_BITFIELD,
+        generator: |seed| vec![seed as u8],
+    };
+
+    // opaque byte strings
+    pub static ref START_BLOB: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (2, &TRUNCATE),
+            (2, &EXTEND_BLOB),
+            (2, &FLIP_BIT),
+            (1, &INSERT_MAGIC_BYTES),
+            (1, &EMPTY_BLOB),
+        ]);
+    pub static ref TRUNCATE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, mut bytes| {
+            bytes.truncate((seed % (bytes.len() as u64 + 1)) as usize);
+            bytes
+        });
+    static ref EXTEND_BLOB: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, mut bytes| {
+            let length = [1, 255, 256, 65536][(seed % 4) as usize];
+            bytes.extend((0..length).map(|i| (seed >> (i % 8)) as u8));
+            bytes
+        });
+    static ref INSERT_MAGIC_BYTES: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, MAGIC_BYTES[(seed % MAGIC_BYTES.len() as u64) as usize]));
+    static ref EMPTY_BLOB: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_func(|_, _| vec![]);
+    pub static ref BLOB_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "binary::blob",
+        initial_node: &START_BLOB,
+        generator: |seed| seed.to_le_bytes().to_vec(),
+    };
+
+    // whole records, e.g. a TLV entry or a struct, along with what's in them
+    static ref START_RECORD: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &DUPLICATE_RECORD),
+            (2, &TRUNCATE),
+            (1, &EMPTY_BLOB),
+            (1, &APPEND_GARBAGE),
+        ]);
+    static ref DUPLICATE_RECORD: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| bytes.repeat(2 + (seed % 3) as usize));
+    static ref APPEND_GARBAGE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, mut bytes| {
+            bytes.extend_from_slice(MAGIC_BYTES[(seed % MAGIC_BYTES.len() as u64) as usize]);
+            bytes
+        });
+    pub static ref RECORD_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "binary::record",
+        initial_node: &START_RECORD,
+        generator: |seed| seed.to_le_bytes().to_vec(),
+    };
+}
+
+/// Values around the limits of signed and unsigned 32 and 64 bit integers
+const VARINT_BOUNDARIES: [u64; 10] = [
+    0,
+    1,
+    127,
+    128,
+    0x7fff_ffff,
+    0x8000_0000,
+    0xffff_ffff,
+    0x1_0000_0000,
+    0x8000_0000_0000_0000,
+    u64::MAX,
+];
+
+/// Byte sequences which are special to many parsers: NULs, maximal values,
+/// format strings and common magic numbers
+const MAGIC_BYTES: [&[u8]; 6] = [
+    &[0],
+    &[0xff, 0xff, 0xff, 0xff],
+    &[0x7f, 0xff, 0xff, 0xff],
+    b"%n%s",
+    &[0x1f, 0x8b],
+    &[0xef, 0xbb, 0xbf],
+];
+
+/// Encodes `value` as a base 128 varint
+pub fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
+
+/// Decodes a base 128 varint at the start of `bytes`. Outputs the value along
+/// with the number of bytes it takes, or None if it's unterminated or has
+/// more than 64 bits.
+pub fn decode_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0_u64;
+    for (idx, byte) in bytes.iter().enumerate().take(10) {
+        let group = u64::from(byte & 0x7f);
+        if idx == 9 && group > 1 {
+            return None;
+        }
+        value |= group << (7 * idx);
+        if byte & 0x80 == 0 {
+            return Some((value, idx + 1));
+        }
+    }
+    None
+}
+
+/// Picks a boundary value of an integer `width` bytes wide, in either byte
+/// order
+fn boundary_int(seed: u64, width: usize) -> Vec<u8> {
+    let mut bytes = match seed % 5 {
+        0 => vec![0; width],
+        1 => vec![0xff; width],
+        2 => [vec![0x7f], vec![0xff; width.saturating_sub(1)]].concat(),
+        3 => [vec![0x80], vec![0; width.saturating_sub(1)]].concat(),
+        _ => [vec![0; width.saturating_sub(1)], vec![1]].concat(),
+    };
+    bytes.truncate(width);
+    if (seed / 5).is_multiple_of(2) {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// Adds or subtracts one, wrapping around, treating `bytes` as a big endian
+/// integer or a little endian one
+fn add_one(mut bytes: Vec<u8>, big_endian: bool, subtract: bool) -> Vec<u8> {
+    let (from, overflow) = if subtract { (0, 0xff) } else { (0xff, 0) };
+    let order: Vec<usize> = if big_endian {
+        (0..bytes.len()).rev().collect()
+    } else {
+        (0..bytes.len()).collect()
+    };
+    for idx in order {
+        if bytes[idx] == from {
+            bytes[idx] = overflow;
+        } else {
+            bytes[idx] = if subtract {
+                bytes[idx] - 1
+            } else {
+                bytes[idx] + 1
+            };
+            break;
+        }
+    }
+    bytes
+}
+
+fn flip_bit(seed: u64, mut bytes: Vec<u8>) -> Vec<u8> {
+    if !bytes.is_empty() {
+        let idx = (seed % bytes.len() as u64) as usize;
+        bytes[idx] ^= 1 << (seed / 8 % 8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn varints_are_encoded_and_decoded() {
+        assert_eq!(encode_varint(1), vec![1]);
+        assert_eq!(encode_varint(300), vec![0xac, 0x02]);
+        assert_eq!(decode_varint(&[0xac, 0x02, 0xff]), Some((300, 2)));
+        assert_eq!(
+            decode_varint(&encode_varint(u64::MAX)),
+            Some((u64::MAX, 10))
+        );
+        assert_eq!(decode_varint(&[0x80]), None);
+        assert_eq!(
+            decode_varint(
+                &[0xff; 9]
+                    .iter()
+                    .chain(&[0x02])
+                    .copied()
+                    .collect::<Vec<u8>>()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn ints_wrap_around() {
+        assert_eq!(add_one(vec![0x00, 0xff], true, false), vec![0x01, 0x00]);
+        assert_eq!(add_one(vec![0x00, 0xff], false, false), vec![0x01, 0xff]);
+        assert_eq!(add_one(vec![0x00, 0x00], true, true), vec![0xff, 0xff]);
+    }
+
+    #[test]
+    fn int_boundaries_keep_their_width() {
+        assert!((0..20).all(|seed| boundary_int(seed, 4).len() == 4));
+        assert!((0..20).any(|seed| boundary_int(seed, 2) == vec![0xff, 0x7f]));
+        assert!((0..20).any(|seed| boundary_int(seed, 2) == vec![0x7f, 0xff]));
+    }
+
+    #[test]
+    fn varints_are_fuzzed() {
+        let fuzzed: Vec<Vec<u8>> = (1..200)
+            .map(|i| VARINT_AUTOMATON.traverse(vec![0x05], i))
+            .sorted()
+            .dedup()
+            .collect();
+        assert!(fuzzed.iter().any(|bytes| bytes == &vec![0x85, 0x00]));
+        assert!(fuzzed.iter().any(|bytes| decode_varint(bytes).is_none()));
+        assert!(fuzzed.iter().any(|bytes| bytes == &vec![0x06]));
+    }
+}
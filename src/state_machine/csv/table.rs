@@ -0,0 +1,186 @@
+use super::super::helper::{pick, FINAL};
+use super::{delimiter, size, split_fields, split_records, DELIMITERS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &PREPEND_BOM),
+            (2, &CHANGE_LINE_BREAKS),
+            (2, &CHANGE_DELIMITER),
+            (3, &RAGGED_RECORD),
+            (1, &WIDE_RECORD),
+            (2, &DUPLICATE_RECORD),
+        ]);
+    // byte order marks, which end up in the name of the first column unless
+    // they're stripped
+    static ref PREPEND_BOM: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| format!("{}{}", pick(seed, &["\u{feff}", "\u{feff}\u{feff}", "\u{fffe}"]), table))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    // line breaks of another platform, for all records or only some of them
+    static ref CHANGE_LINE_BREAKS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let line_break = pick(seed, &["\r\n", "\n", "\r"]);
+            let records = split_records(&table);
+            let changed = (seed / 3) as usize % records.len().max(1);
+            records
+                .iter()
+                .enumerate()
+                .map(|(idx, record)| {
+                    let content = record.trim_end_matches(['\r', '\n']);
+                    match ((seed / 3).is_multiple_of(2) || idx == changed, content.len() < record.len()) {
+                        (true, true) => format!("{}{}", content, line_break),
+                        _ => String::from(*record),
+                    }
+                })
+                .collect()
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    // delimiters of another dialect, for all records or only some of them
+    static ref CHANGE_DELIMITER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let delimiter = delimiter(&table);
+            let other = DELIMITERS[(seed % DELIMITERS.len() as u64) as usize];
+            let records = split_records(&table);
+            let changed = (seed / 4) as usize % records.len().max(1);
+            records
+                .iter()
+                .enumerate()
+                .map(|(idx, record)| {
+                    let (fields, line_break) = split_fields(record, delimiter);
+                    if (seed / 4).is_multiple_of(2) || idx == changed {
+                        format!("{}{}", fields.join(&other.to_string()), line_break)
+                    } else {
+                        String::from(*record)
+                    }
+                })
+                .collect()
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    // records with fewer or more fields than the header
+    static ref RAGGED_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let delimiter = delimiter(&table);
+            change_record(seed, &table, delimiter, |seed, fields| match seed % 3 {
+                0 => {
+                    fields.pop();
+                }
+                1 => fields.truncate(1),
+                _ => fields.extend(["x", ""]),
+            })
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    static ref WIDE_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let delimiter = delimiter(&table);
+            let width = size(seed / 2) / 10;
+            change_record(seed, &table, delimiter, |seed, fields| {
+                let field = if seed.is_multiple_of(2) { "" } else { "x" };
+                fields.extend(std::iter::repeat_n(field, width));
+            })
+        });
+    // duplicated headers, which importers may take for data, and duplicated
+    // records, which they may import twice
+    static ref DUPLICATE_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let mut records: Vec<String> = split_records(&table).iter().map(|record| String::from(*record)).collect();
+            if let Some(last) = records.last_mut() {
+                if !last.ends_with(['\r', '\n']) {
+                    last.push_str("\r\n");
+                }
+            }
+            let idx = match seed % 2 {
+                0 => 0,
+                _ => (seed / 2) as usize % records.len().max(1),
+            };
+            if let Some(record) = records.get(idx).cloned() {
+                records.insert(idx, record);
+            }
+            records.concat()
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    static ref MORE_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref TABLE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "csv::table",
+        initial_node: &START_TABLE,
+        generator: |seed| String::from(pick(seed, &["id,name\r\n1,a\r\n2,b\r\n", "id;name\n1;\"a;b\"\n", "a\tb\n1\t2"])),
+    };
+}
+
+/// Changes the fields of one of the records of a table, the header being
+/// picked more often than the rest
+fn change_record<F: Fn(u64, &mut Vec<&str>)>(
+    seed: u64,
+    table: &str,
+    delimiter: char,
+    change: F,
+) -> String {
+    let records = split_records(table);
+    let changed = match seed % 4 {
+        0 => 0,
+        _ => (seed / 4) as usize % records.len().max(1),
+    };
+    records
+        .iter()
+        .enumerate()
+        .map(|(idx, record)| {
+            if idx == changed {
+                let (mut fields, line_break) = split_fields(record, delimiter);
+                change(seed / 4, &mut fields);
+                format!("{}{}", fields.join(&delimiter.to_string()), line_break)
+            } else {
+                String::from(*record)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TABLE_AUTOMATON;
+    use itertools::Itertools;
+
+    const TABLE: &str = "id,name\r\n1,\"a,\r\nb\"\r\n2,c\r\n";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| TABLE_AUTOMATON.traverse(String::from(TABLE), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn dialects_are_changed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "id;name\r\n1;\"a,\r\nb\"\r\n2;c\r\n"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "id,name\n1,\"a,\r\nb\"\n2,c\n"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("\u{feff}{}", TABLE)));
+    }
+
+    #[test]
+    fn records_are_ragged() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "id\r\n1,\"a,\r\nb\"\r\n2,c\r\n"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "id,name,x,\r\n1,\"a,\r\nb\"\r\n2,c\r\n"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.split("\r\n").any(|record| record.len() > 100_000)));
+    }
+
+    #[test]
+    fn records_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("id,name\r\n{}", TABLE)));
+    }
+}
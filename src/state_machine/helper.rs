@@ -5,6 +5,9 @@ use super::{AutomatonNode, PRandomizer, Randomizer};
 lazy_static! {
     /// A trivial final state without a transformation function
     pub static ref FINAL: AutomatonNode<String> = AutomatonNode::<String>::new();
+
+    /// A trivial final state for byte-level automata
+    pub static ref FINAL_BYTES: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new();
 }
 
 pub fn char_flip_case(ch: char) -> char {
@@ -19,7 +22,7 @@ pub fn random_capitalization(seed: u64, to_transform: String) -> String {
     let mut transformed = String::new();
     let mut randomizer = PRandomizer::new(seed);
     for ch in to_transform.chars() {
-        let to_append: char = if randomizer.get() % 2 == 0 {
+        let to_append: char = if randomizer.get().is_multiple_of(2) {
             ch
         } else {
             char_flip_case(ch)
@@ -139,6 +142,11 @@ pub fn insert_random_char_from_range_in_string(seed: u64, s: &str, chars: &str)
     insert_string_in_string(seed, s, &pick_random_char(seed, chars))
 }
 
+/// Picks one of the values based on the seed
+pub fn pick<'a, T: ?Sized>(seed: u64, values: &[&'a T]) -> &'a T {
+    values[(seed % values.len() as u64) as usize]
+}
+
 pub fn pick_random_char(seed: u64, s: &str) -> String {
     match random_position_in_string(seed, s) {
         None => String::new(),
@@ -245,9 +253,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn picking_value_depends_on_seed() {
+        assert_eq!(pick(0, &["a", "b", "c"]), "a");
+        assert_eq!(pick(4, &["a", "b", "c"]), "b");
+        assert_eq!(pick(2, &[&b"a"[..], &b"b"[..]]), b"a");
+    }
+
     #[test]
     fn picking_position_in_empty_string_fails() {
-        assert!(random_position_in_string(0, &String::new()).is_none());
+        assert!(random_position_in_string(0, "").is_none());
     }
 
     #[test]
@@ -0,0 +1,215 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::mutator::mutate_once;
+use crate::state_machine::{Automaton, AutomatonNode};
+use crate::tokenizer::json_lexer::{JsonLexer, Rule};
+
+lazy_static! {
+    static ref START_BODY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (4, &CHUNK_BODY),
+            (1, &SMUGGLE_REQUEST),
+            (1, &TRUNCATE_BODY),
+            (1, &EMPTY_BODY),
+            (1, &OVERSIZED_BODY),
+        ]);
+    static ref CHUNK_BODY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| chunked(&body, 1 + (seed % 8) as usize))
+        .set_edges(vec![
+            (1, &FINAL),
+            (2, &INVALID_CHUNK_SIZE),
+            (1, &CHUNK_EXTENSION),
+            (1, &REMOVE_LAST_CHUNK),
+            (1, &BARE_LF_IN_CHUNKS),
+            (1, &ADD_TRAILERS),
+            (1, &TRUNCATE_BODY),
+        ]);
+    // negative, overflowing, prefixed, padded and mismatched sizes
+    static ref INVALID_CHUNK_SIZE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| {
+            let size = CHUNK_SIZES[(seed % CHUNK_SIZES.len() as u64) as usize];
+            match body.split_once("\r\n") {
+                Some((_, rest)) if !body.starts_with("0\r\n") => format!("{}\r\n{}", size, rest),
+                _ => format!("{}\r\n{}", size, body),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &CHUNK_EXTENSION)]);
+    static ref CHUNK_EXTENSION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| {
+            let extension = CHUNK_EXTENSIONS[(seed % CHUNK_EXTENSIONS.len() as u64) as usize];
+            body.replacen("\r\n", &format!("{}\r\n", extension), 1)
+        });
+    static ref REMOVE_LAST_CHUNK: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, body| match body.strip_suffix("0\r\n\r\n") {
+            Some(rest) => String::from(rest),
+            None => body,
+        });
+    static ref BARE_LF_IN_CHUNKS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| replace_random_occurrence(body, "\r\n", "\n", seed));
+    static ref ADD_TRAILERS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| {
+            let trailer = TRAILERS[(seed % TRAILERS.len() as u64) as usize];
+            match body.strip_suffix("\r\n") {
+                Some(rest) => format!("{}{}\r\n", rest, trailer),
+                None => format!("{}{}", body, trailer),
+            }
+        });
+    // a second request hidden in the body of the first one
+    static ref SMUGGLE_REQUEST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| format!(
+            "{}{}",
+            body,
+            [
+                "0\r\n\r\nGET /admin HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                "GET /admin HTTP/1.1\r\nX: ",
+                "\r\n\r\nGET /admin HTTP/1.1\r\nContent-Length: 10\r\n\r\n",
+            ][(seed % 3) as usize]
+        ));
+    static ref TRUNCATE_BODY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| match random_position_in_string(seed, &body) {
+            Some(pos) => String::from(&body[..pos]),
+            None => body,
+        });
+    static ref EMPTY_BODY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref OVERSIZED_BODY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, body| oversized(seed, &body));
+    pub static ref BODY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::body",
+        initial_node: &START_BODY,
+        generator: |seed| format!("a={}", seed),
+    };
+
+    static ref START_RESPONSE_BODY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &CHUNK_BODY),
+            (3, &MUTATE_JSON_BODY),
+            (2, &TRUNCATE_BODY),
+            (1, &SPLIT_RESPONSE),
+            (1, &EMPTY_BODY),
+            (1, &OVERSIZED_BODY),
+        ]);
+    // JSON bodies are fuzzed as JSON documents, anything else is left as is
+    static ref MUTATE_JSON_BODY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| mutate_json(&body, seed).unwrap_or(body))
+        .set_edges(vec![(3, &FINAL), (1, &CHUNK_BODY), (1, &TRUNCATE_BODY)]);
+    // a second response, which a client may take as the one to its next
+    // request
+    static ref SPLIT_RESPONSE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, body| format!(
+            "{}{}",
+            body,
+            [
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+                "\r\n\r\nHTTP/1.1 302 Found\r\nLocation: http://evil.example\r\n\r\n",
+                "0\r\n\r\nHTTP/1.1 200 OK\r\nSet-Cookie: session=injected\r\n\r\n",
+            ][(seed % 3) as usize]
+        ));
+    pub static ref RESPONSE_BODY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::response_body",
+        initial_node: &START_RESPONSE_BODY,
+        generator: |seed| format!("{{\"id\":{}}}", seed),
+    };
+}
+
+/// Invalid chunk sizes, which should be rejected but may be parsed leniently
+const CHUNK_SIZES: [&str; 8] = [
+    "-1",
+    "ffffffffffffffff1",
+    "0x5",
+    "00000000000000005",
+    "5 ",
+    "+5",
+    "fffffffffffffffe",
+    "g",
+];
+
+/// Chunk extensions, some of them malformed or long
+const CHUNK_EXTENSIONS: [&str; 5] = [";a=b", ";a=\"b;c\"", ";", "; a\n", ";xxxxxxxxxxxxxxxx"];
+
+/// Trailer fields, including malformed ones and ones which aren't allowed in
+/// a trailer section
+const TRAILERS: [&str; 8] = [
+    "Content-Length: 0\r\n",
+    "Transfer-Encoding: chunked\r\n",
+    "X-Trailer: 1\r\n",
+    "Host: evil.example\r\n",
+    "X-Trailer 1\r\n",
+    " X-Trailer: 1\r\n",
+    "X-Trailer: 1\n\r\n",
+    "Set-Cookie: session=injected\r\n",
+];
+
+/// Mutates a body as a JSON document. Result will be None if it isn't one.
+pub(super) fn mutate_json(body: &str, seed: u64) -> Option<String> {
+    mutate_once::<JsonLexer, Rule>(body, Rule::value, seed)
+}
+
+/// Encodes a body as per the chunked transfer coding, in chunks of the given
+/// size, ending with the last chunk
+pub(super) fn chunked(body: &str, size: usize) -> String {
+    let mut encoded = String::new();
+    for chunk in body.as_bytes().chunks(size) {
+        encoded.push_str(&format!(
+            "{:x}\r\n{}\r\n",
+            chunk.len(),
+            String::from_utf8_lossy(chunk)
+        ));
+    }
+    encoded + "0\r\n\r\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chunked, BODY_AUTOMATON, RESPONSE_BODY_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| BODY_AUTOMATON.traverse(String::from("hello"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn bodies_are_chunked() {
+        assert_eq!(
+            chunked("hello", 2),
+            "2\r\nhe\r\n2\r\nll\r\n1\r\no\r\n0\r\n\r\n"
+        );
+        assert_eq!(chunked("", 2), "0\r\n\r\n");
+    }
+
+    #[test]
+    fn chunk_sizes_are_invalid() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("-1\r\n")));
+    }
+
+    #[test]
+    fn chunks_get_extensions_and_trailers() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains(";a=b\r\n")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.ends_with("0\r\nX-Trailer: 1\r\n\r\n")));
+    }
+
+    #[test]
+    fn requests_are_smuggled() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.ends_with("GET /admin HTTP/1.1\r\nHost: localhost\r\n\r\n")));
+    }
+
+    #[test]
+    fn json_bodies_are_mutated() {
+        let body = String::from("{\"id\":1}");
+        assert!((1..100)
+            .map(|i| RESPONSE_BODY_AUTOMATON.traverse(body.clone(), i))
+            .any(|value| value != body && value.starts_with('{') && value.ends_with('}')));
+    }
+}
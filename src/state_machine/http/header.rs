@@ -0,0 +1,189 @@
+use super::super::helper::*;
+use super::{change_line_ending, oversized};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_HEADER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DUPLICATE_HEADER),
+            (2, &SPACE_BEFORE_COLON),
+            (2, &CHANGE_HEADER_LINE_ENDING),
+            (1, &FOLD_HEADER),
+            (1, &REMOVE_HEADER),
+            (1, &OVERSIZED_HEADER),
+        ]);
+    // e.g. two Host or Content-Length headers, of which each hop may pick a
+    // different one
+    static ref DUPLICATE_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, header| format!("{}{}", header, header))
+        .set_edges(vec![(3, &FINAL), (1, &CHANGE_HEADER_LINE_ENDING), (1, &DUPLICATE_HEADER_AGAIN)]);
+    static ref DUPLICATE_HEADER_AGAIN: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    static ref SPACE_BEFORE_COLON: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| header.replacen(':', [" :", "\t:", "\0:"][(seed % 3) as usize], 1));
+    static ref CHANGE_HEADER_LINE_ENDING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, header| change_line_ending(seed, &header));
+    // obsolete line folding - the header is followed by a line starting with
+    // whitespace, which continues its value
+    static ref FOLD_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| format!("{}{}", header, [" x\r\n", "\tchunked\r\n", " \r\n"][(seed % 3) as usize]));
+    static ref REMOVE_HEADER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref OVERSIZED_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| match header.split_once(':') {
+            Some((name, value)) => format!("{}:{}{}", name, oversized(seed, value.trim_end()), "\r\n"),
+            None => oversized(seed, &header),
+        });
+    pub static ref HEADER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::header",
+        initial_node: &START_HEADER,
+        generator: |seed| format!("X-Request-Id: {}\r\n", seed),
+    };
+
+    static ref START_EXTRA_HEADERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &SMUGGLING_HEADERS),
+            (2, &DUPLICATE_HOST),
+            (1, &FOLDED_EXTRA_HEADER),
+        ]);
+    // a message whose length is read differently by a proxy and by the server
+    // behind it
+    static ref SMUGGLING_HEADERS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}{}", headers, SMUGGLING[(seed % SMUGGLING.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_SMUGGLING_HEADERS), (1, &DUPLICATE_HOST)]);
+    static ref MORE_SMUGGLING_HEADERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    static ref DUPLICATE_HOST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}{}", headers, ["Host: localhost\r\n", "Host: evil.example\r\n", "host: localhost:80\r\n", "Host:\r\n"][(seed % 4) as usize]));
+    static ref FOLDED_EXTRA_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}{}", headers, [" Transfer-Encoding: chunked\r\n", "X: x\r\n Transfer-Encoding: chunked\r\n", "\tHost: evil.example\r\n"][(seed % 3) as usize]));
+    pub static ref EXTRA_HEADERS_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::extra_headers",
+        initial_node: &START_EXTRA_HEADERS,
+        generator: |_| String::new(),
+    };
+
+    static ref START_EXTRA_RESPONSE_HEADERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &MISMATCHED_CONTENT_LENGTH),
+            (2, &MULTIPLE_TRANSFER_ENCODINGS),
+            (2, &MALFORMED_SET_COOKIE),
+        ]);
+    // lengths that are unlikely to match the body, so that a client either
+    // waits for more or reads into the next response
+    static ref MISMATCHED_CONTENT_LENGTH: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}Content-Length: {}\r\n", headers, ["0", "1", "100000", "-1", "18446744073709551616", "1, 2"][(seed % 6) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_RESPONSE_HEADERS)]);
+    static ref MULTIPLE_TRANSFER_ENCODINGS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}{}", headers, [
+            "Transfer-Encoding: chunked\r\nTransfer-Encoding: chunked\r\n",
+            "Transfer-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n",
+            "Transfer-Encoding: chunked, gzip\r\n",
+            "Transfer-Encoding: identity\r\n",
+            "Transfer-Encoding: chunked\r\nContent-Length: 1\r\n",
+        ][(seed % 5) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_RESPONSE_HEADERS)]);
+    static ref MALFORMED_SET_COOKIE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, headers| format!("{}Set-Cookie: {}\r\n", headers, SET_COOKIES[(seed % SET_COOKIES.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_RESPONSE_HEADERS)]);
+    static ref MORE_RESPONSE_HEADERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    pub static ref EXTRA_RESPONSE_HEADERS_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::extra_response_headers",
+        initial_node: &START_EXTRA_RESPONSE_HEADERS,
+        generator: |_| String::new(),
+    };
+}
+
+/// Combinations of framing headers used in request smuggling (CL.TE, TE.CL,
+/// TE.TE and CL.CL)
+const SMUGGLING: [&str; 12] = [
+    "Content-Length: 4\r\nTransfer-Encoding: chunked\r\n",
+    "Transfer-Encoding: chunked\r\nContent-Length: 6\r\n",
+    "Transfer-Encoding: chunked\r\nTransfer-Encoding: identity\r\n",
+    "Transfer-Encoding : chunked\r\n",
+    "Transfer-Encoding: xchunked\r\n",
+    "Transfer-Encoding: chunked, identity\r\n",
+    "Transfer-Encoding:\tchunked\r\n",
+    "Transfer-Encoding: \"chunked\"\r\n",
+    "Content-Length: 0\r\nContent-Length: 5\r\n",
+    "Content-Length: +5\r\n",
+    "Content-Length: 0x5\r\n",
+    "Content-Length: 5, 5\r\n",
+];
+
+/// Cookies with missing names or values, conflicting or invalid attributes
+/// and unusual characters
+const SET_COOKIES: [&str; 10] = [
+    "=",
+    "=value",
+    "name",
+    "a=b; Domain=.com",
+    "a=b; Path=/; Path=/admin",
+    "a=b; Expires=Thu, 01 Jan 1970 99:99:99 GMT",
+    "a=b; Max-Age=-1; Max-Age=99999999999999999999",
+    "a=b; SameSite=None",
+    "__Host-a=b; Domain=localhost",
+    "a=\"b;c\"; a=d, e=f",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{EXTRA_HEADERS_AUTOMATON, EXTRA_RESPONSE_HEADERS_AUTOMATON, HEADER_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| HEADER_AUTOMATON.traverse(String::from("Host: localhost\r\n"), i))
+            .sorted()
+            .dedup()
+            .collect();
+        static ref TEST_EXTRA_HEADERS: Vec<String> = (1..300)
+            .map(|i| EXTRA_HEADERS_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+        static ref TEST_EXTRA_RESPONSE_HEADERS: Vec<String> = (1..300)
+            .map(|i| EXTRA_RESPONSE_HEADERS_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn headers_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "Host: localhost\r\nHost: localhost\r\n"));
+    }
+
+    #[test]
+    fn headers_are_folded() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "Host: localhost\r\n x\r\n"));
+    }
+
+    #[test]
+    fn smuggling_headers_are_added() {
+        assert!(TEST_EXTRA_HEADERS
+            .iter()
+            .any(|value| value == "Content-Length: 4\r\nTransfer-Encoding: chunked\r\n"));
+        assert!(TEST_EXTRA_HEADERS
+            .iter()
+            .any(|value| value.matches("Host").count() == 1 && value.contains("Content-Length")));
+    }
+
+    #[test]
+    fn response_headers_are_added() {
+        assert!(TEST_EXTRA_RESPONSE_HEADERS
+            .iter()
+            .any(|value| value == "Set-Cookie: =\r\n"));
+        assert!(TEST_EXTRA_RESPONSE_HEADERS
+            .iter()
+            .any(|value| value.matches("Transfer-Encoding").count() > 1));
+        assert!(TEST_EXTRA_RESPONSE_HEADERS
+            .iter()
+            .any(|value| value.starts_with("Content-Length: 100000\r\n")));
+    }
+}
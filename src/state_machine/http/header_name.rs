@@ -0,0 +1,80 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_HEADER_NAME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_HEADER_NAME),
+            (2, &CASED_HEADER_NAME),
+            (1, &UNDERSCORED_HEADER_NAME),
+            (1, &INVALID_CHAR_IN_HEADER_NAME),
+            (1, &EMPTY_HEADER_NAME),
+            (1, &OVERSIZED_HEADER_NAME),
+        ]);
+    static ref REPLACE_HEADER_NAME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(HEADER_NAMES[(seed % HEADER_NAMES.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &CASED_HEADER_NAME), (1, &UNDERSCORED_HEADER_NAME)]);
+    static ref CASED_HEADER_NAME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(to_random_case);
+    // some servers, e.g. CGI-based ones, treat "_" and "-" the same
+    static ref UNDERSCORED_HEADER_NAME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, name| name.replace('-', "_"));
+    static ref INVALID_CHAR_IN_HEADER_NAME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, name| insert_string_in_string(seed, &name, pick(seed, &[" ", "\t", "\0", ":", "\r", "\n", "\"", "(", ")", "\u{e9}"])));
+    static ref EMPTY_HEADER_NAME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref OVERSIZED_HEADER_NAME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, name| oversized(seed, &name));
+    pub static ref HEADER_NAME_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::header_name",
+        initial_node: &START_HEADER_NAME,
+        generator: |seed| String::from(HEADER_NAMES[(seed % HEADER_NAMES.len() as u64) as usize]),
+    };
+}
+
+/// Headers which affect how a message is framed, routed or interpreted
+pub const HEADER_NAMES: [&str; 12] = [
+    "Host",
+    "Content-Length",
+    "Transfer-Encoding",
+    "Connection",
+    "Content-Type",
+    "Expect",
+    "Upgrade",
+    "X-Forwarded-For",
+    "X-Forwarded-Host",
+    "X-HTTP-Method-Override",
+    "Authorization",
+    "Cookie",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::HEADER_NAME_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| HEADER_NAME_AUTOMATON.traverse(String::from("Accept-Encoding"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn names_are_replaced() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "Transfer-Encoding"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "Accept_Encoding"));
+    }
+
+    #[test]
+    fn names_get_invalid_chars() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains(' ')));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.is_empty()));
+    }
+}
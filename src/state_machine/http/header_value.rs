@@ -0,0 +1,102 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_HEADER_VALUE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &REPLACE_HEADER_VALUE),
+            (2, &INJECT_HEADER),
+            (2, &BARE_LINE_ENDING_IN_VALUE),
+            (1, &FOLD_HEADER_VALUE),
+            (1, &PAD_HEADER_VALUE),
+            (1, &OBS_TEXT_IN_VALUE),
+            (1, &EMPTY_HEADER_VALUE),
+            (1, &OVERSIZED_HEADER_VALUE),
+        ]);
+    static ref REPLACE_HEADER_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(HEADER_VALUES[(seed % HEADER_VALUES.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &PAD_HEADER_VALUE)]);
+    // CRLF injection, which ends the value and starts another header
+    static ref INJECT_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, value| format!("{}\r\n{}", value, ["X-Injected: 1", "Transfer-Encoding: chunked", "", "Host: evil.example"][(seed % 4) as usize]));
+    static ref BARE_LINE_ENDING_IN_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, value| insert_random_char_from_range_in_string(seed, &value, "\r\n"));
+    // obsolete line folding, i.e. a value continued on the next line
+    static ref FOLD_HEADER_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, value| {
+            let fold = ["\r\n ", "\r\n\t", "\n ", "\r\n \r\n "][(seed % 4) as usize];
+            match random_position_in_string(seed, &value) {
+                Some(pos) => format!("{}{}{}", &value[..pos], fold, &value[pos..]),
+                None => format!("{}{}", fold, value),
+            }
+        });
+    static ref PAD_HEADER_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, value| match seed % 3 {
+            0 => format!(" {}", value),
+            1 => format!("{}\t", value),
+            _ => format!("\x0b{}\x0c", value),
+        });
+    static ref OBS_TEXT_IN_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, value| insert_string_in_string(seed, &value, pick(seed, &["\u{80}", "\u{a0}", "\u{ff}", "\u{85}", "\u{2028}", "\0", "\u{7f}"])));
+    static ref EMPTY_HEADER_VALUE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref OVERSIZED_HEADER_VALUE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, value| oversized(seed, &value));
+    pub static ref HEADER_VALUE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::header_value",
+        initial_node: &START_HEADER_VALUE,
+        generator: |seed| String::from(HEADER_VALUES[(seed % HEADER_VALUES.len() as u64) as usize]),
+    };
+}
+
+/// Values which are ambiguous, or valid for some headers and not for others
+const HEADER_VALUES: [&str; 12] = [
+    "chunked",
+    "chunked, identity",
+    "xchunked",
+    "0",
+    "-1",
+    "+1",
+    "99999999999999999999",
+    "localhost",
+    "localhost:80@evil.example",
+    "100-continue",
+    "close, Transfer-Encoding",
+    "h2c",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::HEADER_VALUE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| HEADER_VALUE_AUTOMATON.traverse(String::from("text/html"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn values_are_folded() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("\r\n ") || value.contains("\r\n\t")));
+    }
+
+    #[test]
+    fn headers_are_injected() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.ends_with("\r\nTransfer-Encoding: chunked")));
+    }
+
+    #[test]
+    fn bare_line_endings_are_inserted() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains('\r') && !value.contains("\r\n")));
+    }
+}
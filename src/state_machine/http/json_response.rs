@@ -0,0 +1,166 @@
+use super::super::helper::*;
+use super::body::{chunked, mutate_json};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_JSON_RESPONSE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (4, &MUTATE_JSON_BODY),
+            (1, &MISMATCH_CONTENT_LENGTH),
+            (1, &CHUNK_RESPONSE),
+            (1, &CHANGE_CONTENT_TYPE),
+        ]);
+    // the body is fuzzed by the JSON automata, while the framing is kept
+    // valid so that it reaches the client's JSON parser
+    static ref MUTATE_JSON_BODY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, response| {
+            let (head, body) = split(&response);
+            match mutate_json(body, seed) {
+                Some(mutated) => with_body(head, &mutated),
+                None => response,
+            }
+        })
+        .set_edges(vec![
+            (3, &FINAL),
+            (1, &MISMATCH_CONTENT_LENGTH),
+            (1, &CHUNK_RESPONSE),
+            (1, &CLOSE_EARLY),
+            (1, &CHANGE_CONTENT_TYPE),
+        ]);
+    static ref MISMATCH_CONTENT_LENGTH: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, response| {
+            let (head, body) = split(&response);
+            let length = match seed % 3 {
+                0 => body.len() + 1,
+                1 => body.len().saturating_sub(1),
+                _ => 0,
+            };
+            format!("{}\r\n\r\n{}", content_length(head, length), body)
+        });
+    static ref CHUNK_RESPONSE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, response| {
+            let (head, body) = split(&response);
+            format!(
+                "{}\r\n\r\n{}",
+                head.replace(&format!("Content-Length: {}", body.len()), "Transfer-Encoding: chunked"),
+                chunked(body, 1 + (seed % 16) as usize)
+            )
+        })
+        .set_edges(vec![(3, &FINAL), (1, &CLOSE_EARLY)]);
+    // the connection is closed before the whole body has been sent
+    static ref CLOSE_EARLY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, response| {
+            let (head, body) = split(&response);
+            match random_position_in_string(seed, body) {
+                Some(pos) => format!("{}\r\n\r\n{}", head, &body[..pos]),
+                None => response,
+            }
+        });
+    static ref CHANGE_CONTENT_TYPE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, response| response.replacen(
+            "application/json",
+            CONTENT_TYPES[(seed % CONTENT_TYPES.len() as u64) as usize],
+            1
+        ));
+    pub static ref JSON_RESPONSE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::json_response",
+        initial_node: &START_JSON_RESPONSE,
+        generator: |seed| json_response(JSON_BODIES[(seed % JSON_BODIES.len() as u64) as usize]),
+    };
+}
+
+/// Bodies of typical API responses
+const JSON_BODIES: [&str; 4] = [
+    r#"{"id":1,"name":"talasum","tags":["a","b"],"active":true}"#,
+    r#"{"error":{"code":404,"message":"Not found"}}"#,
+    r#"[{"id":1,"price":9.99},{"id":2,"price":null}]"#,
+    r#"{"data":{"items":[],"next":"/items?page=2"},"total":0}"#,
+];
+
+/// Media types a JSON body may be served with, some of which clients should
+/// refuse to parse it as JSON
+const CONTENT_TYPES: [&str; 6] = [
+    "application/json; charset=utf-16",
+    "application/problem+json",
+    "text/html",
+    "application/json, text/html",
+    "application/JSON",
+    "",
+];
+
+/// Creates a successful response with a JSON body
+fn json_response(body: &str) -> String {
+    with_body(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 0",
+        body,
+    )
+}
+
+/// Splits a response into its head, without the empty line after it, and its
+/// body
+fn split(response: &str) -> (&str, &str) {
+    response.split_once("\r\n\r\n").unwrap_or((response, ""))
+}
+
+/// Joins a head and a body, setting the length of the body in the head
+fn with_body(head: &str, body: &str) -> String {
+    format!("{}\r\n\r\n{}", content_length(head, body.len()), body)
+}
+
+/// Sets the value of the Content-Length header in a head
+fn content_length(head: &str, length: usize) -> String {
+    head.split("\r\n")
+        .map(|line| match line.strip_prefix("Content-Length:") {
+            Some(_) => format!("Content-Length: {}", length),
+            None => String::from(line),
+        })
+        .collect::<Vec<String>>()
+        .join("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_response, JSON_RESPONSE_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| JSON_RESPONSE_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn responses_have_the_length_of_their_body() {
+        assert_eq!(
+            json_response("{}"),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}"
+        );
+    }
+
+    #[test]
+    fn json_bodies_are_mutated() {
+        let valid_length = |value: &String| match value.split_once("\r\n\r\n") {
+            Some((head, body)) => head.ends_with(&format!("Content-Length: {}", body.len())),
+            None => false,
+        };
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .filter(|value| valid_length(value))
+            .any(|value| serde_json::from_str::<serde_json::Value>(
+                value.split_once("\r\n\r\n").unwrap().1
+            )
+            .is_err()));
+    }
+
+    #[test]
+    fn framing_is_broken() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("Transfer-Encoding: chunked\r\n\r\n")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("text/html")));
+    }
+}
@@ -0,0 +1,61 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_METHOD: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_METHOD),
+            (2, &CASED_METHOD),
+            (1, &OVERSIZED_METHOD),
+            (1, &INVALID_CHAR_IN_METHOD),
+        ]);
+    static ref REPLACE_METHOD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(METHODS[(seed % METHODS.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &CASED_METHOD)]);
+    // methods are case-sensitive, yet some servers normalize them
+    static ref CASED_METHOD: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(to_random_case);
+    static ref OVERSIZED_METHOD: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, method| oversized(seed, &method));
+    static ref INVALID_CHAR_IN_METHOD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, method| insert_random_char_from_range_in_string(seed, &method, "\t\0\r\n\x0b/(@"));
+    pub static ref METHOD_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::method",
+        initial_node: &START_METHOD,
+        generator: |seed| String::from(METHODS[(seed % METHODS.len() as u64) as usize]),
+    };
+}
+
+/// Standard methods along with WebDAV and made-up ones
+const METHODS: [&str; 14] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH", "PROPFIND",
+    "MKCOL", "PRI", "FOO", "G",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::METHOD_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| METHOD_AUTOMATON.traverse(String::from("GET"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn methods_are_replaced() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "TRACE"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "gEt" || value == "get"));
+    }
+
+    #[test]
+    fn methods_are_oversized() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.len() > 4096));
+    }
+}
@@ -0,0 +1,34 @@
+pub mod body;
+pub mod header;
+pub mod header_name;
+pub mod header_value;
+pub mod json_response;
+pub mod method;
+pub mod reason;
+pub mod request_line;
+pub mod status_code;
+pub mod status_line;
+pub mod target;
+pub mod version;
+
+/// Lengths just past the limits commonly put on lines and fields
+const OVERSIZED_LENGTHS: [usize; 3] = [4097, 8193, 65537];
+
+/// Repeats a text, or a placeholder if it's empty, until it exceeds one of
+/// the common length limits
+fn oversized(seed: u64, text: &str) -> String {
+    let length = OVERSIZED_LENGTHS[(seed % OVERSIZED_LENGTHS.len() as u64) as usize];
+    let unit = if text.is_empty() { "A" } else { text };
+    unit.repeat(length / unit.len() + 1)
+}
+
+/// Replaces the line ending of a line with one recipients may or may not
+/// accept
+fn change_line_ending(seed: u64, line: &str) -> String {
+    let ending = ["\r", "\n", "\r\r\n", "\n\r"][(seed % 4) as usize];
+    format!(
+        "{}{}",
+        line.trim_end_matches('\n').trim_end_matches('\r'),
+        ending
+    )
+}
@@ -0,0 +1,57 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_REASON: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &REMOVE_REASON),
+            (2, &INJECT_INTO_REASON),
+            (1, &CONTROL_CHAR_IN_REASON),
+            (1, &OBS_TEXT_IN_REASON),
+            (1, &OVERSIZED_REASON),
+        ]);
+    static ref REMOVE_REASON: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    // response splitting - the reason is followed by headers or a whole
+    // other response
+    static ref INJECT_INTO_REASON: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, reason| format!("{}{}", reason, [
+            "\r\nContent-Length: 0",
+            "\r\nSet-Cookie: session=injected",
+            "\r\n\r\nHTTP/1.1 200 OK",
+            "\nLocation: http://evil.example",
+        ][(seed % 4) as usize]));
+    static ref CONTROL_CHAR_IN_REASON: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, reason| insert_random_char_from_range_in_string(seed, &reason, "\0\r\n\t\x1b\x7f"));
+    static ref OBS_TEXT_IN_REASON: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, reason| insert_string_in_string(seed, &reason, pick(seed, &["\u{80}", "\u{ff}", "\u{202e}", "\u{feff}"])));
+    static ref OVERSIZED_REASON: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, reason| oversized(seed, &reason));
+    pub static ref REASON_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::reason",
+        initial_node: &START_REASON,
+        generator: |seed| String::from(["OK", "Not Found", "Continue", ""][(seed % 4) as usize]),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::REASON_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| REASON_AUTOMATON.traverse(String::from("OK"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn responses_are_split() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "OK\r\n\r\nHTTP/1.1 200 OK"));
+    }
+}
@@ -0,0 +1,58 @@
+use super::super::helper::*;
+use super::change_line_ending;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_REQUEST_LINE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &FINAL),
+            (2, &CHANGE_SEPARATORS),
+            (2, &CHANGE_REQUEST_LINE_ENDING),
+            (1, &LEADING_EMPTY_LINE),
+        ]);
+    // any amount of whitespace between the parts, which some parsers accept
+    static ref CHANGE_SEPARATORS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, line| {
+            let separator = ["  ", "\t", " \t", "\x0b", "\x0c"][(seed % 5) as usize];
+            line.replacen(' ', separator, 1 + (seed % 2) as usize)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &CHANGE_REQUEST_LINE_ENDING)]);
+    static ref CHANGE_REQUEST_LINE_ENDING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, line| change_line_ending(seed, &line));
+    // servers should ignore at least one empty line before the request line
+    static ref LEADING_EMPTY_LINE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, line| format!("{}{}", ["\r\n", "\n", "\r\n\r\n"][(seed % 3) as usize], line));
+    pub static ref REQUEST_LINE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::request_line",
+        initial_node: &START_REQUEST_LINE,
+        generator: |_| String::from("GET / HTTP/1.1\r\n"),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::REQUEST_LINE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| REQUEST_LINE_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn bare_cr_line_endings_are_generated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.ends_with("1.1\r")));
+    }
+
+    #[test]
+    fn separators_are_changed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("GET\t/")));
+    }
+}
@@ -0,0 +1,58 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_STATUS_CODE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_STATUS_CODE),
+            (1, &MALFORMED_STATUS_CODE),
+            (1, &REMOVE_STATUS_CODE),
+        ]);
+    static ref REPLACE_STATUS_CODE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(
+            STATUS_CODES[(seed % STATUS_CODES.len() as u64) as usize]
+        ));
+    static ref MALFORMED_STATUS_CODE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, code| match seed % 5 {
+            0 => format!("{}0", code),
+            1 => code.chars().take(2).collect(),
+            2 => format!("+{}", code),
+            3 => format!("-{}", code),
+            _ => insert_random_char_from_range_in_string(seed, &code, " \t.xX\0"),
+        });
+    static ref REMOVE_STATUS_CODE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    pub static ref STATUS_CODE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::status_code",
+        initial_node: &START_STATUS_CODE,
+        generator: |seed| String::from(STATUS_CODES[(seed % STATUS_CODES.len() as u64) as usize]),
+    };
+}
+
+/// Codes which change how the rest of a response is read, e.g. ones which
+/// can't have a body, along with unassigned and out of range ones
+const STATUS_CODES: [&str; 16] = [
+    "200", "100", "101", "103", "204", "205", "304", "302", "401", "407", "000", "099", "199",
+    "599", "600", "999",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::STATUS_CODE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| STATUS_CODE_AUTOMATON.traverse(String::from("200"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn codes_are_odd() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "101"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "2000"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.is_empty()));
+    }
+}
@@ -0,0 +1,72 @@
+use super::super::helper::*;
+use super::change_line_ending;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_STATUS_LINE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &FINAL),
+            (2, &REMOVE_STATUS_LINE),
+            (1, &REMOVE_REASON_PHRASE),
+            (1, &CHANGE_STATUS_LINE_SEPARATORS),
+            (2, &CHANGE_STATUS_LINE_ENDING),
+            (1, &INTERIM_RESPONSE),
+        ]);
+    // an HTTP/0.9 response, where the body is all there is
+    static ref REMOVE_STATUS_LINE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref REMOVE_REASON_PHRASE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, line| {
+            let status: Vec<&str> = line.splitn(3, ' ').take(2).collect();
+            format!("{}{}", status.join(" "), ["\r\n", " \r\n"][(seed % 2) as usize])
+        });
+    static ref CHANGE_STATUS_LINE_SEPARATORS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, line| {
+            let separator = ["  ", "\t", "", "\x0b"][(seed % 4) as usize];
+            line.replacen(' ', separator, 1 + (seed % 2) as usize)
+        });
+    static ref CHANGE_STATUS_LINE_ENDING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, line| change_line_ending(seed, &line));
+    // informational responses precede the final one and are easy to mistake
+    // for it
+    static ref INTERIM_RESPONSE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, line| format!("{}{}", [
+            "HTTP/1.1 100 Continue\r\n\r\n",
+            "HTTP/1.1 103 Early Hints\r\nLink: </a>\r\n\r\n",
+            "HTTP/1.1 102 Processing\r\n\r\nHTTP/1.1 100 Continue\r\n\r\n",
+        ][(seed % 3) as usize], line));
+    pub static ref STATUS_LINE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::status_line",
+        initial_node: &START_STATUS_LINE,
+        generator: |_| String::from("HTTP/1.1 200 OK\r\n"),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::STATUS_LINE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| STATUS_LINE_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn status_lines_are_removed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.is_empty()));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "HTTP/1.1 200\r\n"));
+    }
+
+    #[test]
+    fn interim_responses_are_added() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,91 @@
+use super::super::helper::*;
+use super::oversized;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TARGET: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &CHANGE_FORM),
+            (2, &TRAVERSE_PATH),
+            (2, &ENCODE_PATH),
+            (1, &DUPLICATE_PARAMETER),
+            (1, &INVALID_CHAR_IN_TARGET),
+            (1, &OVERSIZED_TARGET),
+        ]);
+    // absolute-form, authority-form and asterisk-form instead of origin-form
+    static ref CHANGE_FORM: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, target| match seed % 4 {
+            0 => format!("http://localhost{}", target),
+            1 => format!("http://evil.example{}", target),
+            2 => String::from("localhost:443"),
+            _ => String::from("*"),
+        });
+    static ref TRAVERSE_PATH: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, target| {
+            let traversal = TRAVERSALS[(seed % TRAVERSALS.len() as u64) as usize];
+            format!("/{}{}", traversal, target.trim_start_matches('/'))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &ENCODE_PATH)]);
+    static ref ENCODE_PATH: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, target| insert_string_in_string(
+            seed,
+            &target,
+            ENCODINGS[(seed % ENCODINGS.len() as u64) as usize]
+        ));
+    // parameter pollution, handled differently by proxies and applications
+    static ref DUPLICATE_PARAMETER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, target| match target.split_once('?') {
+            Some((_, query)) => format!("{}&{}", target, query),
+            None => format!("{}?a={}&a=", target, seed % 10),
+        });
+    static ref INVALID_CHAR_IN_TARGET: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, target| insert_string_in_string(seed, &target, pick(seed, &[" ", "\t", "\r", "\n", "\0", "#", "\\", "\u{7f}", "\u{e9}"])));
+    static ref OVERSIZED_TARGET: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, target| oversized(seed, &target));
+    pub static ref TARGET_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::target",
+        initial_node: &START_TARGET,
+        generator: |seed| format!("/{}", seed % 100),
+    };
+}
+
+/// Path segments escaping the document root
+const TRAVERSALS: [&str; 5] = ["../", "..%2f", "%2e%2e/", "..;/", "/"];
+
+/// Encoded characters, some of which are decoded twice or interpreted as
+/// delimiters
+const ENCODINGS: [&str; 6] = ["%00", "%2f", "%252f", "%0d%0a", "%u002f", "%"];
+
+#[cfg(test)]
+mod tests {
+    use super::TARGET_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| TARGET_AUTOMATON.traverse(String::from("/a?b=c"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn forms_are_changed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "*"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("http://")));
+    }
+
+    #[test]
+    fn paths_are_traversed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("/../")));
+    }
+
+    #[test]
+    fn parameters_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "/a?b=c&b=c"));
+    }
+}
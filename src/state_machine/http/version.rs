@@ -0,0 +1,62 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_VERSION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_VERSION),
+            (1, &CASED_VERSION),
+            (1, &REMOVE_VERSION),
+            (1, &PAD_VERSION),
+        ]);
+    static ref REPLACE_VERSION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(VERSIONS[(seed % VERSIONS.len() as u64) as usize]));
+    static ref CASED_VERSION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(to_random_case);
+    // a request line without a version is an HTTP/0.9 one
+    static ref REMOVE_VERSION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref PAD_VERSION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, version| match seed % 3 {
+            0 => format!("{} ", version),
+            1 => format!("{}\r", version),
+            _ => format!(" {}", version),
+        });
+    pub static ref VERSION_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "http::version",
+        initial_node: &START_VERSION,
+        generator: |seed| String::from(VERSIONS[(seed % 2) as usize]),
+    };
+}
+
+/// Existing versions along with malformed ones
+const VERSIONS: [&str; 8] = [
+    "HTTP/1.1",
+    "HTTP/1.0",
+    "HTTP/0.9",
+    "HTTP/2.0",
+    "HTTP/1.10",
+    "HTTP/01.1",
+    "HTTP/9.9",
+    "HTTP/1",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::VERSION_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| VERSION_AUTOMATON.traverse(String::from("HTTP/1.1"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn versions_are_replaced_and_removed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "HTTP/1.0"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.is_empty()));
+    }
+}
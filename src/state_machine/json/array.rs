@@ -6,7 +6,7 @@ use super::string::STRING_AUTOMATON;
 use crate::state_machine::{json::object::OBJECT_AUTOMATON, Automaton, AutomatonNode};
 
 lazy_static! {
-    static ref START_ARRAY: AutomatonNode<String> =
+    pub static ref START_ARRAY: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_edges(vec![
             (5, &ADD_ELEMENT_ARRAY),
             (1, &LARGE_ARRAY),
@@ -53,6 +53,7 @@ lazy_static! {
         .set_cycle(2)
         .set_func(|seed, text| insert_element(seed, text, &OBJECT_AUTOMATON));
     pub static ref ARRAY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::array",
         initial_node: &START_ARRAY,
         generator: |_| String::from("[]")
     };
@@ -42,6 +42,7 @@ lazy_static! {
         .set_func(to_capitalized)
         .set_edge(&START_WS);
     pub static ref BOOL_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::boolean",
         initial_node: &START_BOOLEAN,
         generator: |seed| {
             if seed % 2 == 0 {
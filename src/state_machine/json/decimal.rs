@@ -0,0 +1,204 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use std::fmt;
+
+/// Numbers with exponents beyond this are printed in scientific notation
+/// rather than positional, so that e.g. 1e-999999 doesn't expand to a million
+/// digits
+const MAX_POSITIONAL_EXPONENT: u64 = 1000;
+
+/// Numbers with exponents beyond this aren't parsed, so that arithmetic on
+/// the exponent, e.g. when taking a square root, cannot overflow
+const MAX_EXPONENT: u64 = i32::MAX as u64;
+
+/// A decimal number in arbitrary precision - its value is
+/// `digits * 10^exponent`, negated if `negative` is set. Unlike floats, it
+/// keeps all digits of the text it's parsed from, including trailing zeros of
+/// the fraction and the sign of zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decimal {
+    pub negative: bool,
+    pub digits: BigUint,
+    pub exponent: i64,
+}
+
+impl Decimal {
+    /// Parses a number with an optional sign, fraction and exponent. Leading
+    /// zeros, a leading `+` and a trailing `.` are tolerated. Numbers whose
+    /// exponent exceeds 2^31 - 1 in absolute value are rejected.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (negative, unsigned) = match text.as_bytes().first()? {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(pos) => (&unsigned[..pos], unsigned[pos + 1..].parse::<i64>().ok()?),
+            None => (unsigned, 0),
+        };
+        let (whole, fraction) = match mantissa.find('.') {
+            Some(pos) => (&mantissa[..pos], &mantissa[pos + 1..]),
+            None => (mantissa, ""),
+        };
+        let all_digits = format!("{}{}", whole, fraction);
+        if all_digits.is_empty() || !all_digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let exponent = exponent.checked_sub(fraction.len() as i64)?;
+        if exponent.unsigned_abs() > MAX_EXPONENT {
+            return None;
+        }
+        Some(Self {
+            negative,
+            digits: all_digits.parse().ok()?,
+            exponent,
+        })
+    }
+
+    /// Returns the number in scientific notation, always signed, e.g. +1.5e-3
+    pub fn to_scientific(&self) -> String {
+        let digits = self.digits.to_string();
+        let exponent = self.exponent + digits.len() as i64 - 1;
+        let significant = match digits.trim_end_matches('0') {
+            "" => "0",
+            trimmed => trimmed,
+        };
+        let mantissa = match significant.len() {
+            1 => significant.to_string(),
+            _ => format!("{}.{}", &significant[..1], &significant[1..]),
+        };
+        let sign = if self.negative { "-" } else { "+" };
+        format!("{}{}e{}", sign, mantissa, exponent)
+    }
+
+    /// Returns the square root of the absolute value, with at least
+    /// `precision` digits after the decimal point. The sign is kept.
+    pub fn sqrt(&self, precision: u32) -> Self {
+        let mut root_exponent = self.exponent - 2 * precision as i64;
+        if root_exponent % 2 != 0 {
+            root_exponent -= 1;
+        }
+        let scaled = &self.digits * pow10((self.exponent - root_exponent) as u64);
+        Self {
+            negative: self.negative,
+            digits: scaled.sqrt(),
+            exponent: root_exponent / 2,
+        }
+    }
+
+    /// Returns the integer part of the number, or `None` if the number is
+    /// too large to be expanded
+    pub fn integer_part(&self) -> Option<BigInt> {
+        let magnitude = if self.exponent >= 0 {
+            if self.exponent as u64 > MAX_POSITIONAL_EXPONENT {
+                return None;
+            }
+            &self.digits * pow10(self.exponent as u64)
+        } else if self.exponent.unsigned_abs() > self.digits.bits() {
+            // 10^n > 2^n, which exceeds the digits
+            BigUint::from(0_u32)
+        } else {
+            &self.digits / pow10(self.exponent.unsigned_abs())
+        };
+        let sign = if self.negative {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        Some(BigInt::from_biguint(sign, magnitude))
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Prints the number in positional notation, falling back to scientific
+    /// notation for huge exponents
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exponent.unsigned_abs() > MAX_POSITIONAL_EXPONENT {
+            return write!(f, "{}", self.to_scientific());
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let digits = self.digits.to_string();
+        if self.exponent >= 0 && digits == "0" {
+            write!(f, "0")
+        } else if self.exponent >= 0 {
+            write!(f, "{}{}", digits, "0".repeat(self.exponent as usize))
+        } else {
+            let fraction_len = self.exponent.unsigned_abs() as usize;
+            if digits.len() > fraction_len {
+                let point = digits.len() - fraction_len;
+                write!(f, "{}.{}", &digits[..point], &digits[point..])
+            } else {
+                write!(f, "0.{}{}", "0".repeat(fraction_len - digits.len()), digits)
+            }
+        }
+    }
+}
+
+fn pow10(exponent: u64) -> BigUint {
+    BigUint::from(10_u32).pow(exponent as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decimal;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn representation_is_preserved() {
+        for text in [
+            "0",
+            "-0",
+            "1.50",
+            "-0.001",
+            "123456789012345678901234567890.5",
+        ] {
+            assert_eq!(Decimal::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn lenient_forms_are_parsed() {
+        assert_eq!(Decimal::parse("+007").unwrap().to_string(), "7");
+        assert_eq!(Decimal::parse("5.").unwrap().to_string(), "5");
+        assert_eq!(Decimal::parse("1E+3").unwrap().to_string(), "1000");
+        assert!(Decimal::parse("NaN").is_none());
+        assert!(Decimal::parse("-").is_none());
+        assert!(Decimal::parse("1e99999999999999999999").is_none());
+        assert!(Decimal::parse("1e9223372036854775807").is_none());
+        assert!(Decimal::parse("1e-9223372036854775807").is_none());
+    }
+
+    #[test]
+    fn huge_exponents_are_printed_in_scientific_notation() {
+        assert_eq!(
+            Decimal::parse("1e-999999").unwrap().to_string(),
+            "+1e-999999"
+        );
+        assert_eq!(Decimal::parse("-25e1001").unwrap().to_string(), "-2.5e1002");
+    }
+
+    #[test]
+    fn sqrt_is_precise() {
+        let root = Decimal::parse("2").unwrap().sqrt(30).to_string();
+        assert!(root.starts_with("1.414213562373095048801688724209"));
+        assert_eq!(Decimal::parse("-4").unwrap().sqrt(1).to_string(), "-2.0");
+    }
+
+    #[test]
+    fn integer_part_is_truncated() {
+        assert_eq!(
+            Decimal::parse("-12.9").unwrap().integer_part(),
+            Some(BigInt::from(-12))
+        );
+        assert_eq!(
+            Decimal::parse("1.5e3").unwrap().integer_part(),
+            Some(BigInt::from(1500))
+        );
+        assert_eq!(
+            Decimal::parse("1e-999999").unwrap().integer_part(),
+            Some(BigInt::from(0))
+        );
+        assert_eq!(Decimal::parse("1e999999").unwrap().integer_part(), None);
+    }
+}
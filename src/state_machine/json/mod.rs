@@ -1,7 +1,11 @@
 pub mod array;
 pub mod boolean;
+pub mod decimal;
+pub mod ndjson;
+pub mod nesting;
 pub mod null;
 pub mod number;
 pub mod object;
+pub mod separator;
 pub mod string;
 pub mod whitespace;
@@ -0,0 +1,167 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_STREAM: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &DROP_RECORD),
+            (2, &DUPLICATE_RECORD),
+            (2, &SWAP_RECORDS),
+            (1, &JOIN_RECORDS),
+            (1, &SPLIT_RECORD),
+            (1, &BLANK_LINE),
+            (1, &CRLF_LINE),
+        ]);
+    static ref DROP_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            records.remove(pick_record(seed, records));
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    static ref DUPLICATE_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            let idx = pick_record(seed, records);
+            records.insert(idx, records[idx].clone());
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    static ref SWAP_RECORDS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            let first = pick_record(seed, records);
+            let second = pick_record(seed / 7, records);
+            records.swap(first, second);
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    // two records on the same line, with or without a separator
+    static ref JOIN_RECORDS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            if records.len() > 1 {
+                let idx = pick_record(seed, &records[1..]);
+                let next = records.remove(idx + 1);
+                let separator = [" ", "", ","][(seed % 3) as usize];
+                records[idx] = format!("{}{}{}", records[idx].trim_end(), separator, next);
+            }
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    // a record spanning two lines
+    static ref SPLIT_RECORD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            let idx = pick_record(seed, records);
+            let record = records[idx].trim_end().to_string();
+            if let Some(pos) = random_position_in_string(seed, &record) {
+                let (first, second) = record.split_at(pos);
+                records[idx] = format!("{}\n{}\n", first, second);
+            }
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    static ref BLANK_LINE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            let blank = ["\n", "\r\n", " \n", "\t\n"][(seed % 4) as usize];
+            let idx = (seed % (records.len() as u64 + 1)) as usize;
+            records.insert(idx, String::from(blank));
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    // CRLF or a lone CR as the line terminator of a record
+    static ref CRLF_LINE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| change_records(&text, |records| {
+            let idx = pick_record(seed, records);
+            let terminator = ["\r\n", "\r"][(seed % 2) as usize];
+            records[idx] = format!("{}{}", records[idx].trim_end_matches(['\r', '\n']), terminator);
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &ANOTHER_CHANGE)]);
+    static ref ANOTHER_CHANGE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref NDJSON_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::ndjson",
+        initial_node: &START_STREAM,
+        generator: |seed| format!("{{\"id\": {}}}\n{{\"id\": {}}}\n", seed, seed + 1),
+    };
+}
+
+/// Picks the index of one of `records`, which may be empty
+fn pick_record(seed: u64, records: &[String]) -> usize {
+    (seed % records.len().max(1) as u64) as usize
+}
+
+/// Splits a stream into lines, each ending in its line terminator, applies
+/// `change` to them and joins them back. Whether the stream ends in a line
+/// terminator is kept, and a stream with no lines is treated as a single
+/// empty one.
+fn change_records<F: FnOnce(&mut Vec<String>)>(text: &str, change: F) -> String {
+    let terminated = text.ends_with('\n');
+    let mut records: Vec<String> = text
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.ends_with('\n') {
+                String::from(line)
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect();
+    if records.is_empty() {
+        records.push(String::from("\n"));
+    }
+    change(&mut records);
+
+    let mut changed = records.concat();
+    if !terminated && changed.ends_with('\n') {
+        changed.pop();
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{change_records, NDJSON_AUTOMATON};
+    use itertools::Itertools;
+
+    const STREAM: &str = "{\"a\": 1}\n{\"b\": 2}\n{\"c\": 3}\n";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| NDJSON_AUTOMATON.traverse(String::from(STREAM), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn line_terminators_are_kept() {
+        assert_eq!(change_records("1\n2", |records| records.swap(0, 1)), "2\n1");
+        assert_eq!(
+            change_records("1\r\n2\n", |records| records.swap(0, 1)),
+            "2\n1\r\n"
+        );
+        assert_eq!(change_records("", |records| records.clear()), "");
+    }
+
+    #[test]
+    fn records_are_dropped_and_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.lines().count() < 3));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.matches("{\"a\": 1}").count() > 1));
+    }
+
+    #[test]
+    fn records_are_reordered() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("{\"b\": 2}\n{\"a\": 1}\n")));
+    }
+
+    #[test]
+    fn records_are_joined_and_split() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("}{")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.lines().count() > 3));
+    }
+
+    #[test]
+    fn crlf_and_blank_lines_are_inserted() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("}\r\n")));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("\n\n")));
+    }
+}
@@ -0,0 +1,163 @@
+use super::super::helper::*;
+use crate::configuration::Configurable;
+use crate::randomness::{PRandomizer, Randomizer};
+use crate::state_machine::weights::CONFIG;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+/// Characters closing nested collections, both in JSON and flow-style YAML
+const CLOSERS: &str = "]}";
+
+lazy_static! {
+    static ref START_NESTING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (1, &NEST_IN_ARRAYS),
+            (1, &NEST_IN_OBJECTS),
+            (1, &NEST_IN_MIXED),
+        ]);
+    static ref NEST_IN_ARRAYS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 0, &JSON_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    static ref NEST_IN_OBJECTS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 1, &JSON_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    static ref NEST_IN_MIXED: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest_in_mixed(seed, &text, &JSON_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    pub static ref UNBALANCE_CLOSERS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_edges(vec![
+            (1, &EXTRA_CLOSERS),
+            (1, &MISSING_CLOSERS),
+            (1, &MISMATCHED_CLOSER),
+            (3, &FINAL)
+        ]);
+    static ref EXTRA_CLOSERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(add_extra_closers);
+    static ref MISSING_CLOSERS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(remove_closers);
+    static ref MISMATCHED_CLOSER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(mismatch_closer);
+    pub static ref NESTING_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::nesting",
+        initial_node: &START_NESTING,
+        generator: |_| String::from("null")
+    };
+}
+
+/// (opening, closing) parts of the collections a JSON value can be nested in
+pub const JSON_COLLECTIONS: [(&str, &str); 2] = [("[", "]"), ("{\"a\": ", "}")];
+
+/// Picks the number of levels to nest a value in. Large depths are favored,
+/// the max one being the configured limit.
+pub fn nesting_depth(seed: u64) -> usize {
+    let max_depth = CONFIG.get_max_nesting_depth() as usize;
+    (max_depth >> (seed % 8)).max(1)
+}
+
+/// Nests `value` in `depth` collections. `kind` picks the collection for each
+/// level, starting from the outermost one, out of `collections`.
+pub fn nest<F: Fn(usize) -> usize>(
+    value: &str,
+    depth: usize,
+    kind: F,
+    collections: &[(&str, &str)],
+) -> String {
+    let kinds: Vec<usize> = (0..depth).map(kind).collect();
+    let mut nested = String::new();
+    for kind in &kinds {
+        nested.push_str(collections[*kind].0);
+    }
+    nested.push_str(value);
+    for kind in kinds.iter().rev() {
+        nested.push_str(collections[*kind].1);
+    }
+    nested
+}
+
+/// Nests `value` in collections of kinds picked at random for each level
+pub fn nest_in_mixed(seed: u64, value: &str, collections: &[(&str, &str)]) -> String {
+    let mut randomizer = PRandomizer::new(seed);
+    let kinds: Vec<usize> = (0..nesting_depth(seed))
+        .map(|_| (randomizer.get() % collections.len() as u64) as usize)
+        .collect();
+    nest(value, kinds.len(), |level| kinds[level], collections)
+}
+
+/// Returns the position where the trailing sequence of closers starts
+fn closers_start(text: &str) -> usize {
+    text.trim_end_matches(|ch| CLOSERS.contains(ch)).len()
+}
+
+/// Adds a few closers somewhere amongst the trailing closers of `text`
+pub fn add_extra_closers(seed: u64, mut text: String) -> String {
+    let start = closers_start(&text);
+    let pos = start + (seed % (text.len() - start + 1) as u64) as usize;
+    let extra = pick_random_char(seed, CLOSERS).repeat(1 + (seed % 3) as usize);
+    text.insert_str(pos, &extra);
+    text
+}
+
+/// Removes some of the trailing closers of `text`
+pub fn remove_closers(seed: u64, mut text: String) -> String {
+    let start = closers_start(&text);
+    if start < text.len() {
+        let cnt = 1 + (seed % (text.len() - start) as u64) as usize;
+        text.truncate(text.len() - cnt);
+    }
+    text
+}
+
+/// Replaces one of the trailing closers of `text` with a closer of another kind
+pub fn mismatch_closer(seed: u64, mut text: String) -> String {
+    let start = closers_start(&text);
+    if start < text.len() {
+        let pos = start + (seed % (text.len() - start) as u64) as usize;
+        let replacement = match &text[pos..pos + 1] {
+            "]" => "}",
+            _ => "]",
+        };
+        text.replace_range(pos..pos + 1, replacement);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_extra_closers, mismatch_closer, nest, remove_closers, JSON_COLLECTIONS,
+        NESTING_AUTOMATON,
+    };
+
+    #[test]
+    fn values_are_nested() {
+        assert_eq!(nest("1", 2, |_| 0, &JSON_COLLECTIONS), "[[1]]");
+        assert_eq!(
+            nest("1", 2, |level| level, &JSON_COLLECTIONS),
+            "[{\"a\": 1}]"
+        );
+    }
+
+    #[test]
+    fn closers_are_unbalanced() {
+        let nested = String::from("[[{\"a\": 1}]]");
+        for seed in 0..10 {
+            assert!(add_extra_closers(seed, nested.clone()).len() > nested.len());
+            assert!(remove_closers(seed, nested.clone()).len() < nested.len());
+            assert_ne!(mismatch_closer(seed, nested.clone()), nested);
+        }
+    }
+
+    #[test]
+    fn nesting_reaches_thousands_of_levels() {
+        let max_depth = (1..100)
+            .map(|seed| NESTING_AUTOMATON.generate(seed))
+            .map(|value| value.chars().take_while(|ch| *ch == '[').count())
+            .max()
+            .unwrap();
+        assert!(max_depth >= 1000);
+    }
+
+    #[test]
+    fn nesting_is_seedable() {
+        assert_ne!(NESTING_AUTOMATON.generate(1), NESTING_AUTOMATON.generate(2));
+    }
+}
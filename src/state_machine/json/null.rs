@@ -33,6 +33,7 @@ lazy_static! {
         .set_func(to_capitalized)
         .set_edge(&START_WS);
     pub static ref NULL_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::null",
         initial_node: &START_NULL,
         generator: |_| String::from("null"),
     };
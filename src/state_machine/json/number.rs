@@ -1,10 +1,12 @@
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint};
 
 use super::super::helper::*;
+use super::decimal::Decimal;
+use crate::randomness::{PRandomizer, Randomizer};
 use crate::state_machine::{json::whitespace::START_WS, Automaton, AutomatonNode};
 
 lazy_static! {
-    static ref START_NUMBER: AutomatonNode<String> =
+    pub static ref START_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_edges(vec![
             (8, &WHOLE_NUMBER),
             (1, &super::null::START_NULL),
@@ -12,6 +14,8 @@ lazy_static! {
             (1, &OCTAL_NUMBER),
             (1, &NA_NUMBER),
             (1, &INFINITE_NUMBER),
+            (2, &EDGE_CASE_NUMBER),
+            (2, &NEARBY_BOUNDARY_NUMBER),
         ]);
     static ref REAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_edges(vec![
@@ -20,18 +24,12 @@ lazy_static! {
             (1, &DECIMAL_COMMA_REAL_NUMBER),
             (2, &START_WS)
         ])
-        .set_func(|_, num| num
-            .parse::<f64>()
-            .expect("Invalid automaton definition")
-            .to_string());
+        .set_func(|_, num| Decimal::parse(&num).map_or(num, |num| num.to_string()));
     static ref INCREASED_PRECISION_REAL_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new()
             .set_cycle(1)
             .set_func(|_, num| {
-                num.parse::<f64>()
-                    .expect("Invalid automaton definition")
-                    .sqrt()
-                    .to_string()
+                Decimal::parse(&num).map_or(num, |num| num.sqrt(SQRT_PRECISION).to_string())
             });
     static ref DECIMAL_COMMA_REAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_edge(&START_WS)
@@ -55,11 +53,10 @@ lazy_static! {
         AutomatonNode::<String>::new()
             .set_cycle(1)
             .set_func(|_, input| {
-                input
-                    .parse::<BigUint>()
-                    .expect("Invalid automaton definition")
-                    .pow(2)
-                    .to_string()
+                match input.parse::<BigUint>() {
+                    Ok(num) => num.pow(2).to_string(),
+                    Err(_) => input,
+                }
             });
     static ref SUM_OVERFLOWED_NATURAL_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new()
@@ -73,7 +70,7 @@ lazy_static! {
             match input.parse::<u128>() {
                 Ok(num) if num < u32::MAX as u128 => num.pow(2).to_string(),
                 Ok(_) => u64::MAX.to_string(),
-                Err(_) => panic!("Invalid automaton definition"),
+                Err(_) => input,
             }
         });
     static ref SUM_NATURAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
@@ -82,34 +79,166 @@ lazy_static! {
             match input.parse::<u128>() {
                 Ok(num) if num < u64::MAX as u128 / 2 => (num * 2).to_string(),
                 Ok(_) => u64::MAX.to_string(),
-                Err(_) => panic!("Invalid automaton definition"),
+                Err(_) => input,
             }
         });
-    static ref HEX_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
-        .set_func(|_, input| format!("{:#01x}", input.parse::<u64>().unwrap()));
-    static ref OCTAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
-        .set_func(|_, input| format!("0{:o}", input.parse::<u64>().unwrap()));
+    static ref HEX_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, input| match input.parse::<u64>() {
+            Ok(num) => format!("{:#01x}", num),
+            Err(_) => input,
+        });
+    static ref OCTAL_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, input| match input.parse::<u64>() {
+            Ok(num) => format!("0{:o}", num),
+            Err(_) => input,
+        });
     static ref NA_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_func(|_, _| String::from("NaN"));
     static ref INFINITE_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_func(|_, _| String::from("∞"));
     static ref SCI_NOTATION_REAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_edge(&START_WS)
-        .set_func(|_, num| format!("{:+e}", num.parse::<f64>().unwrap()));
+        .set_func(|_, num| Decimal::parse(&num).map_or(num, |num| num.to_scientific()));
     static ref NEGATIVE_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_func(|_, num| format!("-{}", num))
         .set_edges(vec![(1, &FINAL), (1, &REAL_NUMBER)]);
     static ref POSITIVE_NUMBER: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_edges(vec![(1, &FINAL), (1, &REAL_NUMBER)]);
+    static ref EDGE_CASE_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (1, &NEGATIVE_ZERO_NUMBER),
+            (1, &HUGE_EXPONENT_NUMBER),
+            (1, &SUBNORMAL_NUMBER),
+            (1, &INTEGER_BOUNDARY_NUMBER),
+            (1, &LONG_NUMBER),
+            (1, &LENIENT_FORMAT_NUMBER),
+        ]);
+    static ref NEGATIVE_ZERO_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &NEGATIVE_ZEROS)))
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    static ref HUGE_EXPONENT_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &HUGE_EXPONENTS)))
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    static ref SUBNORMAL_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &SUBNORMALS)))
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    static ref INTEGER_BOUNDARY_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| off_by_one(seed, pick(seed, &INTEGER_BOUNDARIES).parse().unwrap()))
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    static ref NEARBY_BOUNDARY_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(nearby_boundary)
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    static ref LONG_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| long_number(seed))
+        .set_edges(vec![(3, &FINAL), (1, &LENIENT_FORMAT_NUMBER)]);
+    pub static ref LENIENT_FORMAT_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_edges(vec![
+            (1, &TRAILING_DOT_NUMBER),
+            (1, &LEADING_ZEROS_NUMBER),
+            (1, &PLUS_SIGN_NUMBER),
+        ]);
+    static ref TRAILING_DOT_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, num| format!("{}.", num));
+    static ref LEADING_ZEROS_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, num| {
+            let zeros = "0".repeat(1 + (seed % 3) as usize);
+            match num.strip_prefix('-') {
+                Some(unsigned) => format!("-{}{}", zeros, unsigned),
+                None => format!("{}{}", zeros, num),
+            }
+        });
+    static ref PLUS_SIGN_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, num| format!("+{}", num));
     pub static ref NUMBER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::number",
         initial_node: &START_NUMBER,
         generator: |seed| seed.to_string(),
     };
 }
 
+/// Digits after the decimal point kept when increasing precision
+const SQRT_PRECISION: u32 = 16;
+
+const NEGATIVE_ZEROS: [&str; 4] = ["-0", "-0.0", "-0e0", "-0.0E-0"];
+
+/// Exponents out of the range of doubles, both overflowing and underflowing
+const HUGE_EXPONENTS: [&str; 7] = [
+    "1e309",
+    "-1e309",
+    "1e-999999",
+    "-1e-999999",
+    "1E+999999",
+    "1.7976931348623159e308",
+    "2.4703282292062327e-324",
+];
+
+/// Subnormal doubles and the ones right around the smallest normal double,
+/// some of which are known to hang naive parsers
+const SUBNORMALS: [&str; 7] = [
+    "5e-324",
+    "4.9406564584124654e-324",
+    "-5e-324",
+    "1e-320",
+    "2.2250738585072009e-308",
+    "2.2250738585072011e-308",
+    "2.2250738585072014e-308",
+];
+
+/// Integer limits which parsers commonly trip on - zero, i32, u32, 2^53 (the
+/// limit of integers representable in doubles), i64 and u64 limits
+const INTEGER_BOUNDARIES: [&str; 9] = [
+    "0",
+    "-2147483648",
+    "2147483647",
+    "4294967295",
+    "-9007199254740992",
+    "9007199254740992",
+    "-9223372036854775808",
+    "9223372036854775807",
+    "18446744073709551615",
+];
+
+/// Returns `num`, `num - 1` or `num + 1`
+fn off_by_one(seed: u64, num: BigInt) -> String {
+    (num + BigInt::from((seed / 2 % 3) as i64 - 1)).to_string()
+}
+
+/// Turns a number into a value right around one of the integer boundaries
+/// closest to it. Falls back to any of the boundaries if the number can't be
+/// parsed.
+fn nearby_boundary(seed: u64, num: String) -> String {
+    let target = Decimal::parse(num.trim()).and_then(|num| num.integer_part());
+    let mut boundaries: Vec<BigInt> = INTEGER_BOUNDARIES
+        .iter()
+        .map(|boundary| boundary.parse().unwrap())
+        .collect();
+    match target {
+        Some(target) => {
+            boundaries.sort_by_key(|boundary| (boundary - &target).magnitude().clone());
+            off_by_one(seed, boundaries[(seed % 2) as usize].clone())
+        }
+        None => off_by_one(seed, pick(seed, &INTEGER_BOUNDARIES).parse().unwrap()),
+    }
+}
+
+/// Generates a number with thousands of digits, either in the whole or the
+/// fractional part
+fn long_number(seed: u64) -> String {
+    let mut randomizer = PRandomizer::new(seed);
+    let cnt = 1000 + seed % 9000;
+    let digits: String = (0..cnt)
+        .map(|_| char::from(b'0' + (randomizer.get() % 10) as u8))
+        .collect();
+    match seed % 3 {
+        0 => format!("1{}", digits),
+        1 => format!("0.{}", digits),
+        _ => format!("1{}e-{}", digits, cnt),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::NUMBER_AUTOMATON;
+    use super::{nearby_boundary, NUMBER_AUTOMATON};
     use itertools::Itertools;
 
     // leading zeros not allowed by rfc
@@ -133,6 +262,49 @@ mod tests {
         assert!(unique_values > 15);
     }
 
+    #[test]
+    fn edge_cases_are_generated() {
+        for edge_case in ["-0", "1e309", "5e-324"] {
+            assert!(
+                TEST_FUZZ_VALUES
+                    .iter()
+                    .any(|value| value.trim_start_matches(['+', '0']) == edge_case),
+                "{} is missing",
+                edge_case
+            );
+        }
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.len() > 1000));
+    }
+
+    #[test]
+    fn real_numbers_are_turned_into_nearby_boundaries() {
+        let nearby: Vec<String> = (0..12)
+            .map(|seed| nearby_boundary(seed, String::from("2147483000.75")))
+            .collect();
+        for boundary in ["2147483646", "2147483647", "2147483648", "-1", "0", "1"] {
+            assert!(nearby.iter().any(|value| value == boundary));
+        }
+        assert!(nearby_boundary(0, String::from("-1e-999999"))
+            .parse::<i64>()
+            .is_ok());
+    }
+
+    #[test]
+    fn real_numbers_can_be_mutated() {
+        for input in [
+            "1.5",
+            "-0.25e-3",
+            "1e309",
+            "007",
+            "1e9223372036854775807",
+            "1e-9223372036854775807",
+        ] {
+            for seed in 1..300 {
+                NUMBER_AUTOMATON.traverse(String::from(input), seed);
+            }
+        }
+    }
+
     #[test]
     fn try_number() {
         for i in 1..20 {
@@ -6,11 +6,11 @@ use super::string::STRING_AUTOMATON;
 use crate::state_machine::{json::array::ARRAY_AUTOMATON, Automaton, AutomatonNode};
 
 lazy_static! {
-    static ref START_OBJECT: AutomatonNode<String> =
+    pub static ref START_OBJECT: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_edges(vec![
             (5, &ADD_ELEMENT_OBJECT),
             (1, &LARGE_OBJECT),
-            // (1, &DUPLICATE_KEYS_OBJECT),
+            (1, &DUPLICATE_KEYS_OBJECT),
             (5, &FINAL)
         ]);
     static ref ADD_ELEMENT_OBJECT: AutomatonNode<String> = AutomatonNode::<String>::new()
@@ -35,6 +35,54 @@ lazy_static! {
                 format!("{{{}, {}}}", expanded, elements)
             }
         });
+    static ref DUPLICATE_KEYS_OBJECT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_edges(vec![
+            (1, &DUPLICATE_KEY_SAME_VALUE),
+            (1, &DUPLICATE_KEY_DIFFERENT_VALUE),
+            (1, &DUPLICATE_KEY_ESCAPED),
+            (1, &DUPLICATE_KEY_NORMALIZED),
+        ]);
+    static ref DUPLICATE_KEY_SAME_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_cycle(2)
+        .set_func(|seed, text| {
+            let (text, (key, value)) = pick_member(seed, text);
+            append_member(&text, &key, &value)
+        });
+    static ref DUPLICATE_KEY_DIFFERENT_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_cycle(2)
+        .set_func(|seed, text| {
+            let (text, (key, value)) = pick_member(seed, text);
+            let new_value = different_value(seed, &value);
+            append_member(&text, &key, &new_value)
+        });
+    static ref DUPLICATE_KEY_ESCAPED: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_cycle(2)
+        .set_func(|seed, text| {
+            let (text, (key, value)) = pick_member(seed, text);
+            match escape_variant(seed, &key) {
+                Some(variant) => append_member(&text, &variant, &value),
+                None => {
+                    let text = append_member(&text, "\"a\"", &value);
+                    append_member(&text, "\"\\u0061\"", &value)
+                }
+            }
+        });
+    static ref DUPLICATE_KEY_NORMALIZED: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_cycle(2)
+        .set_func(|seed, text| {
+            let (text, (key, value)) = pick_member(seed, text);
+            match normalization_variant(&key) {
+                Some(variant) => append_member(&text, &variant, &value),
+                None => {
+                    // add a pair of keys which differ only in normalization form
+                    let (composed, base, mark) =
+                        NORMALIZATION_PAIRS[(seed % NORMALIZATION_PAIRS.len() as u64) as usize];
+                    let stem = &key[..key.len() - 1];
+                    let text = append_member(&text, &format!("{}{}\"", stem, composed), &value);
+                    append_member(&text, &format!("{}{}{}\"", stem, base, mark), &value)
+                }
+            }
+        });
     static ref ADD_BOOL: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_cycle(2)
         .set_func(|seed, text| insert_element(seed, text, &BOOL_AUTOMATON));
@@ -54,6 +102,7 @@ lazy_static! {
         .set_cycle(2)
         .set_func(|seed, text| insert_element(seed, text, &OBJECT_AUTOMATON));
     pub static ref OBJECT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::object",
         initial_node: &START_OBJECT,
         generator: |_| String::from("{}")
     };
@@ -72,11 +121,223 @@ fn insert_element(seed: u64, text: String, automaton: &Automaton<String>) -> Str
     }
 }
 
+/// Precomposed characters and their canonical decompositions into a base
+/// character and a combining mark
+const NORMALIZATION_PAIRS: [(char, char, char); 12] = [
+    ('\u{e0}', 'a', '\u{300}'),
+    ('\u{e1}', 'a', '\u{301}'),
+    ('\u{e2}', 'a', '\u{302}'),
+    ('\u{e3}', 'a', '\u{303}'),
+    ('\u{e4}', 'a', '\u{308}'),
+    ('\u{e5}', 'a', '\u{30a}'),
+    ('\u{e7}', 'c', '\u{327}'),
+    ('\u{e9}', 'e', '\u{301}'),
+    ('\u{f1}', 'n', '\u{303}'),
+    ('\u{f6}', 'o', '\u{308}'),
+    ('\u{fc}', 'u', '\u{308}'),
+    ('\u{c5}', 'A', '\u{30a}'),
+];
+
+/// Splits the top level of a JSON object into (key, value) pairs. Keys keep
+/// their quotes, values are trimmed. Malformed members are skipped.
+fn object_members(text: &str) -> Vec<(&str, &str)> {
+    let mut members = Vec::new();
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut key_start = 0;
+    let mut key: Option<&str> = None;
+    let mut value: Option<(&str, usize)> = None;
+
+    for (idx, ch) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+                if depth == 1 && value.is_none() {
+                    key = Some(&text[key_start..=idx]);
+                }
+            }
+            continue;
+        }
+        match ch {
+            '"' => {
+                in_string = true;
+                key_start = idx;
+            }
+            '{' | '[' => depth += 1,
+            ':' if depth == 1 => {
+                value = key.take().map(|key| (key, idx + 1));
+            }
+            ',' | '}' | ']' if depth == 1 => {
+                if let Some((key, start)) = value.take() {
+                    let trimmed = text[start..idx].trim();
+                    if !trimmed.is_empty() {
+                        members.push((key, trimmed));
+                    }
+                }
+                key = None;
+            }
+            _ => {}
+        }
+        if ch == '}' || ch == ']' {
+            depth = depth.saturating_sub(1);
+        }
+    }
+    members
+}
+
+/// Picks one of the members of an object. An element is inserted first if
+/// there are none.
+fn pick_member(seed: u64, text: String) -> (String, (String, String)) {
+    let text = if object_members(&text).is_empty() {
+        insert_element(seed, text, &NUMBER_AUTOMATON)
+    } else {
+        text
+    };
+    let members = object_members(&text);
+    let member = match members.len() {
+        0 => (String::from("\"\""), String::from("null")),
+        len => {
+            let (key, value) = members[(seed % len as u64) as usize];
+            (key.to_string(), value.to_string())
+        }
+    };
+    (text, member)
+}
+
+/// Generates a value of some JSON type, different from `value` if possible
+fn different_value(seed: u64, value: &str) -> String {
+    let automata: [&Automaton<String>; 4] = [
+        &NULL_AUTOMATON,
+        &BOOL_AUTOMATON,
+        &NUMBER_AUTOMATON,
+        &STRING_AUTOMATON,
+    ];
+    let mut new_value = String::from(value);
+    for automaton in automata.iter().cycle().skip((seed % 4) as usize).take(4) {
+        new_value = automaton.generate(seed);
+        if new_value != value {
+            break;
+        }
+    }
+    new_value
+}
+
+/// Adds a member at the end of an object
+fn append_member(text: &str, key: &str, value: &str) -> String {
+    match text.rfind('}') {
+        Some(close) => {
+            let elements = text[..close].trim_end();
+            let separator = if elements.ends_with('{') { "" } else { ", " };
+            format!(
+                "{}{}{}: {}{}",
+                elements,
+                separator,
+                key,
+                value,
+                &text[close..]
+            )
+        }
+        None => text.to_string(),
+    }
+}
+
+/// Encodes a character as a \u escape, using a surrogate pair if needed
+fn unicode_escape(ch: char) -> String {
+    let mut units = [0_u16; 2];
+    ch.encode_utf16(&mut units)
+        .iter()
+        .map(|unit| format!("\\u{:04x}", unit))
+        .collect()
+}
+
+/// Returns a form of a quoted JSON string with a different escaping of one
+/// of its characters, but the same decoded value
+fn escape_variant(seed: u64, key: &str) -> Option<String> {
+    let content = key.get(1..key.len().saturating_sub(1))?;
+    // (position, length, alternative form) of each character
+    let mut alternatives: Vec<(usize, usize, String)> = Vec::new();
+    let mut chars = content.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        if ch != '\\' {
+            alternatives.push((idx, ch.len_utf8(), unicode_escape(ch)));
+            continue;
+        }
+        let alternative = match chars.next() {
+            Some((_, 'u')) => {
+                let hex = content.get(idx + 2..idx + 6)?;
+                for _ in 0..4 {
+                    chars.next();
+                }
+                let decoded = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+                match decoded {
+                    Some(ch) if !ch.is_control() && ch != '"' && ch != '\\' => {
+                        (idx, 6, ch.to_string())
+                    }
+                    // flip the case of the hex digits instead
+                    _ if hex.chars().any(|digit| digit.is_ascii_alphabetic()) => {
+                        let flipped: String = hex.chars().map(char_flip_case).collect();
+                        (idx, 6, format!("\\u{}", flipped))
+                    }
+                    _ => continue,
+                }
+            }
+            Some((_, '/')) => (idx, 2, String::from("/")),
+            Some((_, escaped)) => {
+                let decoded = match escaped {
+                    '"' => '"',
+                    '\\' => '\\',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    _ => continue,
+                };
+                (idx, 2, unicode_escape(decoded))
+            }
+            None => continue,
+        };
+        alternatives.push(alternative);
+    }
+
+    if alternatives.is_empty() {
+        return None;
+    }
+    let (pos, len, alternative) = &alternatives[(seed % alternatives.len() as u64) as usize];
+    Some(format!(
+        "\"{}{}{}\"",
+        &content[..*pos],
+        alternative,
+        &content[pos + len..]
+    ))
+}
+
+/// Returns a form of a string with the first character having a canonical
+/// decomposition replaced by it, or the first decomposed pair replaced by the
+/// precomposed character
+fn normalization_variant(key: &str) -> Option<String> {
+    for (composed, base, mark) in NORMALIZATION_PAIRS.iter() {
+        if key.contains(*composed) {
+            return Some(key.replacen(*composed, &format!("{}{}", base, mark), 1));
+        }
+        let decomposed = format!("{}{}", base, mark);
+        if key.contains(&decomposed) {
+            return Some(key.replacen(&decomposed, &composed.to_string(), 1));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use crate::randomness::{PRandomizer, Randomizer};
 
-    use super::OBJECT_AUTOMATON;
+    use super::{escape_variant, normalization_variant, object_members, OBJECT_AUTOMATON};
     use itertools::Itertools;
 
     lazy_static! {
@@ -98,6 +359,69 @@ mod tests {
         assert!(unique_values > 5);
     }
 
+    fn has_duplicate_keys(text: &str) -> bool {
+        let members = object_members(text);
+        members.iter().map(|(key, _)| key).unique().count() < members.len()
+    }
+
+    #[test]
+    fn members_are_split_at_top_level() {
+        assert_eq!(
+            object_members(r#"{"a": {"b": 1, "c": [1, 2]}, "d\"": "x,y"}"#),
+            vec![
+                ("\"a\"", r#"{"b": 1, "c": [1, 2]}"#),
+                ("\"d\\\"\"", "\"x,y\"")
+            ]
+        );
+        assert!(object_members("{}").is_empty());
+    }
+
+    #[test]
+    fn escape_variants_differ_only_in_escaping() {
+        assert_eq!(
+            escape_variant(0, "\"a\""),
+            Some(String::from("\"\\u0061\""))
+        );
+        assert_eq!(
+            escape_variant(0, "\"\\u0061\""),
+            Some(String::from("\"a\""))
+        );
+        assert_eq!(escape_variant(0, "\"\\/\""), Some(String::from("\"/\"")));
+        assert_eq!(
+            escape_variant(0, "\"\\n\""),
+            Some(String::from("\"\\u000a\""))
+        );
+        assert_eq!(escape_variant(0, "\"\""), None);
+    }
+
+    #[test]
+    fn normalization_variants_switch_forms() {
+        assert_eq!(
+            normalization_variant("\"caf\u{e9}\""),
+            Some(String::from("\"cafe\u{301}\""))
+        );
+        assert_eq!(
+            normalization_variant("\"cafe\u{301}\""),
+            Some(String::from("\"caf\u{e9}\""))
+        );
+        assert_eq!(normalization_variant("\"abc\""), None);
+    }
+
+    #[test]
+    fn duplicate_keys_are_generated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| has_duplicate_keys(value)));
+    }
+
+    #[test]
+    fn duplicate_keys_are_added_to_real_objects() {
+        let input = r#"{"key": 1, "other": [1, {"key": 2}]}"#;
+        assert!((1..1000)
+            .map(|seed| OBJECT_AUTOMATON.traverse(String::from(input), seed))
+            .any(|value| has_duplicate_keys(&value)));
+    }
+
     #[test]
     fn try_object() {
         for i in 1..20 {
@@ -0,0 +1,136 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    // separators, i.e. commas between elements and colons between keys and values
+    static ref START_SEPARATOR: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &DROP_SEPARATOR),
+            (2, &DOUBLE_SEPARATOR),
+            (2, &REPLACE_SEPARATOR),
+            (3, &ADD_GAP),
+        ]);
+    static ref DROP_SEPARATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, _| String::new())
+        .set_edges(vec![(3, &FINAL), (1, &ADD_GAP)]);
+    static ref DOUBLE_SEPARATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, text| text.repeat(2))
+        .set_edges(vec![(3, &FINAL), (1, &ADD_GAP)]);
+    static ref REPLACE_SEPARATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let replacements = if text.contains(':') {
+                &COLON_REPLACEMENTS
+            } else {
+                &COMMA_REPLACEMENTS
+            };
+            String::from(replacements[(seed % replacements.len() as u64) as usize])
+        })
+        .set_edges(vec![(3, &FINAL), (1, &ADD_GAP)]);
+    // gaps, i.e. the empty space after the opening and before the closing
+    // bracket of a collection
+    static ref START_GAP: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (4, &ADD_GAP),
+            (2, &ADD_TRAILING_COMMA),
+            (1, &ADD_STRAY_BRACKET),
+        ]);
+    static ref ADD_TRAILING_COMMA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, text| format!("{},", text))
+        .set_edges(vec![(3, &FINAL), (1, &ADD_GAP)]);
+    static ref ADD_STRAY_BRACKET: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| format!("{}{}", text, ["]", "}", "[", "{"][(seed % 4) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &ADD_GAP)]);
+    static ref ADD_GAP: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let gap = EXOTIC_WHITESPACE[(seed % EXOTIC_WHITESPACE.len() as u64) as usize];
+            if (seed / EXOTIC_WHITESPACE.len() as u64).is_multiple_of(2) {
+                format!("{}{}", gap, text)
+            } else {
+                format!("{}{}", text, gap)
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &ADD_ANOTHER_GAP)]);
+    static ref ADD_ANOTHER_GAP: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    pub static ref COMMA_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::comma",
+        initial_node: &START_SEPARATOR,
+        generator: |_| String::from(","),
+    };
+    pub static ref COLON_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::colon",
+        initial_node: &START_SEPARATOR,
+        generator: |_| String::from(":"),
+    };
+    pub static ref GAP_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::gap",
+        initial_node: &START_GAP,
+        generator: |_| String::new(),
+    };
+}
+
+/// Whitespace which is not allowed between JSON tokens, but which lenient
+/// parsers or other languages accept
+const EXOTIC_WHITESPACE: [&str; 9] = [
+    "\u{A0}", "\u{2028}", "\u{2029}", "\u{C}", "\u{0}", "\u{B}", "\u{FEFF}", "\u{3000}", "\u{85}",
+];
+
+/// What a comma is replaced with, e.g. a fullwidth comma
+const COMMA_REPLACEMENTS: [&str; 5] = [";", " ", "\u{FF0C}", "\u{201A}", ",\u{0}"];
+
+/// What a colon is replaced with, e.g. as in other serialization formats
+const COLON_REPLACEMENTS: [&str; 5] = ["=", "=>", " ", "\u{FF1A}", "::"];
+
+#[cfg(test)]
+mod tests {
+    use super::{COLON_AUTOMATON, COMMA_AUTOMATON, GAP_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_COMMA_VALUES: Vec<String> = (1..500)
+            .map(|i| COMMA_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+        static ref TEST_COLON_VALUES: Vec<String> = (1..500)
+            .map(|i| COLON_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+        static ref TEST_GAP_VALUES: Vec<String> = (1..500)
+            .map(|i| GAP_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn commas_are_dropped_and_doubled() {
+        assert!(TEST_COMMA_VALUES.iter().any(|value| value.is_empty()));
+        assert!(TEST_COMMA_VALUES.iter().any(|value| value == ",,"));
+    }
+
+    #[test]
+    fn colons_are_replaced() {
+        assert!(TEST_COLON_VALUES.iter().any(|value| value == "="));
+        assert!(!TEST_COLON_VALUES.iter().any(|value| value == ";"));
+    }
+
+    #[test]
+    fn exotic_whitespace_is_inserted() {
+        for gap in ["\u{A0}", "\u{2028}", "\u{C}", "\u{0}"] {
+            assert!(TEST_COMMA_VALUES.iter().any(|value| value.contains(gap)));
+            assert!(TEST_GAP_VALUES.iter().any(|value| value.contains(gap)));
+        }
+        assert!(TEST_COLON_VALUES
+            .iter()
+            .any(|value| value.starts_with(':') && value.len() > 1));
+    }
+
+    #[test]
+    fn gaps_get_trailing_commas_and_stray_brackets() {
+        assert!(TEST_GAP_VALUES.iter().any(|value| value == ","));
+        assert!(TEST_GAP_VALUES.iter().any(|value| value == "]"));
+        assert!(TEST_GAP_VALUES.iter().any(|value| value == "{"));
+    }
+}
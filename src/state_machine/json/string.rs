@@ -1,7 +1,16 @@
 use super::super::helper::*;
 use crate::{
     configuration::Configurable,
-    state_machine::{json::whitespace::START_WS, weights::CONFIG, Automaton, AutomatonNode},
+    mutator::mutate_once,
+    state_machine::{
+        bytes::{insert_bytes_at, invalid_utf8_sequence, ENCODING_SWITCH},
+        json::whitespace::START_WS,
+        unicode::START_UNICODE,
+        uri::host::HOST_AUTOMATON,
+        weights::CONFIG,
+        Automaton, AutomatonNode,
+    },
+    tokenizer::uri_lexer::{Rule, UriLexer},
 };
 
 lazy_static! {
@@ -9,6 +18,7 @@ lazy_static! {
         AutomatonNode::<String>::new().set_edges(vec![
             (1, &EMPTY_STRING),
             (1, &LONG_STRING),
+            (1, &URI_STRING),
             (5, &NON_EMPTY_STRING)
         ]);
     static ref EMPTY_STRING: AutomatonNode<String> =
@@ -20,6 +30,7 @@ lazy_static! {
             (1, &UNQUOTED_STRING),
             (5, &ADD_VALID_CHAR),
             (5, &ADD_INVALID_CHAR),
+            (2, &START_UNICODE),
             (1, &REMOVE_CHAR),
             (1, &REPLACE_CHAR),
         ]);
@@ -30,6 +41,16 @@ lazy_static! {
             let text = String::from(&CONFIG.get_common_words()[(num % 1000) as usize]);
             format!("\"{}\"", text.repeat((num % 1024_u64) as usize))
         });
+    // URIs are fuzzed by the URI automata and other strings are replaced by
+    // one, keeping the string valid so that it reaches the URI parser
+    static ref URI_STRING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let uri = serde_json::from_str::<String>(&text)
+                .ok()
+                .and_then(|uri| mutate_once::<UriLexer, Rule>(&uri, Rule::uri, seed))
+                .unwrap_or_else(|| format!("http://{}/", HOST_AUTOMATON.generate(seed)));
+            serde_json::to_string(&uri).unwrap_or(text)
+        });
     static ref UNQUOTED_STRING: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_func(|_, text| text.replace('\"', ""));
     static ref ADD_VALID_UNESCAPED_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
@@ -63,7 +84,7 @@ lazy_static! {
         .set_func(|seed, text| { to_upper_case(seed, text) });
     static ref ADD_ENCODED_LOWER_CASE: AutomatonNode<String> =
         AutomatonNode::<String>::new().set_cycle(3);
-    static ref REMOVE_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+    pub static ref REMOVE_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_func(|seed, text| {
             if text.is_empty() {
                 text
@@ -76,7 +97,7 @@ lazy_static! {
             }
         })
         .set_cycle(1);
-    static ref REPLACE_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+    pub static ref REPLACE_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_func(|seed, text| {
             if text.is_empty() {
                 text
@@ -100,7 +121,7 @@ lazy_static! {
     static ref ADD_SURROGATE_PAIR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_cycle(2)
         .set_func(|seed, text| insert_random_surrogate_pair_in_string(seed, &text));
-    static ref ADD_INVALID_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+    pub static ref ADD_INVALID_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_edges(vec![(1, &ADD_UNESCAPED_CHAR), (1, &ADD_UNPAIRED_SURROGATE)]);
     static ref ADD_UNESCAPED_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_edges(vec![
@@ -111,14 +132,38 @@ lazy_static! {
     static ref ADD_UNESCAPED_CONTROL_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
         .set_func(|seed, text| insert_random_unescaped_control_char(seed, &text))
         .set_cycle(3);
-    static ref ADD_VALID_CHAR: AutomatonNode<String> =
-        AutomatonNode::<String>::new().set_edges(vec![
+    pub static ref ADD_VALID_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_edges(vec![
             (1, &ADD_VALID_UNESCAPED_CHAR),
             (1, &ADD_ESCAPED_CHARACTER),
             (1, &ADD_SURROGATE_PAIR),
             (1, &ADD_ENCODED)
         ]);
+    static ref START_STRING_BYTES: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| {
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            STRING_AUTOMATON.traverse(text, seed).into_bytes()
+        })
+        .set_edges(vec![
+            (3, &FINAL_BYTES),
+            (2, &ADD_INVALID_UTF8),
+            (1, &ENCODING_SWITCH),
+        ]);
+    static ref ADD_INVALID_UTF8: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, bytes| {
+            let pos = position_within_quotes(seed, &bytes);
+            insert_bytes_at(pos, bytes, &invalid_utf8_sequence(seed))
+        })
+        .set_edges(vec![(3, &FINAL_BYTES), (1, &ADD_MORE_INVALID_UTF8)]);
+    static ref ADD_MORE_INVALID_UTF8: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_cycle(1);
+    pub static ref STRING_BYTES_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "json::string_bytes",
+        initial_node: &START_STRING_BYTES,
+        generator: |seed| STRING_AUTOMATON.generate(seed).into_bytes(),
+    };
     pub static ref STRING_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json::string",
         initial_node: &START_STRING,
         generator: |seed| {
             format!(
@@ -129,11 +174,21 @@ lazy_static! {
     };
 }
 
+/// Picks a position between the quotes of a string, or anywhere if it isn't
+/// quoted
+fn position_within_quotes(seed: u64, bytes: &[u8]) -> usize {
+    match bytes {
+        [b'"', .., b'"'] => 1 + (seed % (bytes.len() as u64 - 1)) as usize,
+        _ => (seed % (bytes.len() as u64 + 1)) as usize,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::randomness::{PRandomizer, Randomizer};
 
-    use super::STRING_AUTOMATON;
+    use super::{STRING_AUTOMATON, STRING_BYTES_AUTOMATON};
+    use crate::state_machine::unicode::UNICODE_AUTOMATON;
     use itertools::Itertools;
 
     lazy_static! {
@@ -155,6 +210,45 @@ mod tests {
         assert!(unique_values > 15);
     }
 
+    #[test]
+    fn invalid_utf8_is_inserted_within_quotes() {
+        let invalid: Vec<Vec<u8>> = (1..200)
+            .map(|seed| STRING_BYTES_AUTOMATON.generate(seed))
+            .filter(|bytes| std::str::from_utf8(bytes).is_err())
+            .collect();
+        assert!(!invalid.is_empty());
+        assert!(invalid
+            .iter()
+            .any(|bytes| bytes.first() == Some(&b'"') && bytes.last() == Some(&b'"')));
+    }
+
+    #[test]
+    fn unicode_is_inserted_within_quotes() {
+        let text = String::from("\"Kiss file\"");
+        assert!((1..500)
+            .map(|i| UNICODE_AUTOMATON.traverse(text.clone(), i))
+            .all(|value| value.starts_with('"') && value.ends_with('"') && value != text));
+    }
+
+    #[test]
+    fn uris_are_fuzzed() {
+        let uri = String::from("\"https://example.com/a\"");
+        let fuzzed: Vec<String> = (1..300)
+            .map(|i| STRING_AUTOMATON.traverse(uri.clone(), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value.starts_with("\"https://")
+            && !value.contains("example.com")
+            && serde_json::from_str::<String>(value).is_ok()));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("\"http://")));
+    }
+
+    #[test]
+    fn byte_automaton_is_valid() {
+        assert_eq!(STRING_BYTES_AUTOMATON.validate(), vec![]);
+    }
+
     #[test]
     fn try_string() {
         for i in 1..20 {
@@ -0,0 +1,39 @@
+use super::collection::COLLECTION_SYNTAX;
+use crate::state_machine::json::array::START_ARRAY as JSON_ARRAY;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_ARRAY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![(3, &JSON_ARRAY), (2, &COLLECTION_SYNTAX)]);
+    pub static ref ARRAY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::array",
+        initial_node: &START_ARRAY,
+        generator: |_| String::from("[]")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ARRAY_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| ARRAY_AUTOMATON.traverse(String::from("[1, 2]"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn trailing_commas_are_added() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "[1, 2,]"));
+    }
+
+    #[test]
+    fn comments_are_added() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("/*") || value.contains("//")));
+    }
+}
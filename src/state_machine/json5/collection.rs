@@ -0,0 +1,196 @@
+use super::super::helper::*;
+use super::comment::COMMENT_AUTOMATON;
+use crate::state_machine::AutomatonNode;
+use std::ops::Range;
+
+lazy_static! {
+    // changes to the syntax JSON5 relaxes for arrays and objects - trailing
+    // commas and comments
+    pub static ref COLLECTION_SYNTAX: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &ADD_TRAILING_COMMA),
+            (2, &REMOVE_TRAILING_COMMA),
+            (1, &EXTRA_COMMAS),
+            (2, &ADD_COMMENT),
+            (1, &REMOVE_COMMENTS),
+        ]);
+    static ref ADD_TRAILING_COMMA: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, text| add_trailing_comma(text));
+    static ref REMOVE_TRAILING_COMMA: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, text| remove_trailing_comma(text));
+    static ref EXTRA_COMMAS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(extra_commas);
+    static ref ADD_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(add_comment)
+        .set_edges(vec![(3, &FINAL), (1, &ADD_ANOTHER_COMMENT)]);
+    static ref ADD_ANOTHER_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    static ref REMOVE_COMMENTS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, text| remove_comments(&text));
+}
+
+/// Positions of the syntax of a collection - its top level commas, its
+/// comments at any level and its closer. The contents of strings and
+/// comments are skipped.
+#[derive(Default)]
+struct Syntax {
+    commas: Vec<usize>,
+    comments: Vec<Range<usize>>,
+    closer: Option<usize>,
+}
+
+/// Scans the syntax of a JSON5 array or object
+fn scan(text: &str) -> Syntax {
+    let mut syntax = Syntax::default();
+    let mut depth = 0_usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, ch)) = chars.next() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+        match (ch, chars.peek().map(|(_, next)| *next)) {
+            ('"', _) | ('\'', _) => quote = Some(ch),
+            ('/', Some('/')) => {
+                let end = text[idx..]
+                    .find(['\n', '\r', '\u{2028}', '\u{2029}'])
+                    .map_or(text.len(), |end| idx + end);
+                syntax.comments.push(idx..end);
+                while chars.peek().is_some_and(|(next, _)| *next < end) {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                let end = text[idx + 2..]
+                    .find("*/")
+                    .map_or(text.len(), |end| idx + 2 + end + 2);
+                syntax.comments.push(idx..end);
+                while chars.peek().is_some_and(|(next, _)| *next < end) {
+                    chars.next();
+                }
+            }
+            ('[', _) | ('{', _) => depth += 1,
+            (']', _) | ('}', _) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    syntax.closer = Some(idx);
+                }
+            }
+            (',', _) if depth == 1 => syntax.commas.push(idx),
+            _ => {}
+        }
+    }
+    syntax
+}
+
+/// Returns the position right after the last element of a collection, and
+/// the position of its closer
+fn elements_end(text: &str) -> Option<(usize, usize)> {
+    scan(text)
+        .closer
+        .map(|closer| (text[..closer].trim_end().len(), closer))
+}
+
+/// Adds a comma after the last element, unless there is one already
+fn add_trailing_comma(mut text: String) -> String {
+    if let Some((end, _)) = elements_end(&text) {
+        if !text[..end].ends_with([',', '[', '{']) {
+            text.insert(end, ',');
+        }
+    }
+    text
+}
+
+/// Removes the comma after the last element, if there is one
+fn remove_trailing_comma(mut text: String) -> String {
+    if let Some((end, _)) = elements_end(&text) {
+        if text[..end].ends_with(',') {
+            text.remove(end - 1);
+        }
+    }
+    text
+}
+
+/// Adds commas which JSON5 doesn't allow - leading, doubled or in an empty
+/// collection
+fn extra_commas(seed: u64, mut text: String) -> String {
+    let syntax = scan(&text);
+    match (seed % 3, syntax.commas.first()) {
+        (0, Some(comma)) => text.insert(*comma, ','),
+        (1, _) if text.is_char_boundary(1) => text.insert(1, ','),
+        _ => {
+            if let Some(closer) = syntax.closer {
+                text.insert_str(closer, ",,");
+            }
+        }
+    }
+    text
+}
+
+/// Adds a comment after the opener, after a comma or before the closer
+fn add_comment(seed: u64, mut text: String) -> String {
+    let syntax = scan(&text);
+    let mut positions: Vec<usize> = syntax.commas.iter().map(|comma| comma + 1).collect();
+    positions.extend(syntax.closer);
+    if text.is_char_boundary(1) {
+        positions.push(1);
+    }
+    if let Some(pos) = positions.get((seed % positions.len().max(1) as u64) as usize) {
+        let mut comment = COMMENT_AUTOMATON.generate(seed);
+        if comment.starts_with("//") {
+            comment.push('\n');
+        }
+        text.insert_str(*pos, &comment);
+    }
+    text
+}
+
+/// Removes all comments in a collection, including nested ones
+fn remove_comments(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut from = 0;
+    for comment in scan(text).comments {
+        stripped.push_str(&text[from..comment.start]);
+        from = comment.end;
+    }
+    stripped.push_str(&text[from..]);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_trailing_comma, remove_comments, remove_trailing_comma, scan};
+
+    #[test]
+    fn top_level_syntax_is_scanned() {
+        let syntax = scan("[1, \"a,]\", [2, 3] /* , ] */, 4]");
+        assert_eq!(syntax.commas, vec![2, 9, 27]);
+        assert_eq!(syntax.comments, vec![18..27]);
+        assert_eq!(syntax.closer, Some(30));
+    }
+
+    #[test]
+    fn trailing_commas_are_toggled() {
+        assert_eq!(add_trailing_comma(String::from("[1, 2 ]")), "[1, 2, ]");
+        assert_eq!(add_trailing_comma(String::from("[1,]")), "[1,]");
+        assert_eq!(add_trailing_comma(String::from("[]")), "[]");
+        assert_eq!(remove_trailing_comma(String::from("{a: 1,\n}")), "{a: 1\n}");
+    }
+
+    #[test]
+    fn comments_are_removed() {
+        assert_eq!(
+            remove_comments("[1, // one\n '//', [/**/2]]"),
+            "[1, \n '//', [2]]"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::insert_line_terminator;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &REMOVE_COMMENT),
+            (2, &SWITCH_COMMENT_STYLE),
+            (2, &LONG_COMMENT),
+            (1, &UNTERMINATED_COMMENT),
+            (1, &NESTED_COMMENT),
+            (1, &LINE_TERMINATOR_IN_COMMENT),
+        ]);
+    static ref REMOVE_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    static ref SWITCH_COMMENT_STYLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, comment| switch_comment_style(&comment));
+    static ref LONG_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, comment| {
+            let (start, end) = comment_delimiters(&comment);
+            let body = &comment[start.len()..comment.len() - end.len()];
+            format!("{}{}{}", start, body.repeat(1 + (seed % 1024) as usize), end)
+        });
+    static ref UNTERMINATED_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, comment| match comment.strip_suffix("*/") {
+            Some(unterminated) => String::from(unterminated),
+            None => format!("/*{}", comment.get(2..).unwrap_or_default()),
+        });
+    // block comments don't nest, so the outer one ends at the first "*/"
+    static ref NESTED_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, comment| format!("/* {} */", comment));
+    // JSON5 treats U+2028 and U+2029 as line terminators, so they end line
+    // comments prematurely
+    static ref LINE_TERMINATOR_IN_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, comment| {
+            let terminated = insert_line_terminator(seed, &comment);
+            format!("{}:1", terminated)
+        });
+    pub static ref COMMENT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::comment",
+        initial_node: &START_COMMENT,
+        generator: |seed| String::from(COMMENTS[(seed % COMMENTS.len() as u64) as usize]),
+    };
+}
+
+/// Comments of either style, including empty ones and ones containing
+/// comment delimiters
+pub const COMMENTS: [&str; 6] = [
+    "// comment",
+    "/* comment */",
+    "//",
+    "/**/",
+    "/* // */",
+    "// /*",
+];
+
+/// Returns the (start, end) delimiters of a comment
+fn comment_delimiters(comment: &str) -> (&'static str, &'static str) {
+    if comment.starts_with("/*") && comment.ends_with("*/") && comment.len() >= 4 {
+        ("/*", "*/")
+    } else if comment.starts_with("//") {
+        ("//", "")
+    } else {
+        ("", "")
+    }
+}
+
+/// Turns a line comment into a block comment and vice versa
+fn switch_comment_style(comment: &str) -> String {
+    let (start, end) = comment_delimiters(comment);
+    let body = &comment[start.len()..comment.len() - end.len()];
+    match start {
+        "//" => format!("/*{}*/", body),
+        _ => format!("//{}", body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{switch_comment_style, COMMENT_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| COMMENT_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn comment_style_is_switched() {
+        assert_eq!(switch_comment_style("// a"), "/* a*/");
+        assert_eq!(switch_comment_style("/* a */"), "// a ");
+    }
+
+    #[test]
+    fn comments_are_removed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.is_empty()));
+    }
+
+    #[test]
+    fn unterminated_comments_are_generated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("/*") && !value.contains("*/")));
+    }
+}
@@ -0,0 +1,118 @@
+use super::super::helper::*;
+use super::string::{switch_quotes, START_STRING};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_KEY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &UNQUOTED_KEY),
+            (2, &QUOTED_KEY),
+            (2, &START_STRING),
+            (1, &ESCAPED_IDENTIFIER),
+            (1, &RESERVED_KEY),
+            (1, &INVALID_IDENTIFIER),
+        ]);
+    static ref UNQUOTED_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, key| unquote(&key).to_string())
+        .set_edges(vec![(3, &FINAL), (1, &ESCAPED_IDENTIFIER), (1, &INVALID_IDENTIFIER)]);
+    static ref QUOTED_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| {
+            let quoted = format!("\"{}\"", unquote(&key).replace('"', "\\\""));
+            if seed % 2 == 0 {
+                quoted
+            } else {
+                switch_quotes(&quoted)
+            }
+        });
+    // identifiers can contain unicode escapes, even in their first character
+    static ref ESCAPED_IDENTIFIER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| {
+            let key = unquote(&key);
+            let to_escape = (seed % key.chars().count().max(1) as u64) as usize;
+            key.chars()
+                .enumerate()
+                .map(|(idx, ch)| {
+                    if idx == to_escape {
+                        format!("\\u{:04x}", ch as u32)
+                    } else {
+                        String::from(ch)
+                    }
+                })
+                .collect()
+        });
+    static ref RESERVED_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(RESERVED_KEYS[(seed % RESERVED_KEYS.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &QUOTED_KEY)]);
+    static ref INVALID_IDENTIFIER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| {
+            let key = unquote(&key);
+            match seed % 3 {
+                0 => format!("{}{}", seed % 10, key),
+                _ => insert_random_char_from_range_in_string(seed, key, " -.@#\\"),
+            }
+        });
+    pub static ref KEY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::key",
+        initial_node: &START_KEY,
+        generator: |_| String::from("a"),
+    };
+}
+
+/// Keys that are reserved words in JavaScript, or that pollute the prototype
+/// of objects naively built from parsed documents
+const RESERVED_KEYS: [&str; 8] = [
+    "true",
+    "null",
+    "Infinity",
+    "NaN",
+    "class",
+    "__proto__",
+    "constructor",
+    "prototype",
+];
+
+/// Strips the quotes off a key, if there are any
+fn unquote(key: &str) -> &str {
+    match key.chars().next() {
+        Some(quote) if key.len() > 1 && (quote == '"' || quote == '\'') => key
+            .strip_prefix(quote)
+            .and_then(|inner| inner.strip_suffix(quote))
+            .unwrap_or(key),
+        _ => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unquote, KEY_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| KEY_AUTOMATON.traverse(String::from("\"key\""), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn keys_are_unquoted() {
+        assert_eq!(unquote("\"a\""), "a");
+        assert_eq!(unquote("'a'"), "a");
+        assert_eq!(unquote("a"), "a");
+        assert_eq!(unquote("\""), "\"");
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "key"));
+    }
+
+    #[test]
+    fn identifiers_are_escaped() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("\\u006")));
+    }
+
+    #[test]
+    fn prototype_pollution_keys_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "__proto__"));
+    }
+}
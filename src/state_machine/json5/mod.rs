@@ -0,0 +1,15 @@
+pub mod array;
+mod collection;
+pub mod comment;
+pub mod key;
+pub mod number;
+pub mod object;
+pub mod string;
+
+use super::helper::{insert_string_in_string, pick};
+
+/// Puts one of the JSON5 line terminators at a random position in `s`.
+/// Unlike in JSON, U+2028 and U+2029 are among them.
+fn insert_line_terminator(seed: u64, s: &str) -> String {
+    insert_string_in_string(seed, s, pick(seed, &["\n", "\r", "\u{2028}", "\u{2029}"]))
+}
@@ -0,0 +1,105 @@
+use num_bigint::{BigInt, Sign};
+
+use super::super::helper::*;
+use crate::state_machine::json::decimal::Decimal;
+use crate::state_machine::json::number::{LENIENT_FORMAT_NUMBER, START_NUMBER as JSON_NUMBER};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    pub static ref START_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (8, &JSON_NUMBER),
+            (2, &HEX_NUMBER),
+            (2, &NAMED_NUMBER),
+            (1, &LEADING_DOT_NUMBER),
+            (1, &LENIENT_FORMAT_NUMBER),
+        ]);
+    static ref HEX_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, num| to_hex(seed, &num))
+        .set_edges(vec![
+            (3, &FINAL),
+            (1, &SIGNED_NUMBER),
+            (1, &INVALID_HEX_NUMBER)
+        ]);
+    static ref INVALID_HEX_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, num| match seed % 3 {
+            0 => format!("{}.8", num),
+            1 => insert_random_char_from_range_in_string(seed, &num, "gGxX_"),
+            _ => String::from("0x"),
+        });
+    static ref NAMED_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(
+            NAMED_NUMBERS[(seed % NAMED_NUMBERS.len() as u64) as usize]
+        ))
+        .set_edges(vec![
+            (3, &FINAL),
+            (1, &SIGNED_NUMBER),
+            (1, &MISSPELLED_NUMBER)
+        ]);
+    static ref MISSPELLED_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(random_capitalization);
+    static ref SIGNED_NUMBER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, num| format!("{}{}", ["+", "-", "+-", "--"][(seed % 4) as usize], num));
+    static ref LEADING_DOT_NUMBER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, num| match Decimal::parse(&num) {
+            Some(num) if num.exponent < 0 && num.integer_part() == Some(BigInt::from(0)) => {
+                num.to_string().replacen("0.", ".", 1)
+            }
+            _ => format!(".{}e{}", seed % 1000, seed % 5),
+        });
+    pub static ref NUMBER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::number",
+        initial_node: &START_NUMBER,
+        generator: |seed| seed.to_string(),
+    };
+}
+
+/// Numbers JSON5 accepts by name - the sign is added separately
+const NAMED_NUMBERS: [&str; 2] = ["Infinity", "NaN"];
+
+/// Converts the integer part of a number to hexadecimal, with a randomly
+/// cased prefix and digits
+fn to_hex(seed: u64, num: &str) -> String {
+    let integer = Decimal::parse(num)
+        .and_then(|num| num.integer_part())
+        .unwrap_or_else(|| seed.into());
+    let (sign, magnitude) = integer.into_parts();
+    let sign = if sign == Sign::Minus { "-" } else { "" };
+    match seed % 3 {
+        0 => format!("{}0x{:x}", sign, magnitude),
+        1 => format!("{}0X{:X}", sign, magnitude),
+        _ => random_capitalization(seed, format!("{}0x{:x}", sign, magnitude)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_hex, NUMBER_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| NUMBER_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn numbers_are_converted_to_hex() {
+        assert_eq!(to_hex(0, "255"), "0xff");
+        assert_eq!(to_hex(1, "-255.9"), "-0XFF");
+    }
+
+    #[test]
+    fn named_numbers_are_generated() {
+        for name in ["Infinity", "-Infinity", "NaN"] {
+            assert!(TEST_FUZZ_VALUES.iter().any(|value| value == name));
+        }
+    }
+
+    #[test]
+    fn leading_dots_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.starts_with('.')));
+    }
+}
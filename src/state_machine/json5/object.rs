@@ -0,0 +1,39 @@
+use super::collection::COLLECTION_SYNTAX;
+use crate::state_machine::json::object::START_OBJECT as JSON_OBJECT;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_OBJECT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![(3, &JSON_OBJECT), (2, &COLLECTION_SYNTAX)]);
+    pub static ref OBJECT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::object",
+        initial_node: &START_OBJECT,
+        generator: |_| String::from("{}")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OBJECT_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| OBJECT_AUTOMATON.traverse(String::from("{a: 1,}"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn trailing_commas_are_removed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "{a: 1}"));
+    }
+
+    #[test]
+    fn json_members_are_added() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("{ \"")));
+    }
+}
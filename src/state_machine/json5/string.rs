@@ -0,0 +1,149 @@
+use super::super::helper::*;
+use super::insert_line_terminator;
+use crate::state_machine::json::string::{
+    ADD_INVALID_CHAR, ADD_VALID_CHAR, REMOVE_CHAR, REPLACE_CHAR,
+};
+use crate::state_machine::json::whitespace::START_WS;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    pub static ref START_STRING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (1, &EMPTY_STRING),
+            (5, &NON_EMPTY_STRING)
+        ]);
+    static ref EMPTY_STRING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(["\"\"", "''"][(seed % 2) as usize]));
+    // the JSON string nodes are valid here as well, as long as they go back
+    // to this node at the same depth
+    static ref NON_EMPTY_STRING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &START_WS),
+            (5, &ADD_VALID_CHAR),
+            (5, &ADD_INVALID_CHAR),
+            (1, &REMOVE_CHAR),
+            (1, &REPLACE_CHAR),
+            (2, &SWITCH_QUOTES),
+            (2, &ADD_JSON5_ESCAPE),
+            (2, &ADD_LINE_CONTINUATION),
+            (1, &ADD_LINE_TERMINATOR),
+            (1, &MISMATCH_QUOTES),
+        ]);
+    static ref SWITCH_QUOTES: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, text| switch_quotes(&text));
+    static ref ADD_JSON5_ESCAPE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let escape = json5_escape(seed);
+            insert_string_in_string(seed, &text, &escape)
+        })
+        .set_cycle(1);
+    // multi-line strings, where an escaped line terminator is skipped
+    static ref ADD_LINE_CONTINUATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let continuation = LINE_CONTINUATIONS[(seed % LINE_CONTINUATIONS.len() as u64) as usize];
+            insert_string_in_string(seed, &text, continuation)
+        })
+        .set_cycle(1);
+    // line terminators are only allowed in strings when escaped, except for
+    // U+2028 and U+2029
+    static ref ADD_LINE_TERMINATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert_line_terminator(seed, &text));
+    static ref MISMATCH_QUOTES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, mut text| {
+            match text.chars().next() {
+                Some('\'') => text.replace_range(..1, "\""),
+                Some('"') => text.replace_range(..1, "'"),
+                _ => {}
+            }
+            text
+        });
+    pub static ref STRING_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "json5::string",
+        initial_node: &START_STRING,
+        generator: |seed| String::from(["\"a\"", "'a'"][(seed % 2) as usize]),
+    };
+}
+
+/// Escaped line terminators, which JSON5 strings can span lines with
+const LINE_CONTINUATIONS: [&str; 4] = ["\\\n", "\\\r\n", "\\\u{2028}", "\\\u{2029}"];
+
+/// Returns one of the escape sequences JSON5 adds on top of JSON's
+fn json5_escape(seed: u64) -> String {
+    match seed % 6 {
+        0 => format!("\\x{:02x}", seed % 256),
+        1 => String::from("\\'"),
+        2 => String::from("\\v"),
+        3 => String::from("\\0"),
+        // any other character can be escaped as itself
+        4 => format!("\\{}", ['a', 'q', '$', '#', '\u{e9}'][(seed % 5) as usize]),
+        // escaped digits other than 0 are not allowed
+        _ => format!("\\{}", seed % 10),
+    }
+}
+
+/// Turns a double-quoted string into a single-quoted one and vice versa,
+/// escaping the new quote character and unescaping the old one
+pub fn switch_quotes(text: &str) -> String {
+    let (old, new) = match text.chars().next() {
+        Some('\'') => ('\'', '"'),
+        Some('"') => ('"', '\''),
+        _ => return text.to_string(),
+    };
+    let inner = text
+        .strip_prefix(old)
+        .map(|inner| inner.strip_suffix(old).unwrap_or(inner))
+        .unwrap_or(text);
+
+    let mut switched = String::from(new);
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some(escaped) if escaped == old => switched.push(old),
+                Some(escaped) => {
+                    switched.push('\\');
+                    switched.push(escaped);
+                }
+                None => switched.push('\\'),
+            },
+            ch if ch == new => {
+                switched.push('\\');
+                switched.push(new);
+            }
+            ch => switched.push(ch),
+        }
+    }
+    switched.push(new);
+    switched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{switch_quotes, STRING_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..1000)
+            .map(|i| STRING_AUTOMATON.generate(i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn quotes_are_switched() {
+        assert_eq!(switch_quotes("\"it's \\\"a\\\"\""), "'it\\'s \"a\"'");
+        assert_eq!(switch_quotes("'it\\'s \"a\"'"), "\"it's \\\"a\\\"\"");
+    }
+
+    #[test]
+    fn multi_line_strings_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("\\\n")));
+    }
+
+    #[test]
+    fn both_quote_styles_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.starts_with('\'')));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.starts_with('"')));
+    }
+}
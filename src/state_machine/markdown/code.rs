@@ -0,0 +1,228 @@
+use super::super::helper::{pick, FINAL};
+use super::{depth, split_runs};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_CODE_FENCE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &MISMATCH_FENCE_LENGTHS),
+            (2, &MISMATCH_FENCE_CHARS),
+            (2, &UNCLOSE_FENCE),
+            (1, &INDENT_CLOSING_FENCE),
+            (2, &INJECT_INFO_STRING),
+            (2, &INJECT_FENCE),
+        ]);
+    // a closing fence shorter than the opening one, which doesn't close the
+    // block, or a longer one, which does
+    static ref MISMATCH_FENCE_LENGTHS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, block| {
+            let (opening, content, _) = split_fence(&block);
+            let (fence, info) = fence(opening);
+            let extended = format!("{}{}", fence, fence_char(fence));
+            match seed % 3 {
+                0 => join(&format!("{}{}", extended, info), &content, Some(fence)),
+                1 => join(opening, &content, Some(&fence_char(fence).to_string().repeat(fence.len() + 1 + (seed % 100) as usize))),
+                _ => join(&format!("{}{}", fence_char(fence).to_string().repeat(depth(seed / 3)), info), &content, Some(&extended)),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CODE_FENCE)]);
+    static ref MISMATCH_FENCE_CHARS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, block| {
+            let (opening, content, _) = split_fence(&block);
+            let (fence, _) = fence(opening);
+            let other = if fence_char(fence) == '`' { "~" } else { "`" };
+            join(opening, &content, Some(&other.repeat(fence.len())))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CODE_FENCE)]);
+    // a block which swallows the rest of the document
+    static ref UNCLOSE_FENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, block| {
+            let (opening, content, _) = split_fence(&block);
+            join(opening, &content, None)
+        });
+    // closing fences indented by four spaces are part of the content
+    static ref INDENT_CLOSING_FENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, block| {
+            let (opening, content, _) = split_fence(&block);
+            let (fence, _) = fence(opening);
+            let indent = pick(seed, &["    ", "\t", "   "]);
+            join(opening, &content, Some(&format!("{}{}", indent, fence)))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CODE_FENCE)]);
+    // info strings which are invalid for backtick fences, or which end up
+    // in the class attribute of the rendered code
+    static ref INJECT_INFO_STRING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, block| {
+            let (opening, content, closing) = split_fence(&block);
+            let (fence, info) = fence(opening);
+            let injected = match seed % 4 {
+                0 => format!("{}`{}", info, info),
+                1 => String::from("\"><script>alert(1)</script>"),
+                2 => String::from("rust {.class onclick=alert(1)}"),
+                _ => "a".repeat(depth(seed / 4)),
+            };
+            join(&format!("{}{}", fence, injected), &content, closing)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CODE_FENCE)]);
+    // fences within the content, which either close the block early or are
+    // kept as content
+    static ref INJECT_FENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, block| {
+            let (opening, mut content, closing) = split_fence(&block);
+            let (fence, _) = fence(opening);
+            let injected = match seed % 3 {
+                0 => fence.to_string(),
+                1 => fence.get(1..).unwrap_or_default().to_string(),
+                _ => format!("{}{}", fence, fence_char(fence)),
+            };
+            let position = (seed / 3) as usize % (content.len() + 1);
+            content.insert(position, &injected);
+            join(opening, &content, closing)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CODE_FENCE)]);
+    static ref MORE_CODE_FENCE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref CODE_FENCE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::code_fence",
+        initial_node: &START_CODE_FENCE,
+        generator: |seed| String::from(pick(seed, &["```rust\nfn main() {}\n```", "~~~\ncode\n~~~", "````\n```\n````"])),
+    };
+
+    static ref START_CODE_SPAN: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &MISMATCH_BACKTICKS),
+            (1, &UNCLOSE_SPAN),
+            (2, &PAD_SPAN),
+            (1, &BACKTICK_RUNS),
+        ]);
+    static ref MISMATCH_BACKTICKS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, span| {
+            let (opening, inner, closing) = split_runs(&span, '`');
+            match seed % 2 {
+                0 => format!("`{}{}{}", opening, inner, closing),
+                _ => format!("{}{}`{}", opening, inner, closing),
+            }
+        });
+    static ref UNCLOSE_SPAN: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, span| {
+            let (opening, inner, _) = split_runs(&span, '`');
+            format!("{}{}", opening, inner)
+        });
+    // spaces, which are stripped once from both sides, and backticks
+    // within the content
+    static ref PAD_SPAN: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, span| {
+            let (opening, inner, closing) = split_runs(&span, '`');
+            match seed % 3 {
+                0 => format!("{} {} {}", opening, inner, closing),
+                1 => format!("{}`{}`{}", opening, inner, closing),
+                _ => format!("{}  {}", opening, closing),
+            }
+        });
+    // backtick runs of every length up to a limit, none of which match
+    static ref BACKTICK_RUNS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, span| {
+            let runs = [25, 50, 100][(seed % 3) as usize];
+            let prefix: String = (1..=runs).map(|len| format!("{}a", "`".repeat(len))).collect();
+            format!("{}{}", prefix, span)
+        });
+    pub static ref CODE_SPAN_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::code_span",
+        initial_node: &START_CODE_SPAN,
+        generator: |seed| String::from(pick(seed, &["`code`", "``a`b``", "` `` `"])),
+    };
+}
+
+/// Splits a fenced code block into its opening line, its content lines and
+/// its closing line, if it's closed
+fn split_fence(block: &str) -> (&str, Vec<&str>, Option<&str>) {
+    let mut lines: Vec<&str> = block.split('\n').collect();
+    let opening = lines.remove(0);
+    let (fence, _) = fence(opening);
+    let closing = match lines.last() {
+        Some(last) if last.trim().starts_with(fence) => lines.pop(),
+        _ => None,
+    };
+    (opening, lines, closing)
+}
+
+/// Splits the opening line of a fenced code block into its fence and info
+/// string, dropping the indentation
+fn fence(opening: &str) -> (&str, &str) {
+    let opening = opening.trim_start_matches(' ');
+    let info = opening.trim_start_matches(fence_char(opening));
+    (&opening[..opening.len() - info.len()], info)
+}
+
+fn fence_char(fence: &str) -> char {
+    if fence.starts_with('~') {
+        '~'
+    } else {
+        '`'
+    }
+}
+
+fn join(opening: &str, content: &[&str], closing: Option<&str>) -> String {
+    let mut lines = vec![opening];
+    lines.extend(content);
+    lines.extend(closing);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CODE_FENCE_AUTOMATON, CODE_SPAN_AUTOMATON};
+    use itertools::Itertools;
+
+    const BLOCK: &str = "```rust\nfn main() {}\n```";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| CODE_FENCE_AUTOMATON.traverse(String::from(BLOCK), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn fence_lengths_are_mismatched() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "````rust\nfn main() {}\n```"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "```rust\nfn main() {}\n~~~"));
+    }
+
+    #[test]
+    fn fences_are_unclosed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "```rust\nfn main() {}"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "```rust\nfn main() {}\n    ```"));
+    }
+
+    #[test]
+    fn fences_are_injected() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "```rust\n```\nfn main() {}\n```"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("```\"><script>")));
+    }
+
+    #[test]
+    fn code_spans_are_mismatched() {
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| CODE_SPAN_AUTOMATON.traverse(String::from("`code`"), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "``code`"));
+        assert!(fuzzed.iter().any(|value| value == "`code"));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value.starts_with("`a``a```a") && value.ends_with("`code`")));
+    }
+}
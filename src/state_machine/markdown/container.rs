@@ -0,0 +1,139 @@
+use super::super::helper::{pick, FINAL};
+use super::depth;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_LIST_MARKER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &NEST_LISTS),
+            (2, &REPLACE_ORDINAL),
+            (2, &SWITCH_LIST_MARKER),
+            (1, &INDENT_LIST_MARKER),
+        ]);
+    // lists nested within a single item, or across lines with increasing
+    // indentation
+    static ref NEST_LISTS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| {
+            let depth = depth(seed);
+            let bullet = marker.trim_start();
+            match seed % 2 {
+                0 => format!("{}{}", format!("{} ", bullet).repeat(depth), bullet),
+                // indentation grows quadratically, hence fewer levels
+                _ => (0..depth / 50).map(|level| format!("{}{}", "  ".repeat(level), bullet)).collect::<Vec<String>>().join(" a\n"),
+            }
+        });
+    // ordinals which are too long to start a list, or which parsers store
+    // in too small integers
+    static ref REPLACE_ORDINAL: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &["0.", "000000001.", "999999999.", "1234567890.", "-1.", "4294967296)", "2147483648."])));
+    static ref SWITCH_LIST_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| {
+            let indent = &marker[..marker.len() - marker.trim_start().len()];
+            format!("{}{}", indent, pick(seed, &["-", "+", "*", "1.", "1)"]))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LIST_MARKER)]);
+    // four spaces turn an item into an indented code block, unless it's
+    // within another item
+    static ref INDENT_LIST_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| format!("{}{}", pick(seed, &["    ", "\t", "      "]), marker))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LIST_MARKER)]);
+    static ref MORE_LIST_MARKER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref LIST_MARKER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::list_marker",
+        initial_node: &START_LIST_MARKER,
+        generator: |seed| String::from(pick(seed, &["-", "*", "1.", "  +"])),
+    };
+
+    static ref START_QUOTE_MARKER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &NEST_QUOTES),
+            (1, &INDENT_QUOTE_MARKER),
+            (1, &ESCAPE_QUOTE_MARKER),
+        ]);
+    static ref NEST_QUOTES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| {
+            let nested = pick(seed / 9, &[">", "> ", ">- "]).repeat(depth(seed));
+            format!("{}{}", nested, marker.trim_start())
+        });
+    static ref INDENT_QUOTE_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| format!("{}{}", pick(seed, &["    ", "\t"]), marker.trim_start()));
+    static ref ESCAPE_QUOTE_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| marker.replace('>', pick(seed, &["\\>", "&gt;", "\u{ff1e}"])));
+    pub static ref QUOTE_MARKER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::quote_marker",
+        initial_node: &START_QUOTE_MARKER,
+        generator: |seed| String::from(pick(seed, &[">", " >", ">>"])),
+    };
+
+    static ref START_HEADING_MARKER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &EXTEND_HEADING_MARKER),
+            (1, &INDENT_HEADING_MARKER),
+            (1, &ESCAPE_HEADING_MARKER),
+        ]);
+    // seven or more hashes make a paragraph rather than a heading
+    static ref EXTEND_HEADING_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| {
+            let hashes = marker.trim_start().len();
+            match seed % 3 {
+                0 => "#".repeat(7),
+                1 => "#".repeat(depth(seed / 3)),
+                _ => format!("{}#", "#".repeat(hashes.max(1))),
+            }
+        });
+    static ref INDENT_HEADING_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| format!("{}{}", pick(seed, &["    ", "\t", "   "]), marker.trim_start()));
+    static ref ESCAPE_HEADING_MARKER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, marker| marker.replacen('#', pick(seed, &["\\#", "&#35;", "\u{ff03}"]), 1));
+    pub static ref HEADING_MARKER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::heading_marker",
+        initial_node: &START_HEADING_MARKER,
+        generator: |seed| "#".repeat(1 + (seed % 6) as usize),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HEADING_MARKER_AUTOMATON, LIST_MARKER_AUTOMATON, QUOTE_MARKER_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| LIST_MARKER_AUTOMATON.traverse(String::from("-"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn lists_are_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("- - - - ") && value.len() > 200));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("- a\n  - a\n    - a\n")));
+    }
+
+    #[test]
+    fn list_markers_are_replaced() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "1234567890."));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "1)"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "    -"));
+    }
+
+    #[test]
+    fn quotes_are_nested() {
+        assert!((1..50)
+            .map(|i| QUOTE_MARKER_AUTOMATON.traverse(String::from(">"), i))
+            .any(|value| value.starts_with(">>>>") && value.len() > 100));
+    }
+
+    #[test]
+    fn headings_are_too_deep() {
+        assert!((1..50)
+            .map(|i| HEADING_MARKER_AUTOMATON.traverse(String::from("#"), i))
+            .any(|value| value == "#######"));
+    }
+}
@@ -0,0 +1,132 @@
+use super::super::helper::{pick, FINAL};
+use super::{depth, split_runs};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_EMPHASIS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &UNBALANCE_DELIMITERS),
+            (2, &SWITCH_DELIMITER),
+            (2, &BREAK_FLANKING),
+            (1, &LONG_DELIMITER_RUN),
+            (2, &NEST_EMPHASIS),
+        ]);
+    // opening and closing runs of different lengths, e.g. "**a*"
+    static ref UNBALANCE_DELIMITERS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let delimiter = delimiter(&text);
+            let (opening, inner, closing) = split_runs(&text, delimiter);
+            match seed % 3 {
+                0 => format!("{}{}{}{}", delimiter, opening, inner, closing),
+                1 => format!("{}{}{}{}", opening, inner, closing, delimiter),
+                _ => format!("{}{}{}", opening, inner, &closing[closing.len().min(1)..]),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_EMPHASIS)]);
+    // runs of asterisks closed by underscores or the other way around
+    static ref SWITCH_DELIMITER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let delimiter = delimiter(&text);
+            let other = if delimiter == '*' { "_" } else { "*" };
+            let (opening, inner, closing) = split_runs(&text, delimiter);
+            match seed % 2 {
+                0 => format!("{}{}{}", opening, inner, other.repeat(closing.len())),
+                _ => format!("{}{}{}", opening, inner, closing.replacen(delimiter, other, 1)),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_EMPHASIS)]);
+    // delimiter runs which are no longer left- or right-flanking, or which
+    // are within words
+    static ref BREAK_FLANKING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let delimiter = delimiter(&text);
+            let (opening, inner, closing) = split_runs(&text, delimiter);
+            match seed % 4 {
+                0 => format!("{} {}{}", opening, inner, closing),
+                1 => format!("{}{} {}", opening, inner, closing),
+                2 => format!("{}\"{}\"{}", opening, inner, closing),
+                _ => format!("snake{}case", text),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_EMPHASIS)]);
+    static ref LONG_DELIMITER_RUN: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let run = delimiter(&text).to_string().repeat(depth(seed));
+            let (_, inner, _) = split_runs(&text, delimiter(&text));
+            match seed % 2 {
+                0 => format!("{}{}{}", run, inner, run),
+                _ => format!("{}{}", run, inner),
+            }
+        });
+    // emphasis nested within itself or delimiter runs which are never
+    // matched, both of which made parsers go quadratic
+    static ref NEST_EMPHASIS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let depth = depth(seed / 3);
+            match seed % 3 {
+                0 => {
+                    let delimiters: Vec<&str> = (0..depth).map(|level| pick(level as u64, &["*", "_"])).collect();
+                    let opening: String = delimiters.iter().rev().map(|delimiter| format!("{}a ", delimiter)).collect();
+                    let closing: String = delimiters.iter().map(|delimiter| format!(" a{}", delimiter)).collect();
+                    format!("{}{}{}", opening, text, closing)
+                }
+                1 => format!("{}{}", pick(seed / 3, &["*a ", "*a_ ", "**a ", "_a "]).repeat(depth), text),
+                _ => format!("{}{}", text, pick(seed / 3, &[" a*", " a_*", " a**"]).repeat(depth)),
+            }
+        });
+    static ref MORE_EMPHASIS: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref EMPHASIS_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::emphasis",
+        initial_node: &START_EMPHASIS,
+        generator: |seed| String::from(pick(seed, &["*emphasis*", "**strong**", "_emphasis_", "***both***"])),
+    };
+}
+
+/// Returns the character delimiting emphasis, which defaults to an asterisk
+fn delimiter(text: &str) -> char {
+    if text.starts_with('_') {
+        '_'
+    } else {
+        '*'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EMPHASIS_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| EMPHASIS_AUTOMATON.traverse(String::from("**strong**"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn delimiters_are_unbalanced() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "***strong**"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "**strong*"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "**strong__"));
+    }
+
+    #[test]
+    fn flanking_is_broken() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "** strong**"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "snake**strong**case"));
+    }
+
+    #[test]
+    fn emphasis_is_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("_a *a _a ") && value.len() > 400));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("*a *a *a ")));
+    }
+}
@@ -0,0 +1,70 @@
+use super::super::helper::{pick, FINAL};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_ENTITY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &INVALID_CODE_POINT),
+            (2, &TOO_LONG_REFERENCE),
+            (2, &UNKNOWN_NAME),
+            (2, &DOUBLE_ENCODE),
+        ]);
+    // references to code points which have to be replaced by U+FFFD, or
+    // which are beyond Unicode
+    static ref INVALID_CODE_POINT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &["&#0;", "&#x0;", "&#xD800;", "&#xDFFF;", "&#1114112;", "&#x110000;", "&#9999999;", "&#xFFFFFF;", "&#65534;", "&#x202E;"])));
+    // references with more digits or characters than allowed, which aren't
+    // references
+    static ref TOO_LONG_REFERENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| match seed % 3 {
+            0 => String::from("&#00000065;"),
+            1 => String::from("&#x0000041;"),
+            _ => format!("&{};", "a".repeat(33)),
+        });
+    // names which aren't entities, or only are without the semicolon in
+    // HTML
+    static ref UNKNOWN_NAME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, entity| match seed % 4 {
+            0 => String::from(entity.trim_end_matches(';')),
+            1 => entity.to_uppercase(),
+            _ => String::from(pick(seed / 4, &["&foo;", "&notin", "&not;in;", "&ampamp;", "&#;", "&#x;"])),
+        });
+    // references which are decoded again after the ampersand is decoded
+    static ref DOUBLE_ENCODE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, entity| entity.replacen('&', "&amp;", 1))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ENTITY)]);
+    static ref MORE_ENTITY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(1);
+    pub static ref ENTITY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::entity",
+        initial_node: &START_ENTITY,
+        generator: |seed| String::from(pick(seed, &["&amp;", "&#123;", "&#x1F600;", "&lt;"])),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ENTITY_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..200)
+            .map(|i| ENTITY_AUTOMATON.traverse(String::from("&lt;"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn code_points_are_invalid() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "&#xD800;"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "&#1114112;"));
+    }
+
+    #[test]
+    fn references_are_malformed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "&lt"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "&#00000065;"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "&amp;amp;lt;"));
+    }
+}
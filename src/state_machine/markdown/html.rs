@@ -0,0 +1,96 @@
+use super::super::helper::{pick, random_capitalization, FINAL};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_HTML: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &INJECT_SCRIPT),
+            (2, &ADD_EVENT_HANDLER),
+            (2, &UNCLOSE_HTML),
+            (1, &BREAK_COMMENT),
+            (1, &RANDOM_CASE_TAG),
+        ]);
+    static ref INJECT_SCRIPT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, html| {
+            let script = pick(seed, &SCRIPTS);
+            match seed % 2 {
+                0 => String::from(script),
+                _ => format!("{}{}", html, script),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_HTML)]);
+    static ref ADD_EVENT_HANDLER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, html| {
+            let handler = pick(seed, &[" onmouseover=\"alert(1)\"", " onerror=alert(1) src=x", " style=\"background:url(javascript:alert(1))\"", "/onload=alert(1)"]);
+            match html.find('>') {
+                Some(end) => format!("{}{}{}", &html[..end], handler, &html[end..]),
+                None => format!("{}{}", html, handler),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_HTML)]);
+    // constructs which make an HTML block last until they're closed, which
+    // may be never
+    static ref UNCLOSE_HTML: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, html| {
+            let opening = pick(seed, &["<!--", "<![CDATA[", "<?php", "<!DOCTYPE", "<script>", "<pre>", "<textarea>", "<style>"]);
+            format!("{}{}", opening, html)
+        });
+    // comments which are closed differently by the HTML and CommonMark specs
+    static ref BREAK_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, html| format!("{}{}", pick(seed, &["<!-->", "<!--->", "<!-- --!>", "<!-- -- -->"]), html))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_HTML)]);
+    static ref RANDOM_CASE_TAG: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(random_capitalization)
+        .set_edges(vec![(3, &FINAL), (1, &MORE_HTML)]);
+    static ref MORE_HTML: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref HTML_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::html",
+        initial_node: &START_HTML,
+        generator: |seed| String::from(pick(seed, &["<div>html</div>", "<span>", "<!-- comment -->"])),
+    };
+}
+
+/// Raw HTML which runs scripts if it isn't sanitized
+const SCRIPTS: [&str; 6] = [
+    "<script>alert(1)</script>",
+    "<img src=x onerror=alert(1)>",
+    "<svg/onload=alert(1)>",
+    "<iframe srcdoc=\"&lt;script&gt;alert(1)&lt;/script&gt;\"></iframe>",
+    "<a href=\"javascript:alert(1)\">a</a>",
+    "<math><mtext><table><mglyph><style><img src=x onerror=alert(1)>",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::HTML_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| HTML_AUTOMATON.traverse(String::from("<div>a</div>"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn scripts_are_injected() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<script>alert(1)</script>"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<div onmouseover=\"alert(1)\">a</div>"));
+    }
+
+    #[test]
+    fn blocks_are_unclosed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<!--<div>a</div>"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<!--><div>a</div>"));
+    }
+}
@@ -0,0 +1,258 @@
+use super::super::helper::{pick, random_capitalization, FINAL};
+use super::{depth, DANGEROUS_DESTINATIONS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_LINK: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_DESTINATION),
+            (2, &NEST_BRACKETS),
+            (2, &INJECT_TITLE),
+            (1, &NEST_PARENTHESES),
+            (2, &TOGGLE_IMAGE),
+        ]);
+    static ref REPLACE_DESTINATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, link| replace_destination(&link, pick(seed, &DANGEROUS_DESTINATIONS)))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LINK)]);
+    // links within links and brackets which are never closed, both of which
+    // made parsers go quadratic
+    static ref NEST_BRACKETS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, link| {
+            let depth = depth(seed / 3);
+            match seed % 3 {
+                0 => format!("{}{}{}", "[".repeat(depth), link, "]".repeat(depth)),
+                1 => format!("{}{}", pick(seed / 3, &["[", "![", "[a](", "[a]["]).repeat(depth), link),
+                _ => format!("{}{}", link, "](a)".repeat(depth)),
+            }
+        });
+    // titles which break out of the title attribute or are never closed
+    static ref INJECT_TITLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, link| {
+            let title = pick(seed, &["\" onmouseover=\"alert(1)", "\"unclosed", "'\"><script>alert(1)</script>'", "(a\nb)"]);
+            match link.rfind(')') {
+                Some(end) => format!("{} {}{}", &link[..end], title, &link[end..]),
+                None => format!("{}(a {})", link, title),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LINK)]);
+    // balanced parentheses in destinations are only allowed up to a depth
+    static ref NEST_PARENTHESES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, link| {
+            let depth = [32, 33, depth(seed / 3)][(seed % 3) as usize];
+            replace_destination(&link, &format!("{}a{}", "(".repeat(depth), ")".repeat(depth)))
+        });
+    // links turned into images, whose destinations are loaded right away,
+    // or into references to undefined labels
+    static ref TOGGLE_IMAGE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, link| match (seed % 3, link.strip_prefix('!')) {
+            (0, Some(link)) => String::from(link),
+            (0, None) => format!("!{}", link),
+            (1, _) => match link.find("](") {
+                Some(end) => format!("{}][undefined]", &link[..end]),
+                None => format!("{}[undefined]", link),
+            },
+            _ => format!("[{}]", link),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LINK)]);
+    static ref MORE_LINK: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref LINK_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::link",
+        initial_node: &START_LINK,
+        generator: |seed| String::from(pick(seed, &["[link](https://example.com \"title\")", "![image](/a.png)", "[reference][ref]"])),
+    };
+
+    static ref START_LINK_DEFINITION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DUPLICATE_DEFINITION),
+            (2, &FOLD_LABEL),
+            (1, &MANY_DEFINITIONS),
+            (1, &LONG_LABEL),
+            (2, &REPLACE_DEFINITION_DESTINATION),
+        ]);
+    // only the first of definitions with matching labels is used, which
+    // parsers disagree on
+    static ref DUPLICATE_DEFINITION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, definition| {
+            let duplicate = replace_definition_destination(&definition, pick(seed, &DANGEROUS_DESTINATIONS));
+            match seed % 2 {
+                0 => format!("{}\n{}", definition, duplicate),
+                _ => format!("{}\n{}", duplicate, definition),
+            }
+        });
+    // labels are matched after Unicode case folding, e.g. "ẞ" matches "SS"
+    static ref FOLD_LABEL: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, definition| {
+            let (label, rest) = split_label(&definition);
+            let folded = match seed % 3 {
+                0 => random_capitalization(seed, String::from(label)),
+                1 => format!("{}\u{1e9e}", label),
+                _ => format!("  {}\t\n{}", label, label),
+            };
+            format!("[{}]{}", folded, rest)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LINK_DEFINITION)]);
+    static ref MANY_DEFINITIONS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, definition| {
+            let (label, rest) = split_label(&definition);
+            let definitions: Vec<String> = (0..depth(seed)).map(|idx| format!("[{}{}]{}", label, idx, rest)).collect();
+            format!("{}\n{}", definition, definitions.join("\n"))
+        });
+    // labels are limited to 999 characters
+    static ref LONG_LABEL: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, definition| {
+            let (_, rest) = split_label(&definition);
+            format!("[{}]{}", "a".repeat([999, 1000, depth(seed / 3)][(seed % 3) as usize]), rest)
+        });
+    static ref REPLACE_DEFINITION_DESTINATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, definition| {
+            let destination = match seed % 4 {
+                0 => "<>",
+                1 => "<a\\>",
+                _ => pick(seed / 4, &DANGEROUS_DESTINATIONS),
+            };
+            replace_definition_destination(&definition, destination)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_LINK_DEFINITION)]);
+    static ref MORE_LINK_DEFINITION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref LINK_DEFINITION_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::link_definition",
+        initial_node: &START_LINK_DEFINITION,
+        generator: |seed| String::from(pick(seed, &["[ref]: https://example.com \"title\"", "[a]: </b c>", "[Foo Bar]: /url 'title'"])),
+    };
+
+    static ref START_AUTOLINK: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REPLACE_AUTOLINK),
+            (1, &LONG_SCHEME),
+            (2, &BREAK_AUTOLINK),
+        ]);
+    static ref REPLACE_AUTOLINK: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| format!("<{}>", pick(seed, &DANGEROUS_DESTINATIONS)));
+    // schemes are limited to 32 characters
+    static ref LONG_SCHEME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, autolink| {
+            let target = autolink.trim_start_matches('<').split(':').nth(1).unwrap_or("//example.com>");
+            format!("<{}:{}>", "a".repeat([32, 33, depth(seed / 3)][(seed % 3) as usize]), target.trim_end_matches('>'))
+        });
+    // autolinks which are no longer ones, but may still be linked by
+    // extensions
+    static ref BREAK_AUTOLINK: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, autolink| match seed % 4 {
+            0 => autolink.replacen(':', ": ", 1),
+            1 => autolink.replacen('>', "<>", 1),
+            2 => format!("<a@b@{}", autolink.trim_start_matches('<')),
+            _ => autolink.trim_end_matches('>').to_string(),
+        });
+    pub static ref AUTOLINK_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::autolink",
+        initial_node: &START_AUTOLINK,
+        generator: |seed| String::from(pick(seed, &["<https://example.com>", "<mailto:a@example.com>", "<a@example.com>"])),
+    };
+}
+
+/// Replaces the destination of an inline link, along with its title. Links
+/// without one get it appended.
+fn replace_destination(link: &str, destination: &str) -> String {
+    match link.find("](") {
+        Some(start) => {
+            let end = link
+                .rfind(')')
+                .filter(|end| *end > start)
+                .unwrap_or(link.len());
+            format!(
+                "{}]({}){}",
+                &link[..start],
+                destination,
+                link.get(end + 1..).unwrap_or_default()
+            )
+        }
+        None => format!("{}({})", link, destination),
+    }
+}
+
+/// Splits a link reference definition into its label, without the
+/// brackets, and the rest
+fn split_label(definition: &str) -> (&str, &str) {
+    let definition = definition.trim_start().trim_start_matches('[');
+    match definition.find("]:") {
+        Some(end) => (&definition[..end], &definition[end + 1..]),
+        None => (definition, ":"),
+    }
+}
+
+/// Replaces the destination of a link reference definition, keeping its
+/// title
+fn replace_definition_destination(definition: &str, destination: &str) -> String {
+    let (label, rest) = split_label(definition);
+    let rest = rest.trim_start_matches(':').trim_start();
+    let title = match rest.find([' ', '\t']) {
+        Some(end) => &rest[end..],
+        None => "",
+    };
+    format!("[{}]: {}{}", label, destination, title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AUTOLINK_AUTOMATON, LINK_AUTOMATON, LINK_DEFINITION_AUTOMATON};
+    use itertools::Itertools;
+
+    const LINK: &str = "[a](https://example.com \"t\")";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| LINK_AUTOMATON.traverse(String::from(LINK), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn destinations_are_replaced() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "[a](javascript:alert(1))"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("!{}", LINK)));
+    }
+
+    #[test]
+    fn brackets_are_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("{}{}{}", "[".repeat(100), LINK, "]".repeat(100))));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("[a]({}a{})", "(".repeat(33), ")".repeat(33))));
+    }
+
+    #[test]
+    fn definitions_are_duplicated() {
+        let definition = "[ref]: /url \"title\"";
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| LINK_DEFINITION_AUTOMATON.traverse(String::from(definition), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value.starts_with(definition)
+            && value.ends_with("\n[ref]: javascript:alert(1) \"title\"")));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value.starts_with("[ref\u{1e9e}]: /url")));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value.starts_with(&format!("[{}]", "a".repeat(1000)))));
+    }
+
+    #[test]
+    fn autolinks_are_broken() {
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| AUTOLINK_AUTOMATON.traverse(String::from("<https://example.com>"), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "<javascript:alert(1)>"));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value == &format!("<{}://example.com>", "a".repeat(33))));
+    }
+}
@@ -0,0 +1,51 @@
+pub mod code;
+pub mod container;
+pub mod emphasis;
+pub mod entity;
+pub mod html;
+pub mod link;
+pub mod table;
+
+/// Levels of nesting, or numbers of repetitions, which make parsers with
+/// quadratic behavior stall or recurse too deeply
+const NESTING_DEPTHS: [usize; 3] = [100, 1000, 10000];
+
+/// Link destinations which execute scripts or leave the site, in some
+/// cases only once they're decoded
+const DANGEROUS_DESTINATIONS: [&str; 10] = [
+    "javascript:alert(1)",
+    "JaVaScRiPt:alert(1)",
+    "java\tscript:alert(1)",
+    "&#106;avascript:alert(1)",
+    "javascript&colon;alert(1)",
+    "vbscript:msgbox(1)",
+    "data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==",
+    "file:///etc/passwd",
+    "//evil.example",
+    "\\\\evil.example",
+];
+
+fn depth(seed: u64) -> usize {
+    NESTING_DEPTHS[(seed % NESTING_DEPTHS.len() as u64) as usize]
+}
+
+/// Splits text enclosed in runs of `delimiter`, e.g. "**a**", into its
+/// opening run, content and closing run
+fn split_runs(text: &str, delimiter: char) -> (&str, &str, &str) {
+    let content = text.trim_start_matches(delimiter);
+    let opening = &text[..text.len() - content.len()];
+    let inner = content.trim_end_matches(delimiter);
+    (opening, inner, &content[inner.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_runs;
+
+    #[test]
+    fn delimiter_runs_are_split() {
+        assert_eq!(split_runs("**a*b**", '*'), ("**", "a*b", "**"));
+        assert_eq!(split_runs("`a", '`'), ("`", "a", ""));
+        assert_eq!(split_runs("**", '*'), ("**", "", ""));
+    }
+}
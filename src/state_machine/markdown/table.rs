@@ -0,0 +1,140 @@
+use super::super::helper::{insert_string_in_string, pick, FINAL};
+use super::depth;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &RAGGED_ROW),
+            (1, &WIDE_TABLE),
+            (1, &MANY_ROWS),
+            (2, &BREAK_DELIMITER_ROW),
+            (2, &ESCAPE_PIPE),
+        ]);
+    // rows with more or fewer cells than the header, which are either cut
+    // or padded
+    static ref RAGGED_ROW: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            map_row(seed, &table, |seed, row| match seed % 2 {
+                0 => format!("{} x |", closed(row)),
+                _ => {
+                    let row = closed(row);
+                    let cells = row.trim_end_matches('|');
+                    match cells.rfind('|') {
+                        Some(end) => format!("{}|", &cells[..end]),
+                        None => row,
+                    }
+                }
+            })
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    // a header with many columns, which every row is padded to
+    static ref WIDE_TABLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let columns = depth(seed);
+            let mut rows: Vec<String> = table.split('\n').map(String::from).collect();
+            rows[0] = format!("{}{}", closed(&rows[0]), " a |".repeat(columns));
+            if rows.len() > 1 {
+                rows[1] = format!("{}{}", closed(&rows[1]), "-|".repeat(columns));
+            }
+            rows.join("\n")
+        });
+    static ref MANY_ROWS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let row = pick(seed / 3, &["|", "| a |", "a|b", "|||||"]);
+            format!("{}{}", table, format!("\n{}", row).repeat(depth(seed / 12)))
+        });
+    // delimiter rows which don't make a table or which parsers disagree on
+    static ref BREAK_DELIMITER_ROW: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let mut rows: Vec<&str> = table.split('\n').collect();
+            let delimiter = pick(seed, &[":-:-:|", "|::|", "| |", "|-- -|", "|---|---|---|---|"]);
+            match (seed / 5 % 2, rows.len()) {
+                (0, len) if len > 1 => {
+                    rows.remove(1);
+                }
+                (_, len) if len > 1 => rows[1] = delimiter,
+                _ => rows.push(delimiter),
+            }
+            rows.join("\n")
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    // pipes within cells, which split the cell unless they're escaped, even
+    // within code spans
+    static ref ESCAPE_PIPE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            map_row(seed, &table, |seed, row| insert_string_in_string(seed, row, pick(seed, &["\\|", "`|`", "\\\\|", "&#124;", "\\\\\\|"])))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE)]);
+    static ref MORE_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref TABLE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "markdown::table",
+        initial_node: &START_TABLE,
+        generator: |seed| String::from(pick(seed, &["| a | b |\n| --- | :-: |\n| 1 | 2 |", "a|b\n-|-\n1|2", "| a |\n|:--|"])),
+    };
+}
+
+/// Changes a row of a table other than its delimiter row
+fn map_row(seed: u64, table: &str, change: fn(u64, &str) -> String) -> String {
+    let mut rows: Vec<String> = table.split('\n').map(String::from).collect();
+    let idx = match (seed / 4) as usize % rows.len() {
+        1 => 0,
+        idx => idx,
+    };
+    rows[idx] = change(seed, &rows[idx]);
+    rows.join("\n")
+}
+
+/// Returns a row ending in a pipe
+fn closed(row: &str) -> String {
+    let row = row.trim_end();
+    if row.ends_with('|') {
+        String::from(row)
+    } else {
+        format!("{} |", row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TABLE_AUTOMATON;
+    use itertools::Itertools;
+
+    const TABLE: &str = "| a | b |\n|---|---|\n| 1 | 2 |";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| TABLE_AUTOMATON.traverse(String::from(TABLE), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn rows_are_ragged() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "| a | b |\n|---|---|\n| 1 | 2 | x |"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "| a |\n|---|---|\n| 1 | 2 |"));
+    }
+
+    #[test]
+    fn tables_are_large() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.split('\n').count() > 100));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.split('\n').next().unwrap().len() > 400));
+    }
+
+    #[test]
+    fn delimiter_rows_are_broken() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "| a | b |\n| 1 | 2 |"));
+    }
+}
@@ -1,7 +1,27 @@
+use crate::adaptive::{AdaptiveWeights, EdgeId};
 use crate::randomness::{PRandomizer, Randomizer};
+use crate::statistics::Statistics;
+use recorder::Recorder;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use weights::AdaptiveChoice;
+pub mod binary;
+pub mod bytes;
+pub mod csv;
 pub mod helper;
+pub mod http;
 pub mod json;
+pub mod json5;
+pub mod markdown;
+pub mod paths;
+pub mod protobuf;
+mod recorder;
+pub mod toml;
+pub mod unicode;
+pub mod uri;
+pub mod validator;
 pub mod weights;
+pub mod xml;
 pub mod yaml;
 
 // Transformation is an operation to be performed on a value as it's moved
@@ -21,12 +41,13 @@ type Generate<T> = fn(u64) -> T;
 /// Represents an automaton state and transitions to its adjacent states. It consists of
 /// - a transformation function that is applied to the input when the state is visited
 /// - a transition function that returns the next state to be visited (if such exists)
-/// based on a user-provided seed.
+///   based on a user-provided seed.
+///
 /// Automata constructed out of `AutomatonNode`s are:
 /// - finite - each of them is explicitly specified by the user
 /// - deterministic (meaning fuzzing is retraceable)
 /// - weighted - weight of a node transition is its likeliness to be chosen out of all
-/// adjacent nodes
+///   adjacent nodes
 /// - cyclic
 ///
 /// This struct is designed with the intention of its objects being global and immutable,
@@ -37,8 +58,15 @@ pub struct AutomatonNode<T: 'static + Clone + Sync> {
     transition: Transition<T>,
     transformation: Transformation<T>,
     cycle: usize,
+    edges: Vec<Edge<T>>,
 }
 
+/// An outgoing edge of a node as specified by the user - the weight of the
+/// transition (before it has been recalculated) and the adjacent node.
+/// It is kept alongside the transition function so that automata can be
+/// inspected without being traversed.
+type Edge<T> = (u32, &'static AutomatonNode<T>);
+
 impl<T: Clone + Sync> AutomatonNode<T> {
     /// Constructs a trivial automaton node: one without a
     /// transformation function and adjacent nodes.
@@ -47,6 +75,7 @@ impl<T: Clone + Sync> AutomatonNode<T> {
             transition: Box::new(|_| None),
             transformation: |_, input| input,
             cycle: 0,
+            edges: vec![],
         }
     }
 
@@ -60,6 +89,7 @@ impl<T: Clone + Sync> AutomatonNode<T> {
     fn set_edge(mut self, next: &'static Self) -> Self {
         self.transition = Box::new(move |_| Some(next));
         self.cycle = 0;
+        self.edges = vec![(1, next)];
         self
     }
 
@@ -82,6 +112,7 @@ impl<T: Clone + Sync> AutomatonNode<T> {
                 .collect(),
         );
         self.cycle = 0;
+        self.edges = edges;
         self
     }
 
@@ -105,10 +136,40 @@ impl<T: Clone + Sync> AutomatonNode<T> {
     fn set_cycle(mut self, cycle: usize) -> Self {
         self.cycle = cycle;
         self.transition = Box::new(|_| None);
+        self.edges = vec![];
         self
     }
 }
 
+/// Address of a state, which identifies it regardless of its value type
+type NodeAddress = usize;
+
+lazy_static! {
+    /// The ids of the states of every automaton traversed with instrumentation,
+    /// keyed by the name of the automaton and the address of its start state
+    /// (names are only unique amongst the automata of the library)
+    static ref NODE_IDS: Mutex<HashMap<(&'static str, NodeAddress), Arc<NodeIds>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// The ids of the states of an automaton, see `Automaton::nodes`
+pub(crate) struct NodeIds(HashMap<NodeAddress, usize>);
+
+impl NodeIds {
+    /// Returns the id of `node`, which must be part of the automaton
+    pub fn get<T: Clone + Sync>(&self, node: &AutomatonNode<T>) -> usize {
+        *self
+            .0
+            .get(&address(node))
+            .expect("Node is not part of the automaton!")
+    }
+}
+
+/// Returns the address of a state
+fn address<T: Clone + Sync>(node: &AutomatonNode<T>) -> NodeAddress {
+    std::ptr::from_ref(node) as NodeAddress
+}
+
 /// talasum automatons are finite state machines with a predefined set of states and edges.
 /// Each state represents an unfinished fuzz result. Each edge represents an operation
 /// to be performed on said result. The decision of what is the next edge is performed by
@@ -116,18 +177,53 @@ impl<T: Clone + Sync> AutomatonNode<T> {
 /// a pseudo-random number - quota. The quota falls into one category out of multiple pre-defined
 /// ones, each associated with an edge candidate.  
 pub struct Automaton<T: 'static + Eq + Clone + Sync> {
+    name: &'static str,
     initial_node: &'static AutomatonNode<T>,
     generator: Generate<T>,
 }
 
 impl<T: Eq + Clone + Sync> Automaton<T> {
+    /// Returns the unique name of the automaton, e.g. "json::number"
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     /// Returns the start state for the automaton
-    fn init_state(&self) -> &AutomatonNode<T> {
+    fn init_state(&self) -> &'static AutomatonNode<T> {
         self.initial_node
     }
 
+    /// Returns all states reachable from the start state in breadth-first
+    /// order. The position of a state in the list is stable as long as the
+    /// automaton definition doesn't change, so it can be used as the state's id.
+    fn nodes(&self) -> Vec<&'static AutomatonNode<T>> {
+        let mut nodes = vec![self.init_state()];
+        let mut known = HashSet::from([address(self.init_state())]);
+        let mut idx = 0;
+        while let Some(&node) = nodes.get(idx) {
+            for (_, next) in &node.edges {
+                if known.insert(address(*next)) {
+                    nodes.push(next);
+                }
+            }
+            idx += 1;
+        }
+        nodes
+    }
+
+    /// Returns the ids of the states, computed on first use and cached for
+    /// the lifetime of the program
+    fn node_ids(&self) -> Arc<NodeIds> {
+        let mut cache = NODE_IDS.lock().unwrap_or_else(|err| err.into_inner());
+        let key = (self.name, address(self.init_state()));
+        Arc::clone(cache.entry(key).or_insert_with(|| {
+            let ids = self.nodes().into_iter().enumerate();
+            Arc::new(NodeIds(ids.map(|(id, node)| (address(node), id)).collect()))
+        }))
+    }
+
     /// Returns the initial value to be fuzzed in case of generation-based fuzzing
-    fn init_value(&self, seed: u64) -> T {
+    pub(crate) fn init_value(&self, seed: u64) -> T {
         (self.generator)(seed)
     }
 
@@ -138,27 +234,82 @@ impl<T: Eq + Clone + Sync> Automaton<T> {
 
     /// Fuzzes the `input` value based on the `seed` value
     pub fn traverse(&self, input: T, seed: u64) -> T {
-        let mut node_stack: Vec<Option<&AutomatonNode<T>>> = Vec::new();
+        self.traverse_with(input, seed, |node, rand| (node.transition)(rand), |_| {})
+    }
+
+    /// Generates an initial value and fuzzes it, see `traverse_instrumented`
+    pub(crate) fn generate_instrumented(
+        &self,
+        seed: u64,
+        weights: Option<&AdaptiveWeights>,
+        trace: &mut Vec<EdgeId>,
+        statistics: Option<&mut Statistics>,
+    ) -> T {
+        self.traverse_instrumented(self.init_value(seed), seed, weights, trace, statistics)
+    }
+
+    /// Fuzzes the `input` value based on the `seed` value, same as `traverse`,
+    /// with the following optional additions:
+    /// - if `weights` are provided, the predefined weights of the edges are
+    ///   adjusted by them and each chosen edge is recorded in `trace`
+    /// - if `statistics` are provided, visited states and chosen edges are
+    ///   recorded in them
+    pub(crate) fn traverse_instrumented(
+        &self,
+        input: T,
+        seed: u64,
+        weights: Option<&AdaptiveWeights>,
+        trace: &mut Vec<EdgeId>,
+        statistics: Option<&mut Statistics>,
+    ) -> T {
+        // the ids of the states are only needed by the optional additions
+        if weights.is_none() && statistics.is_none() {
+            return self.traverse(input, seed);
+        }
+        let ids = self.node_ids();
+        let mut adaptive =
+            weights.map(|weights| AdaptiveChoice::new(self.name, &ids, weights, trace));
+        let mut recorder = statistics.map(|statistics| Recorder::new(self.name, &ids, statistics));
+        self.traverse_with(
+            input,
+            seed,
+            |node, rand| match adaptive.as_mut() {
+                Some(adaptive) => adaptive.choose(node, rand),
+                None => (node.transition)(rand),
+            },
+            |node| {
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.visit(node);
+                }
+            },
+        )
+    }
+
+    /// Fuzzes the `input` value based on the `seed` value, using `choose`
+    /// for picking the next state out of the adjacent states of a non-cyclic
+    /// state. `visit` is called for every visited state.
+    fn traverse_with<C, V>(&self, input: T, seed: u64, mut choose: C, mut visit: V) -> T
+    where
+        C: FnMut(&'static AutomatonNode<T>, u64) -> Option<&'static AutomatonNode<T>>,
+        V: FnMut(&'static AutomatonNode<T>),
+    {
+        let mut node_stack: Vec<Option<&'static AutomatonNode<T>>> = Vec::new();
         let mut seeder = PRandomizer::new(seed);
         let mut value: T = input;
-        let mut state: Option<&AutomatonNode<T>> = Some(self.init_state());
+        let mut state: Option<&'static AutomatonNode<T>> = Some(self.init_state());
         let mut rand: u64;
-        while let Some(AutomatonNode {
-            transition,
-            transformation,
-            cycle,
-        }) = state
-        {
+        while let Some(node) = state {
+            visit(node);
             rand = seeder.get();
-            value = transformation(rand, value);
+            value = (node.transformation)(rand, value);
             node_stack.push(state);
 
             // if the next node has already been visited
-            if *cycle != 0 {
-                node_stack.truncate(node_stack.len() - *cycle);
+            if node.cycle != 0 {
+                node_stack.truncate(node_stack.len() - node.cycle);
                 state = *node_stack.last().expect("Invalid automaton definition!");
             } else {
-                state = transition(rand);
+                state = choose(node, rand);
             }
         }
 
@@ -192,10 +343,12 @@ mod tests {
             .set_func(|_, text| format!("6{}", text))
             .set_cycle(2);
         static ref TEST_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "test",
             initial_node: &FIRST,
             generator: |seed| { seed.to_string() },
         };
         static ref TEST_CYCLIC_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "test_cyclic",
             initial_node: &FIRST_CYCLIC,
             generator: |seed| { seed.to_string() },
         };
@@ -316,6 +469,7 @@ mod tests {
     #[should_panic(expected = "Invalid automaton definition!")]
     fn traverse_panics_if_cycle_number_is_invalid() {
         let invalid_automata = Automaton::<String> {
+            name: "invalid",
             initial_node: &INVALID_NODE,
             generator: |seed| seed.to_string(),
         };
@@ -0,0 +1,231 @@
+use super::{Automaton, AutomatonNode};
+use crate::randomness::{PRandomizer, Randomizer};
+use std::collections::{HashMap, VecDeque};
+
+/// Limits on the paths that are enumerated through an automaton:
+/// - `max_depth` - max number of states visited along a single path
+/// - `max_unroll` - max number of times a single state can be revisited
+///   along a path, i.e. how many times each cycle can be unrolled
+#[derive(Clone, Copy, Debug)]
+pub struct PathBounds {
+    pub max_depth: usize,
+    pub max_unroll: usize,
+}
+
+impl Default for PathBounds {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_unroll: 1,
+        }
+    }
+}
+
+/// A path through an automaton is represented by the index of the chosen edge
+/// at every visited state with more than one adjacent state. States with a
+/// single adjacent state and cyclic states have only one way to proceed, so
+/// they don't take part in the representation.
+pub type Path = Vec<usize>;
+
+/// Outcome of walking an automaton along a (possibly partial) path
+enum Walk {
+    /// A final state has been reached
+    Complete,
+    /// The path ended at a state with the given number of adjacent states
+    Branch(usize),
+    /// The path exceeds the bounds or the automaton definition is invalid
+    Pruned,
+}
+
+impl<T: Eq + Clone + Sync> Automaton<T> {
+    /// Walks the automaton in the same manner as `traverse`, but instead of
+    /// relying on a seed for choosing transitions, it follows `path`.
+    /// `visit` is called for every visited state.
+    fn walk<F: FnMut(&AutomatonNode<T>)>(
+        &self,
+        path: &[usize],
+        bounds: PathBounds,
+        mut visit: F,
+    ) -> Walk {
+        let mut node_stack: Vec<&AutomatonNode<T>> = Vec::new();
+        let mut visits = HashMap::<*const AutomatonNode<T>, usize>::new();
+        let mut choices = path.iter();
+        let mut state: &AutomatonNode<T> = self.init_state();
+        let mut depth = 0;
+        loop {
+            let visited = visits.entry(state as *const AutomatonNode<T>).or_insert(0);
+            *visited += 1;
+            depth += 1;
+            if depth > bounds.max_depth || *visited > bounds.max_unroll + 1 {
+                return Walk::Pruned;
+            }
+            visit(state);
+            node_stack.push(state);
+
+            state = if state.cycle != 0 {
+                if state.cycle > node_stack.len() - 1 {
+                    return Walk::Pruned;
+                }
+                node_stack.truncate(node_stack.len() - state.cycle);
+                node_stack[node_stack.len() - 1]
+            } else {
+                match state.edges.len() {
+                    0 => return Walk::Complete,
+                    1 => state.edges[0].1,
+                    cnt => match choices.next() {
+                        Some(choice) => state.edges[*choice % cnt].1,
+                        None => return Walk::Branch(cnt),
+                    },
+                }
+            };
+        }
+    }
+
+    /// Fuzzes the `input` value by following `path` through the automaton.
+    /// The `seed` value is still used by the transformation functions.
+    pub fn traverse_path(&self, input: T, seed: u64, path: &[usize]) -> T {
+        let mut seeder = PRandomizer::new(seed);
+        let mut value = Some(input);
+        let bounds = PathBounds {
+            max_depth: usize::MAX,
+            max_unroll: usize::MAX - 1,
+        };
+        self.walk(path, bounds, |node| {
+            value = value
+                .take()
+                .map(|val| (node.transformation)(seeder.get(), val));
+        });
+        value.expect("Value is always present after a transformation")
+    }
+
+    /// Generates an initial value and fuzzes it by following `path`
+    pub fn generate_path(&self, seed: u64, path: &[usize]) -> T {
+        self.traverse_path(self.init_value(seed), seed, path)
+    }
+
+    /// Returns an iterator over all distinct paths through the automaton
+    /// that fit in `bounds`, shortest first.
+    pub fn paths(&self, bounds: PathBounds) -> Paths<'_, T> {
+        Paths {
+            automaton: self,
+            bounds,
+            pending: VecDeque::from(vec![vec![]]),
+        }
+    }
+}
+
+/// Breadth-first iterator over the paths through an automaton, see
+/// [`Automaton::paths`]
+pub struct Paths<'a, T: 'static + Eq + Clone + Sync> {
+    automaton: &'a Automaton<T>,
+    bounds: PathBounds,
+    pending: VecDeque<Path>,
+}
+
+impl<'a, T: Eq + Clone + Sync> Iterator for Paths<'a, T> {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(path) = self.pending.pop_front() {
+            match self.automaton.walk(&path, self.bounds, |_| {}) {
+                Walk::Complete => return Some(path),
+                Walk::Branch(cnt) => {
+                    for choice in 0..cnt {
+                        let mut longer = path.clone();
+                        longer.push(choice);
+                        self.pending.push_back(longer);
+                    }
+                }
+                Walk::Pruned => {}
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathBounds;
+    use crate::state_machine::{helper::FINAL, Automaton, AutomatonNode};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref FIRST: AutomatonNode<String> = AutomatonNode::<String>::new()
+            .set_func(|_, text| format!("1{}", text))
+            .set_edges(vec![(1, &SECOND), (1, &THIRD)]);
+        static ref SECOND: AutomatonNode<String> = AutomatonNode::<String>::new()
+            .set_func(|_, text| format!("2{}", text))
+            .set_edge(&THIRD);
+        static ref THIRD: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_func(|_, text| format!("3{}", text));
+        static ref FIRST_CYCLIC: AutomatonNode<String> = AutomatonNode::<String>::new()
+            .set_func(|_, text| format!("4{}", text))
+            .set_edges(vec![(10, &SECOND_CYCLIC), (1, &FINAL)]);
+        static ref SECOND_CYCLIC: AutomatonNode<String> = AutomatonNode::<String>::new()
+            .set_func(|_, text| format!("5{}", text))
+            .set_cycle(1);
+        static ref TEST_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "test",
+            initial_node: &FIRST,
+            generator: |seed| seed.to_string(),
+        };
+        static ref TEST_CYCLIC_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "test_cyclic",
+            initial_node: &FIRST_CYCLIC,
+            generator: |seed| seed.to_string(),
+        };
+    }
+
+    #[test]
+    fn all_paths_of_acyclic_automaton_are_enumerated() {
+        let values = TEST_AUTOMATON
+            .paths(PathBounds::default())
+            .map(|path| TEST_AUTOMATON.traverse_path(String::new(), 0, &path))
+            .collect_vec();
+        assert_eq!(values, vec!["321", "31"]);
+    }
+
+    #[test]
+    fn cycles_are_unrolled_up_to_the_bound() {
+        for max_unroll in 0..5 {
+            let bounds = PathBounds {
+                max_depth: 100,
+                max_unroll,
+            };
+            let values = TEST_CYCLIC_AUTOMATON
+                .paths(bounds)
+                .map(|path| TEST_CYCLIC_AUTOMATON.traverse_path(String::new(), 0, &path))
+                .collect_vec();
+            assert_eq!(values.len(), max_unroll + 1);
+            assert_eq!(values.last().unwrap(), &("45".repeat(max_unroll) + "4"));
+        }
+    }
+
+    #[test]
+    fn paths_longer_than_max_depth_are_skipped() {
+        let bounds = PathBounds {
+            max_depth: 2,
+            max_unroll: 10,
+        };
+        assert_eq!(TEST_AUTOMATON.paths(bounds).count(), 1);
+    }
+
+    #[test]
+    fn shorter_paths_are_enumerated_first() {
+        let lengths = TEST_CYCLIC_AUTOMATON
+            .paths(PathBounds::default())
+            .map(|path| path.len())
+            .collect_vec();
+        assert!(lengths.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn traversing_a_path_is_deterministic() {
+        for path in TEST_CYCLIC_AUTOMATON.paths(PathBounds::default()) {
+            assert_eq!(
+                TEST_CYCLIC_AUTOMATON.traverse_path(String::from("1"), 123, &path),
+                TEST_CYCLIC_AUTOMATON.traverse_path(String::from("1"), 123, &path)
+            );
+        }
+    }
+}
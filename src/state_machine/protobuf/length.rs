@@ -0,0 +1,90 @@
+use super::super::binary::{decode_varint, encode_varint, OVERLONG_VARINT, START_BLOB};
+use super::super::bytes::{insert_bytes, invalid_utf8_sequence};
+use super::super::helper::FINAL_BYTES;
+use super::super::unicode::UNICODE_AUTOMATON;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_LENGTH: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &SHORTER_LENGTH),
+            (2, &LONGER_LENGTH),
+            (2, &HUGE_LENGTH),
+            (1, &OVERLONG_VARINT),
+        ]);
+    // the rest of the value is then read as the next fields
+    static ref SHORTER_LENGTH: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, length| match decode_varint(&length) {
+            Some((value, _)) => encode_varint(value.saturating_sub(1 + seed % (value / 2 + 1))),
+            None => length,
+        })
+        .set_edges(vec![(4, &FINAL_BYTES), (1, &OVERLONG_VARINT)]);
+    // reaching past the end of the value, or of the message
+    static ref LONGER_LENGTH: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, length| match decode_varint(&length) {
+            Some((value, _)) => encode_varint(value + 1 + seed % 16),
+            None => length,
+        });
+    // lengths which overflow or turn negative as 32 bit integers
+    static ref HUGE_LENGTH: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| encode_varint([0x7fff_ffff, 0x8000_0000, 0xffff_ffff, 0x1_0000_0000, u64::MAX][(seed % 5) as usize]));
+    pub static ref LENGTH_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::length",
+        initial_node: &START_LENGTH,
+        generator: |seed| encode_varint(seed % 256),
+    };
+
+    // strings and bytes fields, which can't be told apart without a schema
+    static ref START_BYTES: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &MUTATE_AS_TEXT),
+            (2, &INVALID_UTF8),
+            (2, &START_BLOB),
+        ]);
+    static ref MUTATE_AS_TEXT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, value| UNICODE_AUTOMATON.traverse(String::from_utf8_lossy(&value).into_owned(), seed).into_bytes());
+    // proto3 requires strings to be valid UTF-8
+    static ref INVALID_UTF8: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, value| insert_bytes(seed, value, &invalid_utf8_sequence(seed)));
+    pub static ref BYTES_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::bytes",
+        initial_node: &START_BYTES,
+        generator: |seed| format!("talasum{}", seed).into_bytes(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BYTES_AUTOMATON, LENGTH_AUTOMATON};
+    use crate::state_machine::binary::{decode_varint, encode_varint};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<u64> = (1..200)
+            .filter_map(|i| decode_varint(&LENGTH_AUTOMATON.traverse(encode_varint(10), i)))
+            .map(|(value, _)| value)
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn lengths_are_truncated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|&value| value < 10));
+    }
+
+    #[test]
+    fn lengths_reach_past_the_end() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|&value| value > 10 && value < 30));
+        assert!(TEST_FUZZ_VALUES.contains(&0x8000_0000));
+    }
+
+    #[test]
+    fn strings_are_invalid_utf8() {
+        assert!((1..100)
+            .map(|i| BYTES_AUTOMATON.traverse(b"hello".to_vec(), i))
+            .any(|value| std::str::from_utf8(&value).is_err()));
+    }
+}
@@ -0,0 +1,123 @@
+use super::super::binary::TRUNCATE;
+use super::super::helper::FINAL_BYTES;
+use super::{encode_length_delimited, encode_tag, WireType, UNKNOWN_FIELD_NUMBERS};
+use crate::state_machine::binary::encode_varint;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    // whole fields, i.e. a tag along with its value
+    static ref START_FIELD: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &DUPLICATE_FIELD),
+            (1, &REMOVE_FIELD),
+            (1, &TRUNCATE),
+        ]);
+    // a non-repeated field given more than once, whose last value should win
+    static ref DUPLICATE_FIELD: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, field| field.repeat([2, 3, 1000][(seed % 3) as usize]));
+    static ref REMOVE_FIELD: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_func(|_, _| vec![]);
+    pub static ref FIELD_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::field",
+        initial_node: &START_FIELD,
+        generator: |seed| [encode_tag(1 + seed % 32, WireType::Varint), encode_varint(seed)].concat(),
+    };
+
+    static ref START_MESSAGE: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &APPEND_UNKNOWN_FIELD),
+            (2, &APPEND_CONFLICTING_FIELD),
+            (1, &NEST_MESSAGE),
+            (1, &TRUNCATE),
+        ]);
+    static ref APPEND_UNKNOWN_FIELD: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, message| {
+            let number = UNKNOWN_FIELD_NUMBERS[(seed % UNKNOWN_FIELD_NUMBERS.len() as u64) as usize];
+            [message, field(number, seed / 8)].concat()
+        })
+        .set_edges(vec![(3, &FINAL_BYTES), (1, &MORE_UNKNOWN_FIELDS)]);
+    static ref MORE_UNKNOWN_FIELDS: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_cycle(1);
+    // a low field number, which is likely in use, with a value of another
+    // wire type than its own
+    static ref APPEND_CONFLICTING_FIELD: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, message| [message, field(1 + seed % 4, seed / 4)].concat());
+    // messages nested beyond the recursion limit of most parsers
+    static ref NEST_MESSAGE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, message| (0..[64, 101, 1000][(seed % 3) as usize]).fold(message, |nested, _| encode_length_delimited(1, &nested)));
+    pub static ref MESSAGE_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::message",
+        initial_node: &START_MESSAGE,
+        generator: |seed| [
+            encode_tag(1, WireType::Varint),
+            encode_varint(seed),
+            encode_length_delimited(2, b"talasum"),
+        ]
+        .concat(),
+    };
+}
+
+/// Encodes a field numbered `number` with a seed-based wire type and value
+fn field(number: u64, seed: u64) -> Vec<u8> {
+    match seed % 4 {
+        0 => [encode_tag(number, WireType::Varint), encode_varint(seed)].concat(),
+        1 => [
+            encode_tag(number, WireType::Fixed64),
+            seed.to_le_bytes().to_vec(),
+        ]
+        .concat(),
+        2 => encode_length_delimited(number, b"talasum"),
+        _ => [
+            encode_tag(number, WireType::Fixed32),
+            (seed as u32).to_le_bytes().to_vec(),
+        ]
+        .concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decode_tag, WireType};
+    use super::{FIELD_AUTOMATON, MESSAGE_AUTOMATON};
+    use itertools::Itertools;
+
+    const MESSAGE: &[u8] = &[0x08, 0x96, 0x01];
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<Vec<u8>> = (1..300)
+            .map(|i| MESSAGE_AUTOMATON.traverse(MESSAGE.to_vec(), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn fields_are_duplicated() {
+        assert!((1..50)
+            .map(|i| FIELD_AUTOMATON.traverse(MESSAGE.to_vec(), i))
+            .any(|field| field == [MESSAGE, MESSAGE].concat()));
+    }
+
+    #[test]
+    fn unknown_fields_are_appended() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|message| message.starts_with(MESSAGE)
+                && decode_tag(&message[3..]).map(|(number, ..)| number) == Some(19000)));
+    }
+
+    #[test]
+    fn conflicting_fields_are_appended() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|message| message.starts_with(MESSAGE)
+                && decode_tag(&message[3..]) == Some((1, WireType::LengthDelimited, 1))));
+    }
+
+    #[test]
+    fn messages_are_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|message| message.len() > 101 * 2 && message.ends_with(MESSAGE)));
+    }
+}
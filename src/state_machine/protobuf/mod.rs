@@ -0,0 +1,100 @@
+pub mod length;
+pub mod message;
+pub mod scalar;
+pub mod tag;
+
+use super::binary::{decode_varint, encode_varint};
+
+/// Wire type of a protobuf field, i.e. how its value is encoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireType {
+    Varint = 0,
+    Fixed64 = 1,
+    LengthDelimited = 2,
+    StartGroup = 3,
+    EndGroup = 4,
+    Fixed32 = 5,
+}
+
+impl WireType {
+    pub fn from_tag(tag: u64) -> Option<Self> {
+        match tag & 7 {
+            0 => Some(Self::Varint),
+            1 => Some(Self::Fixed64),
+            2 => Some(Self::LengthDelimited),
+            3 => Some(Self::StartGroup),
+            4 => Some(Self::EndGroup),
+            5 => Some(Self::Fixed32),
+            _ => None,
+        }
+    }
+}
+
+/// Highest field number allowed by the protobuf spec
+pub const MAX_FIELD_NUMBER: u64 = (1 << 29) - 1;
+
+/// Field numbers which are invalid, reserved for the implementation or at
+/// the boundaries of tag sizes
+pub const UNKNOWN_FIELD_NUMBERS: [u64; 8] = [
+    0,
+    15,
+    16,
+    2047,
+    19000,
+    19999,
+    MAX_FIELD_NUMBER,
+    MAX_FIELD_NUMBER + 1,
+];
+
+/// Encodes the tag of a field
+pub fn encode_tag(number: u64, wire_type: WireType) -> Vec<u8> {
+    encode_varint(number << 3 | wire_type as u64)
+}
+
+/// Decodes the tag at the start of `bytes`. Outputs the field number and
+/// wire type along with the number of bytes the tag takes.
+pub fn decode_tag(bytes: &[u8]) -> Option<(u64, WireType, usize)> {
+    let (tag, size) = decode_varint(bytes)?;
+    Some((tag >> 3, WireType::from_tag(tag)?, size))
+}
+
+/// Encodes a length-delimited field, e.g. a string or a nested message
+pub fn encode_length_delimited(number: u64, payload: &[u8]) -> Vec<u8> {
+    [
+        encode_tag(number, WireType::LengthDelimited),
+        encode_varint(payload.len() as u64),
+        payload.to_vec(),
+    ]
+    .concat()
+}
+
+/// Maps a signed integer to an unsigned one as per the sint32 and sint64
+/// encoding, so that small negative numbers have short encodings
+pub fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_encoded_and_decoded() {
+        assert_eq!(encode_tag(1, WireType::Varint), vec![0x08]);
+        assert_eq!(encode_tag(16, WireType::LengthDelimited), vec![0x82, 0x01]);
+        assert_eq!(
+            decode_tag(&[0x82, 0x01, 0x00]),
+            Some((16, WireType::LengthDelimited, 2))
+        );
+        assert_eq!(decode_tag(&[0x0f]), None);
+    }
+
+    #[test]
+    fn signed_integers_are_zigzag_encoded() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(i64::MAX), u64::MAX - 1);
+        assert_eq!(zigzag(i64::MIN), u64::MAX);
+    }
+}
@@ -0,0 +1,133 @@
+use super::super::binary::{encode_varint, OVERLONG_VARINT, START_INT, START_VARINT};
+use super::super::helper::FINAL_BYTES;
+use super::zigzag;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_PROTOBUF_VARINT: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &ZIGZAG_EXTREME),
+            (3, &START_VARINT),
+            (2, &NEGATIVE_INT),
+            (1, &NON_CANONICAL_BOOL),
+        ]);
+    // extremes of sint32 and sint64, which are out of range for one another
+    static ref ZIGZAG_EXTREME: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| encode_varint(zigzag(ZIGZAG_EXTREMES[(seed % ZIGZAG_EXTREMES.len() as u64) as usize])))
+        .set_edges(vec![(4, &FINAL_BYTES), (1, &OVERLONG_VARINT)]);
+    // negative int32 and int64 values are sign-extended to 10 bytes, which
+    // some encoders get wrong by encoding only 5 of them
+    static ref NEGATIVE_INT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| match seed % 4 {
+            0 => encode_varint(u64::MAX),
+            1 => encode_varint(i64::from(i32::MIN) as u64),
+            2 => encode_varint(u64::from(u32::MAX)),
+            _ => encode_varint(i64::MIN as u64),
+        });
+    // bools and enums are varints too, and may be decoded into out of range
+    // values
+    static ref NON_CANONICAL_BOOL: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, _| encode_varint([2, 255, 256, 1 << 32][(seed % 4) as usize]));
+    pub static ref VARINT_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::varint",
+        initial_node: &START_PROTOBUF_VARINT,
+        generator: |seed| encode_varint(zigzag(seed as i64)),
+    };
+
+    static ref START_FIXED: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &START_INT),
+            (2, &SPECIAL_FLOAT),
+        ]);
+    // fixed32 and fixed64 fields are also used for floats and doubles
+    static ref SPECIAL_FLOAT: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, value| special_float(seed, value.len()));
+    pub static ref FIXED_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::fixed",
+        initial_node: &START_FIXED,
+        generator: |seed| seed.to_le_bytes().to_vec(),
+    };
+}
+
+/// Signed values at the limits of sint32 and sint64
+const ZIGZAG_EXTREMES: [i64; 8] = [
+    i64::MIN,
+    i64::MAX,
+    i32::MIN as i64,
+    i32::MAX as i64,
+    i32::MIN as i64 - 1,
+    i32::MAX as i64 + 1,
+    -1,
+    0,
+];
+
+/// Picks a special float or double, e.g. NaN or a subnormal, encoded in
+/// `width` bytes as per its wire type. Any other width is kept.
+fn special_float(seed: u64, width: usize) -> Vec<u8> {
+    match width {
+        4 => [
+            f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            -0.0,
+            f32::MIN_POSITIVE / 2.0,
+            f32::MAX,
+        ][(seed % 6) as usize]
+            .to_le_bytes()
+            .to_vec(),
+        _ => {
+            let double = [
+                f64::NAN,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                -0.0,
+                f64::MIN_POSITIVE / 2.0,
+                f64::MAX,
+            ][(seed % 6) as usize];
+            double.to_le_bytes()[..width.min(8)].to_vec()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FIXED_AUTOMATON, VARINT_AUTOMATON};
+    use crate::state_machine::binary::{decode_varint, encode_varint};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<Vec<u8>> = (1..300)
+            .map(|i| VARINT_AUTOMATON.traverse(encode_varint(150), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn zigzag_extremes_are_produced() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| decode_varint(value) == Some((u64::MAX, 10))));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| decode_varint(value) == Some((u64::from(u32::MAX), 5))));
+    }
+
+    #[test]
+    fn varints_are_overlong() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| {
+            let (decoded, size) = decode_varint(value).unwrap_or((0, 0));
+            size > encode_varint(decoded).len()
+        }));
+    }
+
+    #[test]
+    fn floats_are_special() {
+        assert!((1..100)
+            .map(|i| FIXED_AUTOMATON.traverse(1.5_f32.to_le_bytes().to_vec(), i))
+            .any(|value| value == f32::INFINITY.to_le_bytes().to_vec()));
+        assert!((1..100)
+            .map(|i| FIXED_AUTOMATON.traverse(1.5_f64.to_le_bytes().to_vec(), i))
+            .all(|value| value.len() >= 7 && value.len() <= 9));
+    }
+}
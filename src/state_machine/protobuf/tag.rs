@@ -0,0 +1,86 @@
+use super::super::binary::OVERLONG_VARINT;
+use super::super::helper::FINAL_BYTES;
+use super::{decode_tag, encode_tag, WireType, UNKNOWN_FIELD_NUMBERS};
+use crate::state_machine::binary::encode_varint;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TAG: AutomatonNode<Vec<u8>> =
+        AutomatonNode::<Vec<u8>>::new().set_edges(vec![
+            (3, &WRONG_WIRE_TYPE),
+            (2, &UNKNOWN_FIELD_NUMBER),
+            (1, &INVALID_WIRE_TYPE),
+            (1, &OVERLONG_VARINT),
+        ]);
+    // the value is then read as the wrong type, e.g. a string as a varint
+    static ref WRONG_WIRE_TYPE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, tag| match decode_tag(&tag) {
+            Some((number, wire_type, _)) => {
+                let wire_types = [
+                    WireType::Varint,
+                    WireType::Fixed64,
+                    WireType::LengthDelimited,
+                    WireType::Fixed32,
+                    WireType::StartGroup,
+                    WireType::EndGroup,
+                ];
+                let others: Vec<&WireType> = wire_types.iter().filter(|&&other| other != wire_type).collect();
+                encode_tag(number, *others[(seed % others.len() as u64) as usize])
+            }
+            None => tag,
+        })
+        .set_edges(vec![(4, &FINAL_BYTES), (1, &OVERLONG_VARINT)]);
+    static ref UNKNOWN_FIELD_NUMBER: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, tag| match decode_tag(&tag) {
+            Some((_, wire_type, _)) => encode_tag(UNKNOWN_FIELD_NUMBERS[(seed % UNKNOWN_FIELD_NUMBERS.len() as u64) as usize], wire_type),
+            None => tag,
+        })
+        .set_edges(vec![(4, &FINAL_BYTES), (1, &OVERLONG_VARINT)]);
+    // wire types 6 and 7 aren't defined
+    static ref INVALID_WIRE_TYPE: AutomatonNode<Vec<u8>> = AutomatonNode::<Vec<u8>>::new()
+        .set_func(|seed, tag| match decode_tag(&tag) {
+            Some((number, _, _)) => encode_varint(number << 3 | (6 + seed % 2)),
+            None => tag,
+        });
+    pub static ref TAG_AUTOMATON: Automaton<Vec<u8>> = Automaton::<Vec<u8>> {
+        name: "protobuf::tag",
+        initial_node: &START_TAG,
+        generator: |seed| encode_tag(1 + seed % 32, WireType::Varint),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decode_tag, encode_tag, WireType};
+    use super::TAG_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<Vec<u8>> = (1..200)
+            .map(|i| TAG_AUTOMATON.traverse(encode_tag(1, WireType::Varint), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn wire_types_are_changed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|tag| decode_tag(tag) == Some((1, WireType::LengthDelimited, 1))));
+        assert!(TEST_FUZZ_VALUES.iter().any(|tag| tag == &vec![0x0e]));
+    }
+
+    #[test]
+    fn field_numbers_are_unknown() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|tag| decode_tag(tag).map(|(number, ..)| number) == Some(19000)));
+        assert!(TEST_FUZZ_VALUES.iter().any(|tag| tag == &vec![0x00]));
+    }
+
+    #[test]
+    fn tags_are_overlong() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|tag| tag == &vec![0x88, 0x00]));
+    }
+}
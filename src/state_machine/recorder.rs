@@ -0,0 +1,43 @@
+use super::{AutomatonNode, NodeIds};
+use crate::statistics::Statistics;
+
+/// Records the states visited during a single traversal of an automaton,
+/// as well as the edges taken between them, in a [`Statistics`] instance.
+pub(crate) struct Recorder<'s, T: 'static + Clone + Sync> {
+    automaton: &'static str,
+    ids: &'s NodeIds,
+    statistics: &'s mut Statistics,
+    previous: Option<(usize, &'static AutomatonNode<T>)>,
+}
+
+impl<'s, T: 'static + Clone + Sync> Recorder<'s, T> {
+    /// `ids` are the ids of the states of the automaton named `automaton`
+    pub fn new(automaton: &'static str, ids: &'s NodeIds, statistics: &'s mut Statistics) -> Self {
+        Self {
+            automaton,
+            ids,
+            statistics,
+            previous: None,
+        }
+    }
+
+    /// Records a visit of `node` and how it has been reached
+    pub fn visit(&mut self, node: &'static AutomatonNode<T>) {
+        let node_id = self.ids.get(node);
+        self.statistics.record_visit(self.automaton, node_id);
+
+        if let Some((previous_id, previous)) = self.previous {
+            if previous.cycle != 0 {
+                self.statistics.record_cycle(self.automaton, previous_id);
+            } else if let Some(edge) = previous
+                .edges
+                .iter()
+                .position(|(_, next)| std::ptr::eq(*next, node))
+            {
+                self.statistics
+                    .record_edge(self.automaton, previous_id, edge);
+            }
+        }
+        self.previous = Some((node_id, node));
+    }
+}
@@ -0,0 +1,161 @@
+use super::super::helper::{pick, FINAL};
+use super::{depth, inner};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_ARRAY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &ARRAY_COMMAS),
+            (2, &MIX_TYPES),
+            (2, &MULTILINE_ARRAY),
+            (1, &DEEP_ARRAY),
+        ]);
+    // trailing commas are allowed, but not empty elements
+    static ref ARRAY_COMMAS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, array| {
+            let elements = inner(&array, "[", "]");
+            match seed % 4 {
+                0 => format!("[{},]", elements),
+                1 => format!("[{},,]", elements),
+                2 => format!("[,{}]", elements),
+                _ => format!("[{}]", elements.replace(',', ",,")),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ARRAY)]);
+    // arrays may mix types since TOML 1.0, which older parsers reject
+    static ref MIX_TYPES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, array| {
+            let elements = inner(&array, "[", "]");
+            let other = pick(seed, &["\"a\"", "1.5", "true", "1979-05-27", "[]", "{ x = 1 }"]);
+            if elements.is_empty() {
+                format!("[{}]", other)
+            } else {
+                format!("[{}, {}]", elements, other)
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ARRAY)]);
+    // elements split over lines, with comments in between
+    static ref MULTILINE_ARRAY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, array| {
+            let elements = inner(&array, "[", "]");
+            let separator = pick(seed, &[",\n  ", ", # comment\n", "\n,", ",\r\n"]);
+            format!("[\n  {}\n]", elements.split(',').map(str::trim).collect::<Vec<&str>>().join(separator))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ARRAY)]);
+    static ref DEEP_ARRAY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, array| {
+            let depth = depth(seed);
+            format!("{}{}{}", "[".repeat(depth), array, "]".repeat(depth))
+        });
+    static ref MORE_ARRAY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref ARRAY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::array",
+        initial_node: &START_ARRAY,
+        generator: |seed| String::from(pick(seed, &["[ 1, 2, 3 ]", "[ \"red\", \"yellow\" ]", "[ [ 1, 2 ], [\"a\"] ]", "[]"])),
+    };
+
+    static ref START_INLINE_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &INLINE_TABLE_COMMAS),
+            (2, &NEWLINE_IN_INLINE_TABLE),
+            (3, &DUPLICATE_INLINE_KEY),
+            (1, &DEEP_INLINE_TABLE),
+        ]);
+    // trailing commas are only allowed since TOML 1.1
+    static ref INLINE_TABLE_COMMAS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let pairs = inner(&table, "{", "}");
+            match seed % 3 {
+                0 => format!("{{ {}, }}", pairs),
+                1 => format!("{{ {},, }}", pairs),
+                _ => format!("{{ , {} }}", pairs),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INLINE_TABLE)]);
+    // inline tables are meant to be on a single line
+    static ref NEWLINE_IN_INLINE_TABLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let pairs = inner(&table, "{", "}");
+            match seed % 3 {
+                0 => format!("{{\n  {}\n}}", pairs.replace(", ", ",\n  ")),
+                1 => format!("{{ {} # comment\n}}", pairs),
+                _ => format!("{{ {}\r\n}}", pairs),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INLINE_TABLE)]);
+    // keys defined twice, directly or through dotted keys
+    static ref DUPLICATE_INLINE_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let pairs = inner(&table, "{", "}");
+            let first = pairs.split(',').next().unwrap_or("").trim();
+            let key = first.split('=').next().unwrap_or("").trim();
+            let key = if key.is_empty() { "a" } else { key };
+            let added = match seed % 3 {
+                0 if !first.is_empty() => String::from(first),
+                1 => format!("{}.dotted = 1", key),
+                _ => format!("{} = {{}}", key),
+            };
+            if pairs.is_empty() {
+                format!("{{ {} = 1, {} }}", key, added)
+            } else {
+                format!("{{ {}, {} }}", pairs, added)
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INLINE_TABLE)]);
+    static ref DEEP_INLINE_TABLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, table| {
+            let depth = depth(seed);
+            format!("{}{}{}", "{ a = ".repeat(depth), table, " }".repeat(depth))
+        });
+    static ref MORE_INLINE_TABLE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref INLINE_TABLE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::inline_table",
+        initial_node: &START_INLINE_TABLE,
+        generator: |seed| String::from(pick(seed, &["{ x = 1, y = 2 }", "{ first = \"Tom\", last = \"Preston-Werner\" }", "{ type.name = \"pug\" }", "{}"])),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ARRAY_AUTOMATON, INLINE_TABLE_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| INLINE_TABLE_AUTOMATON.traverse(String::from("{ x = 1, y = 2 }"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn arrays_are_changed() {
+        let fuzzed: Vec<String> = (1..200)
+            .map(|i| ARRAY_AUTOMATON.traverse(String::from("[ 1, 2 ]"), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "[1, 2,]"));
+        assert!(fuzzed.iter().any(|value| value == "[1,, 2]"));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value == &format!("{}[ 1, 2 ]{}", "[".repeat(100), "]".repeat(100))));
+    }
+
+    #[test]
+    fn trailing_commas_are_added() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "{ x = 1, y = 2, }"));
+    }
+
+    #[test]
+    fn keys_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "{ x = 1, y = 2, x = 1 }"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "{ x = 1, y = 2, x.dotted = 1 }"));
+    }
+}
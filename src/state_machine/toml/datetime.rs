@@ -0,0 +1,160 @@
+use super::super::helper::{pick, FINAL};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_DATETIME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &CHANGE_OFFSET),
+            (2, &CHANGE_SEPARATOR),
+            (3, &LOCAL_VARIANT),
+            (3, &INVALID_DATE),
+            (2, &INVALID_TIME),
+        ]);
+    // offsets which are lower case, out of range or without their colon
+    static ref CHANGE_OFFSET: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, datetime| {
+            let (date, time, _) = split_datetime(&datetime);
+            let offset = pick(seed, &["Z", "z", "+00:00", "-00:00", "+23:59", "+24:00", "-12:60", "+0530", "+05"]);
+            format!("{}T{}{}", date.unwrap_or("1979-05-27"), time.unwrap_or("07:32:00"), offset)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DATETIME)]);
+    // dates and times may be separated by a space, but by nothing else
+    static ref CHANGE_SEPARATOR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, datetime| {
+            let (date, time, offset) = split_datetime(&datetime);
+            let separator = pick(seed, &["t", " ", "  ", "\t", "_", ""]);
+            format!("{}{}{}{}", date.unwrap_or("1979-05-27"), separator, time.unwrap_or("07:32:00"), offset)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DATETIME)]);
+    // local date-times, local dates and local times
+    static ref LOCAL_VARIANT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, datetime| {
+            let (date, time, _) = split_datetime(&datetime);
+            let date = date.unwrap_or("1979-05-27");
+            let time = time.unwrap_or("07:32:00");
+            match seed % 4 {
+                0 => format!("{}T{}", date, time),
+                1 => String::from(date),
+                2 => String::from(time),
+                // an offset without a date
+                _ => format!("{}Z", time),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DATETIME)]);
+    // dates which are well formed but which don't exist
+    static ref INVALID_DATE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, datetime| {
+            let (_, time, offset) = split_datetime(&datetime);
+            let date = pick(seed, &[
+                "2023-02-29", "1900-02-29", "1979-13-01", "1979-00-10", "1979-04-31",
+                "1979-05-00", "0000-01-01", "979-05-27", "1979-5-27", "+1979-05-27",
+            ]);
+            match time {
+                Some(time) => format!("{}T{}{}", date, time, offset),
+                None => String::from(date),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DATETIME)]);
+    // leap seconds, hours out of range and fractions of a second beyond
+    // nanoseconds
+    static ref INVALID_TIME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, datetime| {
+            let (date, _, offset) = split_datetime(&datetime);
+            let time = pick(seed, &[
+                "24:00:00", "23:59:60", "23:60:00", "07:32", "7:32:00", "07:32:00.",
+                "07:32:00.1234567891234", "07:32:00,5",
+            ]);
+            match date {
+                Some(date) => format!("{}T{}{}", date, time, offset),
+                None => String::from(time),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DATETIME)]);
+    static ref MORE_DATETIME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref DATETIME_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::datetime",
+        initial_node: &START_DATETIME,
+        generator: |seed| String::from(pick(seed, &[
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T00:32:00.999999-07:00",
+            "1979-05-27T07:32:00",
+            "1979-05-27",
+            "07:32:00",
+        ])),
+    };
+}
+
+/// Splits a datetime into its date, its time and its offset, any of which
+/// may be missing
+fn split_datetime(datetime: &str) -> (Option<&str>, Option<&str>, &str) {
+    let (date, rest) = match datetime.get(..10) {
+        Some(date) if date.matches('-').count() == 2 => (
+            Some(date),
+            datetime[10..].trim_start_matches(['T', 't', ' ']),
+        ),
+        _ => (None, datetime),
+    };
+    if rest.is_empty() {
+        return (date, None, "");
+    }
+    let offset = rest
+        .char_indices()
+        .skip(8)
+        .find(|&(_, ch)| matches!(ch, 'Z' | 'z' | '+' | '-'))
+        .map_or(rest.len(), |(idx, _)| idx);
+    (date, Some(&rest[..offset]), &rest[offset..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_datetime, DATETIME_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| DATETIME_AUTOMATON.traverse(String::from("1979-05-27T07:32:00Z"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn datetimes_are_split() {
+        assert_eq!(
+            split_datetime("1979-05-27T00:32:00.999999-07:00"),
+            (Some("1979-05-27"), Some("00:32:00.999999"), "-07:00")
+        );
+        assert_eq!(split_datetime("1979-05-27"), (Some("1979-05-27"), None, ""));
+        assert_eq!(split_datetime("07:32:00"), (None, Some("07:32:00"), ""));
+    }
+
+    #[test]
+    fn offsets_are_changed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "1979-05-27T07:32:00+24:00"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "1979-05-27 07:32:00Z"));
+    }
+
+    #[test]
+    fn local_variants_are_generated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "1979-05-27T07:32:00"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "1979-05-27"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "07:32:00"));
+    }
+
+    #[test]
+    fn invalid_dates_are_generated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "2023-02-29T07:32:00Z"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "1979-05-27T23:59:60Z"));
+    }
+}
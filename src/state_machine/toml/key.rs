@@ -0,0 +1,93 @@
+use super::super::helper::{insert_string_in_string, pick, random_capitalization, FINAL};
+use super::depth;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_KEY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &QUOTE_KEY),
+            (3, &CHANGE_DOTS),
+            (2, &BREAK_BARE_KEY),
+            (1, &RANDOM_CASE_KEY),
+            (1, &DEEP_KEY),
+        ]);
+    // quoted keys which are the same as the bare key, or which are empty
+    static ref QUOTE_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| {
+            let bare = key.trim_matches(['"', '\'']);
+            match seed % 5 {
+                0 => format!("\"{}\"", bare),
+                1 => format!("'{}'", bare),
+                2 => match bare.chars().next() {
+                    Some(first) => format!("\"\\u{:04X}{}\"", first as u32, &bare[first.len_utf8()..]),
+                    None => String::from("\"\""),
+                },
+                3 => String::from("\"\""),
+                _ => String::from("''"),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_KEY)]);
+    // dotted keys which are split differently, or which have empty parts
+    static ref CHANGE_DOTS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| match seed % 5 {
+            0 => format!("{}.{}", key, key),
+            1 => key.replace('.', " . "),
+            2 => format!("\"{}\"", key.replace('"', "")),
+            3 => format!("{}..{}", key, key),
+            _ => format!("{}.", key),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_KEY)]);
+    // characters which aren't allowed in bare keys
+    static ref BREAK_BARE_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| {
+            let invalid = pick(seed, &[" ", "#", "=", "[", "\u{0}", "\u{e9}", "\u{2028}", "+", "\\"]);
+            insert_string_in_string(seed, &key, invalid)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_KEY)]);
+    // keys are case-sensitive
+    static ref RANDOM_CASE_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(random_capitalization)
+        .set_edges(vec![(3, &FINAL), (1, &MORE_KEY)]);
+    // dotted keys which nest tables deeply
+    static ref DEEP_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, key| format!("{}{}", "a.".repeat(depth(seed)), key));
+    static ref MORE_KEY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref KEY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::key",
+        initial_node: &START_KEY,
+        generator: |seed| String::from(pick(seed, &["name", "\"quoted key\"", "a.b.c", "'literal'"])),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KEY_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..200)
+            .map(|i| KEY_AUTOMATON.traverse(String::from("key"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn keys_are_quoted() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\"key\""));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "\"\\u006Bey\""));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\"\""));
+    }
+
+    #[test]
+    fn keys_are_dotted() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "key.key"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "key..key"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("{}key", "a.".repeat(100))));
+    }
+}
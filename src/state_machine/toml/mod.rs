@@ -0,0 +1,32 @@
+pub mod collection;
+pub mod datetime;
+pub mod key;
+pub mod number;
+pub mod string;
+pub mod table;
+
+/// Levels of nesting which make recursive parsers overflow their stack
+const NESTING_DEPTHS: [usize; 3] = [100, 1000, 10000];
+
+fn depth(seed: u64) -> usize {
+    NESTING_DEPTHS[(seed % NESTING_DEPTHS.len() as u64) as usize]
+}
+
+/// Returns the content of a value enclosed in `open` and `close`, e.g. the
+/// elements of an array, without the whitespace around it
+fn inner<'a>(value: &'a str, open: &str, close: &str) -> &'a str {
+    let value = value.strip_prefix(open).unwrap_or(value);
+    value.strip_suffix(close).unwrap_or(value).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inner;
+
+    #[test]
+    fn content_is_found() {
+        assert_eq!(inner("[ 1, 2 ]", "[", "]"), "1, 2");
+        assert_eq!(inner("{}", "{", "}"), "");
+        assert_eq!(inner("[[a]]", "[[", "]]"), "a");
+    }
+}
@@ -0,0 +1,209 @@
+use super::super::helper::{insert_string_in_string, pick, FINAL};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_INTEGER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &CHANGE_BASE),
+            (3, &UNDERSCORES),
+            (2, &LEADING_ZEROS),
+            (2, &OVERFLOW),
+        ]);
+    // the same value in hexadecimal, octal or binary, with prefixes which
+    // are upper case or signed
+    static ref CHANGE_BASE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, integer| {
+            let value = parse_integer(&integer).unwrap_or(0);
+            let magnitude = value.unsigned_abs();
+            let digits = match seed % 3 {
+                0 => format!("0x{:x}", magnitude),
+                1 => format!("0o{:o}", magnitude),
+                _ => format!("0b{:b}", magnitude),
+            };
+            match (seed / 3) % 4 {
+                0 => digits,
+                1 => digits.to_uppercase(),
+                2 => format!("-{}", digits),
+                _ => format!("+{}", digits),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INTEGER)]);
+    // underscores are only allowed between digits
+    static ref UNDERSCORES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, integer| {
+            let (prefix, digits) = split_prefix(&integer);
+            let changed = match seed % 5 {
+                0 => digits
+                    .chars()
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+                    .join("_"),
+                1 => format!("_{}", digits),
+                2 => format!("{}_", digits),
+                3 => insert_string_in_string(seed / 5, digits, "__"),
+                _ => return format!("{}_{}", prefix, digits),
+            };
+            format!("{}{}", prefix, changed)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INTEGER)]);
+    // leading zeros aren't allowed in decimal integers
+    static ref LEADING_ZEROS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, integer| {
+            let unsigned = integer.trim_start_matches(['+', '-']);
+            let sign = pick(seed, &["", "+", "-"]);
+            format!("{}{}{}", sign, "0".repeat(1 + (seed / 3 % 3) as usize), unsigned)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_INTEGER)]);
+    // integers have to be represented losslessly as 64 bits signed integers
+    static ref OVERFLOW: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &[
+            "9223372036854775807",
+            "9223372036854775808",
+            "-9223372036854775808",
+            "-9223372036854775809",
+            "0xffffffffffffffff",
+            "0x7fffffffffffffff",
+            "0b1111111111111111111111111111111111111111111111111111111111111111",
+            "-0",
+            "+0",
+        ])));
+    static ref MORE_INTEGER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref INTEGER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::integer",
+        initial_node: &START_INTEGER,
+        generator: |seed| String::from(pick(seed, &["42", "-17", "1_000", "0xDEAD_BEEF", "0o755", "0b1101"])),
+    };
+
+    static ref START_FLOAT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &SPECIAL_FLOAT),
+            (3, &CHANGE_EXPONENT),
+            (3, &CHANGE_FORM),
+            (1, &PRECISION),
+        ]);
+    // infinities and NaNs, which are lower case only
+    static ref SPECIAL_FLOAT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(pick(seed, &[
+            "inf", "+inf", "-inf", "nan", "+nan", "-nan", "Inf", "NaN", "infinity", "-0.0",
+        ])));
+    // exponents which are signed, empty, or out of range
+    static ref CHANGE_EXPONENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, float| {
+            let mantissa = float.split(['e', 'E']).next().unwrap_or(&float);
+            let exponent = pick(seed, &["e+22", "E-2", "e", "e+", "e_1", "e1_0", "e400", "e-400"]);
+            format!("{}{}", mantissa, exponent)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FLOAT)]);
+    // decimal points need digits on both sides
+    static ref CHANGE_FORM: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, float| {
+            let (integral, fractional) = float.split_once('.').unwrap_or((&float, "0"));
+            match seed % 5 {
+                0 => format!(".{}", fractional),
+                1 => format!("{}.", integral),
+                2 => format!("{}._{}", integral, fractional),
+                3 => format!("{}_.{}", integral, fractional),
+                _ => format!("0{}.{}", integral.trim_start_matches(['+', '-']), fractional),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FLOAT)]);
+    // more digits than a 64 bits float holds
+    static ref PRECISION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, float| {
+            let digits = [20, 400, 4000][(seed % 3) as usize];
+            match float.split_once(['e', 'E']) {
+                Some((mantissa, exponent)) => format!("{}{}e{}", mantissa, "1".repeat(digits), exponent),
+                None => format!("{}{}", float, "1".repeat(digits)),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_FLOAT)]);
+    static ref MORE_FLOAT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref FLOAT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::float",
+        initial_node: &START_FLOAT,
+        generator: |seed| String::from(pick(seed, &["3.1415", "-0.01", "5e+22", "6.626e-34", "224_617.445_991"])),
+    };
+}
+
+/// Splits an integer into its base prefix, along with its sign, and its
+/// digits
+fn split_prefix(integer: &str) -> (&str, &str) {
+    let unsigned = integer.trim_start_matches(['+', '-']);
+    let prefix = match unsigned.get(..2) {
+        Some(base) if ["0x", "0o", "0b", "0X", "0O", "0B"].contains(&base) => base.len(),
+        _ => 0,
+    };
+    integer.split_at(integer.len() - unsigned.len() + prefix)
+}
+
+fn parse_integer(integer: &str) -> Option<i64> {
+    let (prefix, digits) = split_prefix(integer);
+    let digits = digits.replace('_', "");
+    let radix = match prefix
+        .trim_start_matches(['+', '-'])
+        .to_lowercase()
+        .as_str()
+    {
+        "0x" => 16,
+        "0o" => 8,
+        "0b" => 2,
+        _ => 10,
+    };
+    let value = i64::from_str_radix(&digits, radix).ok()?;
+    if prefix.starts_with('-') {
+        Some(-value)
+    } else {
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_integer, split_prefix, FLOAT_AUTOMATON, INTEGER_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| INTEGER_AUTOMATON.traverse(String::from("255"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn integers_are_parsed() {
+        assert_eq!(split_prefix("-0x1f"), ("-0x", "1f"));
+        assert_eq!(split_prefix("12"), ("", "12"));
+        assert_eq!(parse_integer("0xDEAD_BEEF"), Some(0xdead_beef));
+        assert_eq!(parse_integer("-17"), Some(-17));
+        assert_eq!(parse_integer("0b"), None);
+    }
+
+    #[test]
+    fn bases_are_changed() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "0xff"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "0o377"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "0b11111111"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "0XFF"));
+    }
+
+    #[test]
+    fn underscores_are_inserted() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "2_5_5"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "_255"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "255_"));
+    }
+
+    #[test]
+    fn floats_are_changed() {
+        let fuzzed: Vec<String> = (1..200)
+            .map(|i| FLOAT_AUTOMATON.traverse(String::from("1.5"), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == ".5"));
+        assert!(fuzzed.iter().any(|value| value == "1."));
+        assert!(fuzzed.iter().any(|value| value == "nan"));
+        assert!(fuzzed.iter().any(|value| value == "1.5e+"));
+    }
+}
@@ -0,0 +1,146 @@
+use super::super::helper::{
+    insert_random_unescaped_control_char, insert_string_in_string, pick, FINAL,
+};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_STRING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &INVALID_ESCAPE),
+            (3, &SWITCH_QUOTES),
+            (3, &ESCAPED_LINE_ENDING),
+            (2, &QUOTES_IN_MULTILINE),
+            (2, &CONTROL_CHAR),
+            (1, &UNTERMINATE),
+        ]);
+    // escapes which aren't defined, and unicode escapes out of range
+    static ref INVALID_ESCAPE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (open, content, close) = split_quotes(&string);
+            let escape = pick(seed, &["\\x41", "\\uD800", "\\U00110000", "\\u00", "\\e", "\\ "]);
+            format!("{}{}{}", open, insert_string_in_string(seed / 6, content, escape), close)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_STRING)]);
+    // basic strings turned into literal ones, where escapes aren't processed,
+    // and single-line strings into multi-line ones
+    static ref SWITCH_QUOTES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (_, content, _) = split_quotes(&string);
+            let quotes = pick(seed, &["\"", "'", "\"\"\"", "'''"]);
+            format!("{}{}{}", quotes, content, quotes)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_STRING)]);
+    // line ending backslashes, which trim the whitespace that follows them
+    // in multi-line basic strings only
+    static ref ESCAPED_LINE_ENDING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (_, content, _) = split_quotes(&string);
+            let ending = pick(seed, &["\\\n   ", "\\ \t\n", "\\\r\n\n", "\\\\\n", "\\"]);
+            let quotes = pick(seed / 5, &["\"\"\"", "\"", "'''"]);
+            format!("{}{}{}", quotes, insert_string_in_string(seed / 15, content, ending), quotes)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_STRING)]);
+    // up to two quotes are allowed next to the delimiters of multi-line
+    // strings, but not three
+    static ref QUOTES_IN_MULTILINE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (_, content, _) = split_quotes(&string);
+            let quote = pick(seed, &["\"", "'"]);
+            let count = 1 + (seed / 2 % 3) as usize;
+            let quotes = quote.repeat(3);
+            let extra = quote.repeat(count);
+            format!("{}{}{}{}{}", quotes, extra, content, extra, quotes)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_STRING)]);
+    // control characters other than tabs must be escaped
+    static ref CONTROL_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (open, content, close) = split_quotes(&string);
+            format!("{}{}{}", open, insert_random_unescaped_control_char(seed, content), close)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_STRING)]);
+    static ref UNTERMINATE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, string| {
+            let (open, content, _) = split_quotes(&string);
+            match seed % 2 {
+                0 => format!("{}{}", open, content),
+                _ => format!("{}{}\\{}", open, content, open),
+            }
+        });
+    static ref MORE_STRING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref STRING_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::string",
+        initial_node: &START_STRING,
+        generator: |seed| String::from(pick(seed, &[
+            "\"basic\"",
+            "'C:\\Users'",
+            "\"\"\"\nmulti\nline\"\"\"",
+            "'''\nraw\n'''",
+        ])),
+    };
+}
+
+/// Splits a string into its opening delimiter, content and closing delimiter
+fn split_quotes(string: &str) -> (&str, &str, &str) {
+    for quotes in ["\"\"\"", "'''", "\"", "'"] {
+        if string.len() >= 2 * quotes.len()
+            && string.starts_with(quotes)
+            && string.ends_with(quotes)
+        {
+            let content = &string[quotes.len()..string.len() - quotes.len()];
+            return (quotes, content, quotes);
+        }
+    }
+    ("\"", string, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_quotes, STRING_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| STRING_AUTOMATON.traverse(String::from("\"abc\""), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn quotes_are_split() {
+        assert_eq!(split_quotes("\"\"\"a\"\"\""), ("\"\"\"", "a", "\"\"\""));
+        assert_eq!(split_quotes("'a'"), ("'", "a", "'"));
+        assert_eq!(split_quotes("\"\""), ("\"", "", "\""));
+        assert_eq!(split_quotes("a"), ("\"", "a", "\""));
+    }
+
+    #[test]
+    fn quotes_are_switched() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "'abc'"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "\"\"\"abc\"\"\""));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("\"\"\"\"\"") && value.ends_with("\"\"\"\"\"")));
+    }
+
+    #[test]
+    fn line_endings_are_escaped() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("\"\"\"") && value.contains("\\\n   ")));
+    }
+
+    #[test]
+    fn invalid_escapes_are_inserted() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("\\uD800")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("\\U00110000")));
+    }
+}
@@ -0,0 +1,224 @@
+use super::super::helper::{pick, FINAL};
+use super::{depth, inner};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TABLE_HEADER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &TOGGLE_ARRAY_OF_TABLES),
+            (2, &CHANGE_TABLE_NAME),
+            (2, &QUOTE_TABLE_NAME),
+            (2, &BREAK_HEADER),
+        ]);
+    // tables turned into arrays of tables and the other way around
+    static ref TOGGLE_ARRAY_OF_TABLES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| {
+            let (name, array) = table_name(&header);
+            match (seed % 2, array) {
+                (0, true) => format!("[{}]", name),
+                (0, false) => format!("[[{}]]", name),
+                // whitespace between the brackets isn't allowed
+                _ => format!("[ [{}] ]", name),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE_HEADER)]);
+    // sub-tables, parent tables and deeply nested ones
+    static ref CHANGE_TABLE_NAME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| {
+            let (name, array) = table_name(&header);
+            let changed = match seed % 3 {
+                0 => format!("{}.sub", name),
+                1 => String::from(name.rsplit_once('.').map_or(name, |(parent, _)| parent)),
+                _ => format!("{}{}", "a.".repeat(depth(seed / 3)), name),
+            };
+            header_of(&changed, array)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE_HEADER)]);
+    // names which are the same once they're unquoted, or which are empty
+    static ref QUOTE_TABLE_NAME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| {
+            let (name, array) = table_name(&header);
+            let changed = match seed % 5 {
+                0 => format!("\"{}\"", name),
+                1 => name.replace('.', " . "),
+                2 => format!("\"{}\"", name.replace('"', "")),
+                3 => String::new(),
+                _ => format!(" {}\t", name),
+            };
+            header_of(&changed, array)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE_HEADER)]);
+    // unbalanced brackets, and anything but a comment after the header
+    static ref BREAK_HEADER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, header| match seed % 4 {
+            0 => format!("{}]", header),
+            1 => String::from(header.trim_end_matches(']')),
+            2 => format!("[{}", header),
+            _ => format!("{} key = 1", header),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TABLE_HEADER)]);
+    static ref MORE_TABLE_HEADER: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref TABLE_HEADER_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::table_header",
+        initial_node: &START_TABLE_HEADER,
+        generator: |seed| String::from(pick(seed, &["[server]", "[[products]]", "[a.\"b.c\".d]"])),
+    };
+
+    static ref START_DOCUMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DUPLICATE_TABLE),
+            (3, &REDEFINE_WITH_DOTTED_KEY),
+            (3, &CONFLICT_ARRAY_OF_TABLES),
+            (2, &DUPLICATE_KEY),
+        ]);
+    // tables which are defined twice
+    static ref DUPLICATE_TABLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, document| {
+            let name = pick_table(seed, &document);
+            format!("{}\n[{}]\n", document.trim_end(), name)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCUMENT)]);
+    // tables which are defined both by dotted keys and by headers, in either
+    // order
+    static ref REDEFINE_WITH_DOTTED_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, document| {
+            let name = pick_table(seed / 2, &document);
+            match seed % 2 {
+                0 => format!("{}.dotted = 1\n{}", name, document),
+                _ => format!("{}\n[{}]\nsub.key = 1\n[{}.sub]\n", document.trim_end(), name, name),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCUMENT)]);
+    // arrays of tables which are also defined as tables, static arrays or
+    // inline tables
+    static ref CONFLICT_ARRAY_OF_TABLES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, document| {
+            let name = pick_table(seed / 4, &document);
+            match seed % 4 {
+                0 => format!("{}\n[[{}]]\n", document.trim_end(), name),
+                1 => format!("{}\n[[{}]]\n[{}]\n", document.trim_end(), name, name),
+                2 => format!("{} = []\n{}\n[[{}]]\n", name, document.trim_end(), name),
+                _ => format!("{} = {{}}\n{}\n[{}]\n", name, document.trim_end(), name),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCUMENT)]);
+    // keys which are defined twice within the same table
+    static ref DUPLICATE_KEY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, document| {
+            let lines: Vec<&str> = document.lines().collect();
+            let pairs: Vec<usize> = (0..lines.len())
+                .filter(|&idx| {
+                    let line = lines[idx].trim_start();
+                    line.contains('=') && !line.starts_with(['[', '#'])
+                })
+                .collect();
+            match pairs.get((seed as usize) % pairs.len().max(1)) {
+                Some(&idx) => {
+                    let mut lines = lines.clone();
+                    lines.insert(idx + 1, lines[idx]);
+                    lines.join("\n")
+                }
+                None => format!("key = 1\nkey = 2\n{}", document),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCUMENT)]);
+    static ref MORE_DOCUMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref DOCUMENT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "toml::document",
+        initial_node: &START_DOCUMENT,
+        generator: |seed| String::from(pick(seed, &[
+            "title = \"a\"\n\n[owner]\nname = \"b\"\n",
+            "[[products]]\nname = \"a\"\n\n[[products]]\nname = \"b\"\n",
+            "[a.b]\nc = 1\n",
+        ])),
+    };
+}
+
+/// Returns the name of a table from its header, along with whether it's an
+/// array of tables
+fn table_name(header: &str) -> (&str, bool) {
+    let header = header.trim();
+    if header.starts_with("[[") {
+        (inner(header, "[[", "]]"), true)
+    } else {
+        (inner(header, "[", "]"), false)
+    }
+}
+
+fn header_of(name: &str, array: bool) -> String {
+    if array {
+        format!("[[{}]]", name)
+    } else {
+        format!("[{}]", name)
+    }
+}
+
+/// Picks the name of one of the tables of a document, which is "table" if
+/// it has none
+fn pick_table(seed: u64, document: &str) -> String {
+    let names: Vec<&str> = document
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('['))
+        .map(|line| table_name(line.split('#').next().unwrap_or(line)).0)
+        .collect();
+    match names.get(seed as usize % names.len().max(1)) {
+        Some(name) => String::from(*name),
+        None => String::from("table"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DOCUMENT_AUTOMATON, TABLE_HEADER_AUTOMATON};
+    use itertools::Itertools;
+
+    const DOCUMENT: &str = "title = \"a\"\n\n[owner]\nname = \"b\"\n";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| DOCUMENT_AUTOMATON.traverse(String::from(DOCUMENT), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn headers_are_changed() {
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| TABLE_HEADER_AUTOMATON.traverse(String::from("[a.b]"), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "[[a.b]]"));
+        assert!(fuzzed.iter().any(|value| value == "[a]"));
+        assert!(fuzzed.iter().any(|value| value == "[\"a.b\"]"));
+    }
+
+    #[test]
+    fn tables_are_redefined() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("{}\n[owner]\n", DOCUMENT.trim_end())));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("owner.dotted = 1\n{}", DOCUMENT)));
+    }
+
+    #[test]
+    fn arrays_of_tables_conflict() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("{}\n[[owner]]\n", DOCUMENT.trim_end())));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("owner = []\n{}\n[[owner]]\n", DOCUMENT.trim_end())));
+    }
+
+    #[test]
+    fn keys_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "title = \"a\"\ntitle = \"a\"\n\n[owner]\nname = \"b\""));
+    }
+}
@@ -0,0 +1,353 @@
+use super::helper::{pick, FINAL};
+use super::{Automaton, AutomatonNode};
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref START_UNICODE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &COMBINING_MARKS),
+            (2, &ZERO_WIDTH_CHAR),
+            (2, &BIDI_CONTROL),
+            (2, &CONFUSABLE),
+            (2, &NORMALIZATION_DIVERGENCE),
+            (2, &CASE_EXPANSION),
+            (1, &NONCHARACTER),
+            (1, &PRIVATE_USE_CHAR),
+            (1, &LONG_GRAPHEME),
+        ]);
+    // "Zalgo" text - a base character followed by a stack of marks
+    static ref COMBINING_MARKS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let marks: String = (0..2 + seed % 62).map(|i| combining_mark(seed.wrapping_add(i))).collect();
+            insert(seed, &text, &marks)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    static ref ZERO_WIDTH_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert(seed, &text, pick(seed, &ZERO_WIDTH_CHARS)))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    // either a lone control or a "Trojan Source" override, which makes a
+    // part of the text be displayed reversed
+    static ref BIDI_CONTROL: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| match seed % 3 {
+            0 => wrap(&text, "\u{202e}", "\u{202c}"),
+            1 => wrap(&text, "\u{2067}", "\u{2069}"),
+            _ => insert(seed, &text, pick(seed, &BIDI_CONTROLS)),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    static ref CONFUSABLE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| replace_chars(seed, &text, |ch| {
+            CONFUSABLES.iter().find(|(latin, _)| *latin == ch).map(|(_, homoglyph)| homoglyph.to_string())
+        }))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    // text that is changed by NFC, NFD or NFKC normalization, so that two
+    // components comparing it may disagree
+    static ref NORMALIZATION_DIVERGENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| match seed % 3 {
+            0 => replace_chars(seed, &text, fullwidth),
+            1 => replace_chars(seed, &text, |ch| {
+                DECOMPOSITIONS.iter().find(|(composed, _)| *composed == ch).map(|(_, decomposed)| decomposed.to_string())
+            }),
+            _ => insert(seed, &text, pick(seed, &COMPATIBILITY_CHARS)),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    // characters whose upper or lower case is longer than themselves, or
+    // which change case to an ASCII letter
+    static ref CASE_EXPANSION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| match seed % 2 {
+            0 => replace_chars(seed, &text, |ch| match ch {
+                's' => Some(String::from("\u{17f}")),
+                'k' | 'K' => Some(String::from("\u{212a}")),
+                'i' => Some(String::from("\u{131}")),
+                'I' => Some(String::from("\u{130}")),
+                _ => None,
+            }),
+            _ => insert(seed, &text, pick(seed, &CASE_EXPANDING_CHARS)),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_UNICODE)]);
+    static ref NONCHARACTER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert(seed, &text, &noncharacter(seed).to_string()));
+    static ref PRIVATE_USE_CHAR: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert(seed, &text, &private_use_char(seed).to_string()));
+    static ref LONG_GRAPHEME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert(seed, &text, &long_grapheme(seed)));
+    static ref MORE_UNICODE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref UNICODE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "unicode::text",
+        initial_node: &START_UNICODE,
+        generator: |seed| String::from(pick(seed, &SAMPLE_TEXTS)),
+    };
+    static ref START_GRAPHEME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| long_grapheme(seed));
+    pub static ref GRAPHEME_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "unicode::grapheme",
+        initial_node: &START_GRAPHEME,
+        generator: |_| String::new(),
+    };
+}
+
+/// Words which are affected by case mapping, normalization or have
+/// confusable characters
+const SAMPLE_TEXTS: [&str; 8] = [
+    "admin",
+    "paypal.com",
+    "Stra\u{df}e",
+    "\u{130}stanbul",
+    "caf\u{e9}",
+    "file.txt",
+    "\u{645}\u{631}\u{62d}\u{628}\u{627}",
+    "user@example.com",
+];
+
+/// Characters which have no width, some of them joining or separating the
+/// ones around them
+const ZERO_WIDTH_CHARS: [&str; 9] = [
+    "\u{200b}", "\u{200c}", "\u{200d}", "\u{2060}", "\u{feff}", "\u{180e}", "\u{ad}", "\u{34f}",
+    "\u{2062}",
+];
+
+/// Embeddings, overrides, isolates and marks changing the direction of text
+const BIDI_CONTROLS: [&str; 12] = [
+    "\u{202a}", "\u{202b}", "\u{202c}", "\u{202d}", "\u{202e}", "\u{2066}", "\u{2067}", "\u{2068}",
+    "\u{2069}", "\u{200e}", "\u{200f}", "\u{61c}",
+];
+
+/// Latin letters and digits along with Cyrillic, Greek or other characters
+/// that look the same
+const CONFUSABLES: [(char, char); 16] = [
+    ('a', '\u{430}'),
+    ('c', '\u{441}'),
+    ('e', '\u{435}'),
+    ('i', '\u{456}'),
+    ('j', '\u{458}'),
+    ('l', '\u{217c}'),
+    ('o', '\u{43e}'),
+    ('p', '\u{440}'),
+    ('s', '\u{455}'),
+    ('x', '\u{445}'),
+    ('y', '\u{443}'),
+    ('A', '\u{391}'),
+    ('B', '\u{392}'),
+    ('H', '\u{41d}'),
+    ('0', '\u{39f}'),
+    ('1', '\u{6f1}'),
+];
+
+/// Precomposed characters along with their canonical decomposition (NFD)
+const DECOMPOSITIONS: [(char, &str); 8] = [
+    ('\u{e9}', "e\u{301}"),
+    ('\u{e8}', "e\u{300}"),
+    ('\u{e0}', "a\u{300}"),
+    ('\u{fc}', "u\u{308}"),
+    ('\u{f6}', "o\u{308}"),
+    ('\u{f1}', "n\u{303}"),
+    ('\u{e7}', "c\u{327}"),
+    ('\u{c5}', "A\u{30a}"),
+];
+
+/// Characters which compatibility normalization (NFKC) turns into ASCII,
+/// and ones which canonical normalization (NFC) turns into others
+const COMPATIBILITY_CHARS: [&str; 10] = [
+    "\u{fb01}",
+    "\u{fb00}",
+    "\u{2460}",
+    "\u{2126}",
+    "\u{212b}",
+    "\u{2024}",
+    "\u{ff0f}",
+    "\u{2044}",
+    "\u{fe64}script\u{fe65}",
+    "\u{2474}",
+];
+
+/// Characters whose case mapping consists of multiple characters
+const CASE_EXPANDING_CHARS: [&str; 8] = [
+    "\u{df}", "\u{130}", "\u{149}", "\u{fb00}", "\u{390}", "\u{1f0}", "\u{1e9e}", "\u{587}",
+];
+
+/// Outputs the range of `text` which may be changed, i.e. the part
+/// between its quotes if it's a quoted string
+fn span(text: &str) -> (usize, usize) {
+    match text.chars().next() {
+        Some(quote)
+            if text.len() > 1 && (quote == '"' || quote == '\'') && text.ends_with(quote) =>
+        {
+            (1, text.len() - 1)
+        }
+        _ => (0, text.len()),
+    }
+}
+
+/// Inserts `to_insert` at a random character boundary of `text`, between
+/// its quotes if it's a quoted string
+fn insert(seed: u64, text: &str, to_insert: &str) -> String {
+    let (start, end) = span(text);
+    let mut pos = start + (seed % (end - start + 1) as u64) as usize;
+    while !text.is_char_boundary(pos) {
+        pos += 1;
+    }
+    format!("{}{}{}", &text[..pos], to_insert, &text[pos..])
+}
+
+/// Puts `open` and `close` around `text`, within its quotes if it's a
+/// quoted string
+fn wrap(text: &str, open: &str, close: &str) -> String {
+    let (start, end) = span(text);
+    format!(
+        "{}{}{}{}{}",
+        &text[..start],
+        open,
+        &text[start..end],
+        close,
+        &text[end..]
+    )
+}
+
+/// Replaces the characters of `text` which `replace` has a replacement for,
+/// either all of them or a single one, leaving its quotes intact. Inserts a
+/// replacement of an ASCII letter if there are none.
+fn replace_chars<F: Fn(char) -> Option<String>>(seed: u64, text: &str, replace: F) -> String {
+    let (start, end) = span(text);
+    let replaceable = |i: usize, ch: char| (start..end).contains(&i) && replace(ch).is_some();
+    let candidates: Vec<usize> = text
+        .char_indices()
+        .filter(|(i, ch)| replaceable(*i, *ch))
+        .map(|(i, _)| i)
+        .collect();
+    if candidates.is_empty() {
+        let letter = char::from(b'a' + (seed % 26) as u8);
+        return insert(
+            seed,
+            text,
+            &replace(letter).unwrap_or_else(|| letter.to_string()),
+        );
+    }
+    let only = candidates[(seed % candidates.len() as u64) as usize];
+    text.char_indices()
+        .map(|(i, ch)| match replace(ch) {
+            Some(replacement) if replaceable(i, ch) && (seed.is_multiple_of(2) || i == only) => {
+                replacement
+            }
+            _ => ch.to_string(),
+        })
+        .collect()
+}
+
+/// Fullwidth form of an ASCII character, which NFKC maps back to it
+fn fullwidth(ch: char) -> Option<String> {
+    match ch {
+        '!'..='~' => char::from_u32(ch as u32 + 0xfee0).map(String::from),
+        _ => None,
+    }
+}
+
+/// A combining diacritical mark
+fn combining_mark(seed: u64) -> char {
+    char::from_u32(0x300 + (seed % 0x70) as u32).expect("Combining marks are valid characters")
+}
+
+/// One of the 66 noncharacters: U+FDD0..U+FDEF and the last two code points
+/// of each plane
+fn noncharacter(seed: u64) -> char {
+    let index = u32::try_from(seed % 66).expect("Index is below 66");
+    let code = match index {
+        0..=31 => 0xfdd0 + index,
+        _ => ((index - 32) / 2) * 0x10000 + 0xfffe + index % 2,
+    };
+    char::from_u32(code).expect("Noncharacters are valid characters")
+}
+
+/// A code point from the private use area of the BMP or from one of the
+/// supplementary ones
+fn private_use_char(seed: u64) -> char {
+    let code = match seed % 3 {
+        0 => 0xe000 + (seed / 3 % 0x1900) as u32,
+        1 => 0xf0000 + (seed / 3 % 0xfffe) as u32,
+        _ => 0x100000 + (seed / 3 % 0xfffe) as u32,
+    };
+    char::from_u32(code).expect("Private use code points are valid characters")
+}
+
+/// A single grapheme cluster of thousands of code points
+fn long_grapheme(seed: u64) -> String {
+    let length = 1 << (10 + seed % 7);
+    match seed / 7 % 4 {
+        0 => std::iter::once('a')
+            .chain((0..length).map(|i| combining_mark(seed.wrapping_add(i))))
+            .collect(),
+        1 => format!(
+            "{}\u{1f469}",
+            "\u{1f468}\u{200d}".repeat(length as usize / 2)
+        ),
+        2 => format!("{}\u{1161}\u{11a8}", "\u{1100}".repeat(length as usize)),
+        _ => format!("\u{1f3f4}{}\u{e007f}", "\u{e0067}".repeat(length as usize)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fullwidth, insert, noncharacter, replace_chars, wrap, GRAPHEME_AUTOMATON, UNICODE_AUTOMATON,
+    };
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| UNICODE_AUTOMATON.traverse(String::from("Kiss file"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn insertions_stay_within_quotes() {
+        for seed in 0..10 {
+            let inserted = insert(seed, "\"ab\"", "\u{200b}");
+            assert!(inserted.starts_with('"') && inserted.ends_with('"'));
+        }
+        assert_eq!(insert(3, "", "x"), "x");
+    }
+
+    #[test]
+    fn quotes_are_kept_intact() {
+        assert_eq!(wrap("\"ab\"", "<", ">"), "\"<ab>\"");
+        assert_eq!(wrap("ab", "<", ">"), "<ab>");
+        for seed in 0..10 {
+            assert_eq!(replace_chars(seed, "\"a\"", fullwidth), "\"\u{ff41}\"");
+        }
+    }
+
+    #[test]
+    fn noncharacters_are_generated() {
+        assert_eq!(noncharacter(0), '\u{fdd0}');
+        assert_eq!(noncharacter(32), '\u{fffe}');
+        assert_eq!(noncharacter(65), '\u{10ffff}');
+    }
+
+    #[test]
+    fn confusables_and_normalization_variants_are_used() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains('\u{435}') || value.contains('\u{456}')));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains('\u{ff2b}')));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains('\u{17f}') || value.contains('\u{212a}')));
+    }
+
+    #[test]
+    fn text_is_reversed() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "\u{202e}Kiss file\u{202c}"));
+    }
+
+    #[test]
+    fn graphemes_are_long() {
+        assert!((1..20)
+            .map(|i| GRAPHEME_AUTOMATON.generate(i))
+            .all(|value| value.chars().count() > 1000));
+    }
+}
@@ -0,0 +1,133 @@
+use super::super::helper::*;
+use super::percent_encode;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_HOST: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &IPV4_HOST),
+            (2, &IPV6_HOST),
+            (2, &LOOKALIKE_HOST),
+            (2, &CONFUSE_HOST),
+            (1, &TRAILING_DOT),
+            (1, &CASED_HOST),
+            (1, &ENCODE_HOST),
+            (1, &OVERSIZED_LABEL),
+            (1, &REMOVE_HOST),
+        ]);
+    // loopback and metadata addresses in the many notations of IPv4
+    static ref IPV4_HOST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(IPV4_HOSTS[(seed % IPV4_HOSTS.len() as u64) as usize]))
+        .set_edges(vec![(4, &FINAL), (1, &TRAILING_DOT)]);
+    static ref IPV6_HOST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(IPV6_HOSTS[(seed % IPV6_HOSTS.len() as u64) as usize]));
+    // internationalized names which may be normalized to an ASCII one, as
+    // well as their punycode
+    static ref LOOKALIKE_HOST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, host| match seed % 4 {
+            0 => host.replacen('.', ["\u{3002}", "\u{ff0e}", "\u{ff61}"][(seed / 4 % 3) as usize], 1),
+            1 => host.replacen('l', "\u{24db}", 1).replacen('o', "\u{ff4f}", 1),
+            2 => host.replacen('i', "\u{131}", 1).replacen('a', "\u{430}", 1),
+            _ => String::from(["xn--lcalhost-0ya", "xn--80ak6aa92e.com", "xn--", "xn--zz"][(seed / 4 % 4) as usize]),
+        });
+    // hosts which parsers disagree on, e.g. "good.example\@evil.example" is
+    // "evil.example" to some and "good.example" to others
+    static ref CONFUSE_HOST: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, host| match seed % 6 {
+            0 => format!("{}\\@evil.example", host),
+            1 => format!("evil.example\\{}", host),
+            2 => format!("{}.evil.example", host),
+            3 => format!("{}%00.evil.example", host),
+            4 => format!("{}#@evil.example", host),
+            _ => format!("{} evil.example", host),
+        });
+    static ref TRAILING_DOT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, host| format!("{}{}", host, [".", "..", "\u{3002}"][(seed % 3) as usize]));
+    static ref CASED_HOST: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(to_random_case);
+    static ref ENCODE_HOST: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, host| percent_encode(seed, &host));
+    // labels are limited to 63 characters and names to 253
+    static ref OVERSIZED_LABEL: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, host| match seed % 2 {
+            0 => format!("{}.{}", "a".repeat(64), host),
+            _ => format!("{}{}", "a.".repeat(127), host),
+        });
+    static ref REMOVE_HOST: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    pub static ref HOST_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::host",
+        initial_node: &START_HOST,
+        generator: |seed| String::from(["example.com", "localhost", "127.0.0.1"][(seed % 3) as usize]),
+    };
+}
+
+/// Loopback, unspecified and cloud metadata addresses in decimal, octal and
+/// hexadecimal notation, with some of their parts left out
+const IPV4_HOSTS: [&str; 12] = [
+    "127.0.0.1",
+    "127.1",
+    "2130706433",
+    "0x7f000001",
+    "0x7f.0.0.1",
+    "0177.0.0.1",
+    "017700000001",
+    "127.000.000.001",
+    "0",
+    "0.0.0.0",
+    "169.254.169.254",
+    "256.256.256.256",
+];
+
+/// Loopback and IPv4-mapped addresses along with zone identifiers and
+/// malformed literals
+const IPV6_HOSTS: [&str; 10] = [
+    "[::1]",
+    "[::]",
+    "[0:0:0:0:0:0:0:1]",
+    "[::ffff:127.0.0.1]",
+    "[::ffff:7f00:1]",
+    "[fe80::1%25eth0]",
+    "[fe80::1%eth0]",
+    "[v1.x]",
+    "[::1",
+    "::1",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::HOST_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| HOST_AUTOMATON.traverse(String::from("localhost"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn ip_literals_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "0x7f.0.0.1"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "[::ffff:127.0.0.1]"));
+    }
+
+    #[test]
+    fn hosts_are_confused() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "localhost\\@evil.example"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "localhost."));
+    }
+
+    #[test]
+    fn lookalikes_are_generated() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| !value.is_ascii()));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("xn--")));
+    }
+}
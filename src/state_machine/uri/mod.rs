@@ -0,0 +1,68 @@
+pub mod host;
+pub mod path;
+pub mod port;
+pub mod query;
+pub mod scheme;
+pub mod userinfo;
+
+/// Percent-encodes a random character of a text, as is, double-encoded or
+/// with its hex digits in either case
+fn percent_encode(seed: u64, text: &str) -> String {
+    let position = match text
+        .char_indices()
+        .nth((seed % text.chars().count().max(1) as u64) as usize)
+    {
+        Some((position, _)) => position,
+        None => return text.to_string(),
+    };
+    let ch = text[position..]
+        .chars()
+        .next()
+        .expect("Position is within text");
+    let mut buffer = [0; 4];
+    let encoded: String = ch
+        .encode_utf8(&mut buffer)
+        .bytes()
+        .map(|byte| match (seed / 4) % 3 {
+            0 => format!("%{:02X}", byte),
+            1 => format!("%{:02x}", byte),
+            _ => format!("%25{:02X}", byte),
+        })
+        .collect();
+    format!(
+        "{}{}{}",
+        &text[..position],
+        encoded,
+        &text[position + ch.len_utf8()..]
+    )
+}
+
+/// Malformed percent-encodings, encoded delimiters and overlong UTF-8
+/// sequences of them
+const ENCODINGS: [&str; 12] = [
+    "%",
+    "%2",
+    "%zz",
+    "%%32%65",
+    "%00",
+    "%2f",
+    "%2F",
+    "%5c",
+    "%252f",
+    "%c0%af",
+    "%e0%80%af",
+    "%u002f",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::percent_encode;
+
+    #[test]
+    fn characters_are_percent_encoded() {
+        assert_eq!(percent_encode(0, "/a"), "%2Fa");
+        assert_eq!(percent_encode(5, "/a"), "/%61");
+        assert_eq!(percent_encode(8, "é"), "%25C3%25A9");
+        assert_eq!(percent_encode(1, ""), "");
+    }
+}
@@ -0,0 +1,86 @@
+use super::super::helper::*;
+use super::{percent_encode, ENCODINGS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_PATH: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &ADD_DOT_SEGMENTS),
+            (2, &ADD_BACKSLASHES),
+            (2, &ADD_ENCODING),
+            (1, &ENCODE_PATH),
+            (1, &DOUBLE_SLASH),
+            (1, &ADD_MATRIX_PARAMETER),
+        ]);
+    static ref ADD_DOT_SEGMENTS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, path| {
+            let segments = DOT_SEGMENTS[(seed % DOT_SEGMENTS.len() as u64) as usize];
+            format!("/{}{}", segments.repeat(1 + (seed / 16 % 4) as usize), path.trim_start_matches('/'))
+        })
+        .set_edges(vec![(3, &FINAL), (1, &ADD_BACKSLASHES), (1, &ENCODE_PATH)]);
+    // WHATWG parsers treat backslashes in special URLs as slashes, RFC 3986
+    // ones don't
+    static ref ADD_BACKSLASHES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, path| match seed % 2 {
+            0 => path.replace('/', "\\"),
+            _ => replace_random_occurrence(path, "/", "\\", seed),
+        });
+    static ref ADD_ENCODING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, path| insert_string_in_string(seed, &path, ENCODINGS[(seed % ENCODINGS.len() as u64) as usize]));
+    static ref ENCODE_PATH: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, path| percent_encode(seed, &path));
+    // a path starting with "//" is taken for an authority when resolved
+    // as a relative reference
+    static ref DOUBLE_SLASH: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, path| format!("{}{}", ["//evil.example", "/\\evil.example", "//", "///"][(seed % 4) as usize], path));
+    static ref ADD_MATRIX_PARAMETER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, path| insert_string_in_string(seed, &path, [";", ";a=b", ";/..", ";jsessionid=1"][(seed % 4) as usize]));
+    pub static ref PATH_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::path",
+        initial_node: &START_PATH,
+        generator: |seed| format!("/api/v1/items/{}", seed % 100),
+    };
+}
+
+/// Dot-segments, some of them encoded or only removed by lenient parsers
+const DOT_SEGMENTS: [&str; 8] = [
+    "../",
+    "./",
+    "%2e%2e/",
+    ".%2e/",
+    "%2e%2e%2f",
+    "..%2f",
+    "..\\",
+    ".../",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::PATH_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..500)
+            .map(|i| PATH_AUTOMATON.traverse(String::from("/a/b"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn dot_segments_are_added() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("/../")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("%2e%2e")));
+    }
+
+    #[test]
+    fn paths_are_encoded() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("%252f")));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value.contains("%zz")));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "\\a\\b"));
+    }
+}
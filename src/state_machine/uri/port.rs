@@ -0,0 +1,58 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_PORT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &OVERFLOW_PORT),
+            (2, &REPLACE_PORT),
+            (1, &PAD_PORT),
+            (1, &INVALID_CHAR_IN_PORT),
+        ]);
+    // ports which wrap around to a valid one, e.g. 65536 + 80
+    static ref OVERFLOW_PORT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, port| {
+            let port = port.parse::<u64>().unwrap_or(80);
+            match seed % 4 {
+                0 => (port + (1 << 16)).to_string(),
+                1 => (port + (1 << 32)).to_string(),
+                2 => format!("{}{}", port, "0".repeat(20)),
+                _ => String::from("65536"),
+            }
+        });
+    static ref REPLACE_PORT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(["0", "-1", "", "22", "6379", "11211", "2375"][(seed % 7) as usize]));
+    static ref PAD_PORT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, port| match seed % 3 {
+            0 => format!("000{}", port),
+            1 => format!("+{}", port),
+            _ => format!(" {}", port),
+        });
+    static ref INVALID_CHAR_IN_PORT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, port| insert_string_in_string(seed, &port, pick(seed, &[":", "@", ".", "x", "\u{664}", "\u{ff18}"])));
+    pub static ref PORT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::port",
+        initial_node: &START_PORT,
+        generator: |seed| String::from(["80", "443", "8080"][(seed % 3) as usize]),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PORT_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| PORT_AUTOMATON.traverse(String::from("80"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn ports_overflow() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "65616"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "4294967376"));
+    }
+}
@@ -0,0 +1,89 @@
+use super::super::helper::*;
+use super::{percent_encode, ENCODINGS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_QUERY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &POLLUTE_PARAMETER),
+            (2, &ADD_QUERY_ENCODING),
+            (1, &ENCODE_QUERY),
+            (1, &REDIRECT_PARAMETER),
+            (1, &CHANGE_DELIMITERS),
+        ]);
+    // the same parameter twice, of which each component may pick a different
+    // one
+    static ref POLLUTE_PARAMETER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, query| match query.split('&').next() {
+            Some(first) if first.contains('=') => format!("{}&{}", query, first),
+            _ => format!("{}&a={}&a=", query, seed % 10),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &ADD_QUERY_ENCODING)]);
+    static ref ADD_QUERY_ENCODING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, query| insert_string_in_string(seed, &query, QUERY_ENCODINGS[(seed % QUERY_ENCODINGS.len() as u64) as usize]));
+    static ref ENCODE_QUERY: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, query| percent_encode(seed, &query));
+    static ref REDIRECT_PARAMETER: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, query| format!("{}&{}", query, [
+            "url=http://127.0.0.1/",
+            "next=//evil.example",
+            "redirect=/\\evil.example",
+            "callback=http://169.254.169.254/latest/meta-data/",
+        ][(seed % 4) as usize]));
+    static ref CHANGE_DELIMITERS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, query| query.replace('&', [";", "&&", "%26", "&amp;"][(seed % 4) as usize]));
+    pub static ref QUERY_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::query",
+        initial_node: &START_QUERY,
+        generator: |seed| format!("id={}&page=1", seed % 100),
+    };
+
+    static ref START_FRAGMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (2, &CONFUSE_FRAGMENT),
+            (1, &ADD_FRAGMENT_ENCODING),
+            (1, &ENCODE_FRAGMENT),
+        ]);
+    // fragments which a parser not splitting on "#" reads as part of the
+    // authority or path
+    static ref CONFUSE_FRAGMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, fragment| format!("{}{}", fragment, ["@evil.example", "/../../", "?a=b", "#", "\\@evil.example"][(seed % 5) as usize]));
+    static ref ADD_FRAGMENT_ENCODING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, fragment| insert_string_in_string(seed, &fragment, ENCODINGS[(seed % ENCODINGS.len() as u64) as usize]));
+    static ref ENCODE_FRAGMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, fragment| percent_encode(seed, &fragment));
+    pub static ref FRAGMENT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::fragment",
+        initial_node: &START_FRAGMENT,
+        generator: |seed| format!("section-{}", seed % 10),
+    };
+}
+
+/// Encoded delimiters of the query along with malformed encodings
+const QUERY_ENCODINGS: [&str; 8] = ["%26", "%3d", "%23", "+", "%2b", "%", "%zz", "%00"];
+
+#[cfg(test)]
+mod tests {
+    use super::{FRAGMENT_AUTOMATON, QUERY_AUTOMATON};
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| QUERY_AUTOMATON.traverse(String::from("a=b"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn parameters_are_polluted() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "a=b&a=b"));
+    }
+
+    #[test]
+    fn fragments_are_confused() {
+        assert!((1..100)
+            .map(|i| FRAGMENT_AUTOMATON.traverse(String::new(), i))
+            .any(|value| value == "@evil.example"));
+    }
+}
@@ -0,0 +1,76 @@
+use super::super::helper::*;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_SCHEME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (4, &REPLACE_SCHEME),
+            (2, &CASED_SCHEME),
+            (2, &CONTROL_CHAR_IN_SCHEME),
+            (1, &REMOVE_SCHEME),
+        ]);
+    static ref REPLACE_SCHEME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(SCHEMES[(seed % SCHEMES.len() as u64) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &CASED_SCHEME), (1, &CONTROL_CHAR_IN_SCHEME)]);
+    static ref CASED_SCHEME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(to_random_case);
+    // browsers and some libraries strip these, e.g. "java\tscript"
+    static ref CONTROL_CHAR_IN_SCHEME: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, scheme| match seed % 3 {
+            0 => format!("{}{}", [" ", "\0", "\x01", "\u{feff}"][(seed / 3 % 4) as usize], scheme),
+            _ => insert_random_char_from_range_in_string(seed, &scheme, "\t\r\n\0 "),
+        });
+    static ref REMOVE_SCHEME: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    pub static ref SCHEME_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::scheme",
+        initial_node: &START_SCHEME,
+        generator: |seed| String::from(SCHEMES[(seed % 2) as usize]),
+    };
+}
+
+/// Schemes which reach internal services or local files, run scripts or are
+/// handled by other parsers than the one for HTTP
+const SCHEMES: [&str; 14] = [
+    "http",
+    "https",
+    "file",
+    "gopher",
+    "dict",
+    "ftp",
+    "ldap",
+    "jar",
+    "netdoc",
+    "data",
+    "javascript",
+    "vbscript",
+    "ws",
+    "h2c",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::SCHEME_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| SCHEME_AUTOMATON.traverse(String::from("https"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn schemes_are_replaced() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "gopher"));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "javascript"));
+    }
+
+    #[test]
+    fn schemes_get_control_chars() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains('\t') || value.starts_with('\0')));
+    }
+}
@@ -0,0 +1,57 @@
+use super::super::helper::*;
+use super::percent_encode;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_USERINFO: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &HOST_IN_USERINFO),
+            (2, &REPLACE_USERINFO),
+            (1, &ENCODE_USERINFO),
+            (1, &REMOVE_USERINFO),
+        ]);
+    // the part before the last "@" is the userinfo, yet some parsers split
+    // on the first one or stop at a backslash
+    static ref HOST_IN_USERINFO: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, userinfo| format!("{}{}", userinfo, [
+            "@evil.example",
+            ":@evil.example",
+            "\\@evil.example",
+            "@evil.example#",
+            "%40evil.example",
+            "@localhost:80",
+        ][(seed % 6) as usize]));
+    static ref REPLACE_USERINFO: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| String::from(["admin:admin", "root:", ":", "a:b:c", "user%3Apass", "evil.example"][(seed % 6) as usize]))
+        .set_edges(vec![(3, &FINAL), (1, &ENCODE_USERINFO)]);
+    static ref ENCODE_USERINFO: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|seed, userinfo| percent_encode(seed, &userinfo));
+    static ref REMOVE_USERINFO: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_func(|_, _| String::new());
+    pub static ref USERINFO_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "uri::userinfo",
+        initial_node: &START_USERINFO,
+        generator: |seed| format!("user{}:password", seed % 10),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::USERINFO_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| USERINFO_AUTOMATON.traverse(String::from("user"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn hosts_are_hidden_in_userinfo() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "user\\@evil.example"));
+    }
+}
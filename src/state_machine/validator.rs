@@ -0,0 +1,437 @@
+use super::{Automaton, AutomatonNode};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+/// Traversal stacks longer than this many times the number of states are
+/// not explored any further
+const MAX_STACK_FACTOR: usize = 4;
+
+/// Max number of distinct traversal stacks to be explored
+const MAX_EXPLORED_STATES: usize = 100_000;
+
+/// A problem found in an automaton definition. States are identified by
+/// their position in the automaton's breadth-first order, starting from 0
+/// for the start state.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Diagnostic {
+    /// `set_cycle` points further back than the number of states visited
+    /// before `node` when it's reached at `depth` - traversal panics with
+    /// "Invalid automaton definition!"
+    CycleOutOfRange {
+        node: usize,
+        cycle: usize,
+        depth: usize,
+    },
+    /// `node` is adjacent to some state, but no traversal ever visits it,
+    /// e.g. because it's only reachable through zero-weight edges other than
+    /// the first one of their state
+    UnreachableNode { node: usize },
+    /// All edges of `node` have weight 0 - choosing among them panics
+    ZeroWeightEdges { node: usize },
+    /// `node` has an edge to `target`, which precedes it (or is itself). If
+    /// both are initialized in a lazy_static block, this leads to deadlock.
+    SelfReference { node: usize, target: usize },
+    /// No final state can be reached once `node` is visited, so traversal
+    /// never ends
+    NoExit { node: usize },
+}
+
+impl<T: Eq + Clone + Sync> Automaton<T> {
+    /// Statically checks the automaton definition by walking its graph.
+    /// Returns the list of problems found, empty if the definition is valid.
+    ///
+    /// Meant to be run from unit tests, so that bad definitions are caught
+    /// before they show up at runtime.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let nodes = self.nodes();
+        let ids = self.node_ids();
+        let id_of = |node: &AutomatonNode<T>| ids.get(node);
+        let mut diagnostics = BTreeSet::new();
+
+        for (node_id, node) in nodes.iter().enumerate() {
+            if !node.edges.is_empty() && node.edges.iter().all(|(weight, _)| *weight == 0) {
+                diagnostics.insert(Diagnostic::ZeroWeightEdges { node: node_id });
+            }
+        }
+        self.find_self_references(&nodes, &id_of, &mut diagnostics);
+        self.explore_traversals(&nodes, &id_of, &mut diagnostics);
+
+        diagnostics.into_iter().collect()
+    }
+
+    /// Looks for edges pointing back to preceding states with a depth-first
+    /// search over the adjacency graph
+    fn find_self_references<F: Fn(&AutomatonNode<T>) -> usize>(
+        &self,
+        nodes: &[&'static AutomatonNode<T>],
+        id_of: &F,
+        diagnostics: &mut BTreeSet<Diagnostic>,
+    ) {
+        let mut finished = vec![false; nodes.len()];
+        let mut on_path = vec![false; nodes.len()];
+        // (node, index of the next edge to be checked)
+        let mut dfs_stack = vec![(0, 0)];
+        on_path[0] = true;
+        while let Some((node_id, edge_idx)) = dfs_stack.pop() {
+            match nodes[node_id].edges.get(edge_idx) {
+                Some((_, next)) => {
+                    dfs_stack.push((node_id, edge_idx + 1));
+                    let next_id = id_of(next);
+                    if on_path[next_id] {
+                        diagnostics.insert(Diagnostic::SelfReference {
+                            node: node_id,
+                            target: next_id,
+                        });
+                    } else if !finished[next_id] {
+                        on_path[next_id] = true;
+                        dfs_stack.push((next_id, 0));
+                    }
+                }
+                None => {
+                    on_path[node_id] = false;
+                    finished[node_id] = true;
+                }
+            }
+        }
+    }
+
+    /// Returns the adjacent states of `node` that can be chosen by the
+    /// transition function. Zero-weight edges all share the same range of
+    /// seeds, so only the first one of them is ever chosen. If there are no
+    /// other edges, choosing panics and no state is returned.
+    fn taken_edges(
+        node: &AutomatonNode<T>,
+    ) -> impl Iterator<Item = &'static AutomatonNode<T>> + '_ {
+        let has_positive = node.edges.iter().any(|(weight, _)| *weight != 0);
+        let first_zero = node.edges.iter().position(|(weight, _)| *weight == 0);
+        node.edges
+            .iter()
+            .enumerate()
+            .filter(move |(idx, (weight, _))| {
+                has_positive && (*weight != 0 || Some(*idx) == first_zero)
+            })
+            .map(|(_, (_, next))| *next)
+    }
+
+    /// Explores the traversals of the automaton. Each explored state is a
+    /// stack of visited states as maintained by `traverse`, the top of the
+    /// stack being the current state. Stacks grow with each cycle iteration,
+    /// so the observed transitions are finally collapsed onto the states of
+    /// the automaton in order to look for cycles with no exit.
+    fn explore_traversals<F: Fn(&AutomatonNode<T>) -> usize>(
+        &self,
+        nodes: &[&'static AutomatonNode<T>],
+        id_of: &F,
+        diagnostics: &mut BTreeSet<Diagnostic>,
+    ) {
+        let max_depth = nodes.len() * MAX_STACK_FACTOR;
+        let mut explored: HashSet<Vec<usize>> = HashSet::new();
+        let mut pending = VecDeque::from(vec![vec![0]]);
+        let mut visited = vec![false; nodes.len()];
+        // states where a traversal may end, successfully or not
+        let mut exits = vec![false; nodes.len()];
+        let mut predecessors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); nodes.len()];
+        explored.insert(vec![0]);
+
+        while let Some(stack) = pending.pop_front() {
+            let node_id = stack[stack.len() - 1];
+            let node = nodes[node_id];
+            visited[node_id] = true;
+
+            let next_stacks: Vec<Vec<usize>> = if node.cycle != 0 {
+                if node.cycle >= stack.len() {
+                    diagnostics.insert(Diagnostic::CycleOutOfRange {
+                        node: node_id,
+                        cycle: node.cycle,
+                        depth: stack.len(),
+                    });
+                    vec![]
+                } else {
+                    let mut truncated = stack[..stack.len() - node.cycle].to_vec();
+                    truncated.push(truncated[truncated.len() - 1]);
+                    vec![truncated]
+                }
+            } else {
+                Self::taken_edges(node)
+                    .map(|next| {
+                        let mut longer = stack.clone();
+                        longer.push(id_of(next));
+                        longer
+                    })
+                    .collect()
+            };
+
+            if next_stacks.is_empty() {
+                exits[node_id] = true;
+            }
+            for next_stack in next_stacks {
+                predecessors[next_stack[next_stack.len() - 1]].insert(node_id);
+                if next_stack.len() <= max_depth
+                    && explored.len() < MAX_EXPLORED_STATES
+                    && explored.insert(next_stack.clone())
+                {
+                    pending.push_back(next_stack);
+                }
+            }
+        }
+
+        for (node_id, _) in visited.iter().enumerate().filter(|(_, seen)| !**seen) {
+            diagnostics.insert(Diagnostic::UnreachableNode { node: node_id });
+        }
+
+        let mut can_exit = exits.clone();
+        let mut pending: VecDeque<usize> = (0..nodes.len()).filter(|n| exits[*n]).collect();
+        while let Some(node_id) = pending.pop_front() {
+            for previous in &predecessors[node_id] {
+                if !can_exit[*previous] {
+                    can_exit[*previous] = true;
+                    pending.push_back(*previous);
+                }
+            }
+        }
+        for node_id in (0..nodes.len()).filter(|n| visited[*n] && !can_exit[*n]) {
+            diagnostics.insert(Diagnostic::NoExit { node: node_id });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Diagnostic;
+    use crate::state_machine::json::{
+        array::ARRAY_AUTOMATON, boolean::BOOL_AUTOMATON, nesting::NESTING_AUTOMATON,
+        null::NULL_AUTOMATON, number::NUMBER_AUTOMATON, object::OBJECT_AUTOMATON,
+        string::STRING_AUTOMATON,
+    };
+    use crate::state_machine::yaml::{
+        flow_scalar::FLOW_SCALAR_AUTOMATON, indentation::INDENTATION_AUTOMATON,
+        nesting::NESTING_AUTOMATON as YAML_NESTING_AUTOMATON,
+    };
+    use crate::state_machine::{
+        binary, bytes, csv, helper::FINAL, http, json, json5, markdown, protobuf, toml, unicode,
+        uri, xml, Automaton, AutomatonNode,
+    };
+    use std::collections::BTreeSet;
+
+    lazy_static! {
+        static ref VALID_FIRST: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edges(vec![(1, &VALID_SECOND), (1, &FINAL)]);
+        static ref VALID_SECOND: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_cycle(1);
+        static ref VALID_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "valid",
+            initial_node: &VALID_FIRST,
+            generator: |seed| seed.to_string(),
+        };
+        static ref OUT_OF_RANGE: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edges(vec![(1, &OUT_OF_RANGE_CYCLE), (1, &FINAL)]);
+        static ref OUT_OF_RANGE_CYCLE: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_cycle(2);
+        static ref OUT_OF_RANGE_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "out_of_range",
+            initial_node: &OUT_OF_RANGE,
+            generator: |seed| seed.to_string(),
+        };
+        static ref ZERO_WEIGHTS: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edges(vec![(0, &FINAL), (0, &FINAL)]);
+        static ref ZERO_WEIGHTS_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "zero_weights",
+            initial_node: &ZERO_WEIGHTS,
+            generator: |seed| seed.to_string(),
+        };
+        static ref ENDLESS: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edge(&ENDLESS_CYCLE);
+        static ref ENDLESS_CYCLE: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_cycle(1);
+        static ref ENDLESS_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "endless",
+            initial_node: &ENDLESS,
+            generator: |seed| seed.to_string(),
+        };
+        static ref SHADOWING: AutomatonNode<String> = AutomatonNode::<String>::new()
+            .set_edges(vec![(0, &SHADOWING_ZERO), (0, &SHADOWED_ZERO), (1, &FINAL)]);
+        static ref SHADOWING_ZERO: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edge(&FINAL);
+        static ref SHADOWED_ZERO: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edge(&FINAL);
+        static ref UNREACHABLE_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "unreachable",
+            initial_node: &SHADOWING,
+            generator: |seed| seed.to_string(),
+        };
+        static ref SELF_REFERENCE: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edge(&SELF_REFERENCE_NEXT);
+        static ref SELF_REFERENCE_NEXT: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edges(vec![(1, &SELF_REFERENCE_ALIAS), (1, &FINAL)]);
+        static ref SELF_REFERENCE_ALIAS: AutomatonNode<String> = AutomatonNode::<String>::new();
+    }
+
+    #[test]
+    fn predefined_automata_are_valid() {
+        let automata: &[&Automaton<String>] = &[
+            &ARRAY_AUTOMATON,
+            &BOOL_AUTOMATON,
+            &NESTING_AUTOMATON,
+            &NULL_AUTOMATON,
+            &NUMBER_AUTOMATON,
+            &OBJECT_AUTOMATON,
+            &STRING_AUTOMATON,
+            &FLOW_SCALAR_AUTOMATON,
+            &INDENTATION_AUTOMATON,
+            &YAML_NESTING_AUTOMATON,
+            &http::body::BODY_AUTOMATON,
+            &http::body::RESPONSE_BODY_AUTOMATON,
+            &http::header::HEADER_AUTOMATON,
+            &http::header::EXTRA_HEADERS_AUTOMATON,
+            &http::header::EXTRA_RESPONSE_HEADERS_AUTOMATON,
+            &http::header_name::HEADER_NAME_AUTOMATON,
+            &http::header_value::HEADER_VALUE_AUTOMATON,
+            &http::method::METHOD_AUTOMATON,
+            &http::request_line::REQUEST_LINE_AUTOMATON,
+            &http::target::TARGET_AUTOMATON,
+            &http::version::VERSION_AUTOMATON,
+            &http::json_response::JSON_RESPONSE_AUTOMATON,
+            &http::reason::REASON_AUTOMATON,
+            &http::status_code::STATUS_CODE_AUTOMATON,
+            &http::status_line::STATUS_LINE_AUTOMATON,
+            &uri::host::HOST_AUTOMATON,
+            &uri::path::PATH_AUTOMATON,
+            &uri::port::PORT_AUTOMATON,
+            &uri::query::QUERY_AUTOMATON,
+            &uri::query::FRAGMENT_AUTOMATON,
+            &uri::scheme::SCHEME_AUTOMATON,
+            &uri::userinfo::USERINFO_AUTOMATON,
+            &unicode::UNICODE_AUTOMATON,
+            &unicode::GRAPHEME_AUTOMATON,
+            &markdown::code::CODE_FENCE_AUTOMATON,
+            &markdown::code::CODE_SPAN_AUTOMATON,
+            &markdown::container::LIST_MARKER_AUTOMATON,
+            &markdown::container::QUOTE_MARKER_AUTOMATON,
+            &markdown::container::HEADING_MARKER_AUTOMATON,
+            &markdown::emphasis::EMPHASIS_AUTOMATON,
+            &markdown::entity::ENTITY_AUTOMATON,
+            &markdown::html::HTML_AUTOMATON,
+            &markdown::link::LINK_AUTOMATON,
+            &markdown::link::LINK_DEFINITION_AUTOMATON,
+            &markdown::link::AUTOLINK_AUTOMATON,
+            &markdown::table::TABLE_AUTOMATON,
+            &xml::attribute::ATTRIBUTE_AUTOMATON,
+            &xml::declaration::DECLARATION_AUTOMATON,
+            &xml::doctype::DOCTYPE_AUTOMATON,
+            &xml::element::ELEMENT_AUTOMATON,
+            &xml::markup::CDATA_AUTOMATON,
+            &xml::markup::COMMENT_AUTOMATON,
+            &xml::markup::PROCESSING_INSTRUCTION_AUTOMATON,
+            &xml::text::TEXT_AUTOMATON,
+            &csv::field::FIELD_AUTOMATON,
+            &csv::formula::FORMULA_AUTOMATON,
+            &csv::table::TABLE_AUTOMATON,
+            &toml::collection::ARRAY_AUTOMATON,
+            &toml::collection::INLINE_TABLE_AUTOMATON,
+            &toml::datetime::DATETIME_AUTOMATON,
+            &toml::key::KEY_AUTOMATON,
+            &toml::number::INTEGER_AUTOMATON,
+            &toml::number::FLOAT_AUTOMATON,
+            &toml::string::STRING_AUTOMATON,
+            &toml::table::TABLE_HEADER_AUTOMATON,
+            &toml::table::DOCUMENT_AUTOMATON,
+            &json5::array::ARRAY_AUTOMATON,
+            &json5::comment::COMMENT_AUTOMATON,
+            &json5::key::KEY_AUTOMATON,
+            &json5::number::NUMBER_AUTOMATON,
+            &json5::object::OBJECT_AUTOMATON,
+            &json5::string::STRING_AUTOMATON,
+            &json::ndjson::NDJSON_AUTOMATON,
+            &json::separator::COMMA_AUTOMATON,
+            &json::separator::COLON_AUTOMATON,
+            &json::separator::GAP_AUTOMATON,
+        ];
+        for automaton in automata {
+            assert_eq!(automaton.validate(), vec![], "{}", automaton.name());
+        }
+    }
+
+    #[test]
+    fn predefined_byte_automata_are_valid() {
+        let automata: &[&Automaton<Vec<u8>>] = &[
+            &binary::INT_AUTOMATON,
+            &binary::VARINT_AUTOMATON,
+            &binary::BITFIELD_AUTOMATON,
+            &binary::BLOB_AUTOMATON,
+            &binary::RECORD_AUTOMATON,
+            &protobuf::length::LENGTH_AUTOMATON,
+            &protobuf::length::BYTES_AUTOMATON,
+            &protobuf::message::FIELD_AUTOMATON,
+            &protobuf::message::MESSAGE_AUTOMATON,
+            &protobuf::scalar::VARINT_AUTOMATON,
+            &protobuf::scalar::FIXED_AUTOMATON,
+            &protobuf::tag::TAG_AUTOMATON,
+            &bytes::BYTES_AUTOMATON,
+        ];
+        for automaton in automata {
+            assert_eq!(automaton.validate(), vec![], "{}", automaton.name());
+        }
+    }
+
+    #[test]
+    fn valid_automaton_has_no_diagnostics() {
+        assert!(VALID_AUTOMATON.validate().is_empty());
+    }
+
+    #[test]
+    fn out_of_range_cycle_is_detected() {
+        assert_eq!(
+            OUT_OF_RANGE_AUTOMATON.validate(),
+            vec![Diagnostic::CycleOutOfRange {
+                node: 1,
+                cycle: 2,
+                depth: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn zero_weight_only_edges_are_detected() {
+        assert!(ZERO_WEIGHTS_AUTOMATON
+            .validate()
+            .contains(&Diagnostic::ZeroWeightEdges { node: 0 }));
+    }
+
+    #[test]
+    fn cycles_without_exit_are_detected() {
+        let diagnostics = ENDLESS_AUTOMATON.validate();
+        assert!(diagnostics.contains(&Diagnostic::NoExit { node: 0 }));
+        assert!(diagnostics.contains(&Diagnostic::NoExit { node: 1 }));
+    }
+
+    #[test]
+    fn self_references_are_detected() {
+        // lazy_static can't express a reference to a preceding state without
+        // deadlocking, so the last state stands in for the start state
+        let nodes: [&'static AutomatonNode<String>; 3] =
+            [&SELF_REFERENCE, &SELF_REFERENCE_NEXT, &FINAL];
+        let id_of = |node: &AutomatonNode<String>| {
+            if std::ptr::eq(node, &*SELF_REFERENCE_ALIAS) {
+                0
+            } else {
+                nodes
+                    .iter()
+                    .position(|known| std::ptr::eq(*known, node))
+                    .unwrap()
+            }
+        };
+        let mut diagnostics = BTreeSet::new();
+        VALID_AUTOMATON.find_self_references(&nodes, &id_of, &mut diagnostics);
+        assert_eq!(
+            diagnostics.into_iter().collect::<Vec<_>>(),
+            vec![Diagnostic::SelfReference { node: 1, target: 0 }]
+        );
+    }
+
+    #[test]
+    fn states_behind_shadowed_zero_weight_edges_are_unreachable() {
+        assert_eq!(
+            UNREACHABLE_AUTOMATON.validate(),
+            vec![Diagnostic::UnreachableNode { node: 2 }]
+        );
+    }
+}
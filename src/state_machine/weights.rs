@@ -1,7 +1,12 @@
-use super::{AutomatonNode, Transition};
+use super::{AutomatonNode, NodeIds, Transition};
+use crate::adaptive::{AdaptiveWeights, EdgeId};
 use crate::configuration::{Config, Configurable};
 use itertools::Itertools;
 
+/// Learned multipliers are fractional, so predefined weights are scaled up
+/// before being multiplied in order to preserve the proportions
+const ADAPTIVE_WEIGHT_SCALE: f64 = 16.0;
+
 lazy_static! {
     pub static ref CONFIG: Config = Config::new();
 }
@@ -17,11 +22,11 @@ pub fn choose<T: Clone + Sync>(vec: Vec<WeightedTransition<T>>) -> Transition<T>
 /// It is a function of the seed, weights and v_coef:
 ///
 /// - the seed is expected to vary for each run in order to guarantee diverse
-/// output values
+///   output values
 /// - the weights are the predefined proportions at which each transition is
-/// likely to happen
+///   likely to happen
 /// - v_coef is a configurable value which determines the degree
-/// of the extremeness of the output
+///   of the extremeness of the output
 ///
 /// The output of the function is another automaton state (or
 /// no state if the current state is final).
@@ -89,9 +94,74 @@ impl<T: 'static + Clone + Sync> TransitionChoice<T> {
     }
 }
 
+/// Transition choice based on the predefined weights adjusted by learned
+/// multipliers, see [`AdaptiveWeights`]. Each choice is recorded in `trace`
+/// so that the chosen edges can be rewarded later.
+pub(crate) struct AdaptiveChoice<'w> {
+    automaton: &'static str,
+    ids: &'w NodeIds,
+    weights: &'w AdaptiveWeights,
+    trace: &'w mut Vec<EdgeId>,
+    v_coef: u32,
+}
+
+impl<'w> AdaptiveChoice<'w> {
+    /// `ids` are the ids of the states of the automaton named `automaton`
+    pub fn new(
+        automaton: &'static str,
+        ids: &'w NodeIds,
+        weights: &'w AdaptiveWeights,
+        trace: &'w mut Vec<EdgeId>,
+    ) -> Self {
+        Self {
+            automaton,
+            ids,
+            weights,
+            trace,
+            v_coef: CONFIG.get_vertical_randomness_coef(),
+        }
+    }
+
+    /// Chooses the state to follow `node` based on the seed and the adjusted
+    /// weights of the reachable states.
+    pub fn choose<T: Clone + Sync>(
+        &mut self,
+        node: &'static AutomatonNode<T>,
+        seed: u64,
+    ) -> Option<&'static AutomatonNode<T>> {
+        if node.edges.len() < 2 {
+            return (node.transition)(seed);
+        }
+        let node_id = self.ids.get(node);
+        let adapted = node
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(edge, (weight, next))| {
+                let multiplier = self.weights.multiplier(self.automaton, node_id, edge);
+                let scaled = (*weight as f64 * multiplier * ADAPTIVE_WEIGHT_SCALE).round();
+                (scaled.max(1.0) as u32, Some(*next))
+            })
+            .collect();
+        let next = TransitionChoice::new(adapted, self.v_coef).choice_func(seed);
+        if let Some(edge) = next.and_then(|next| {
+            node.edges
+                .iter()
+                .position(|(_, known)| std::ptr::eq(*known, next))
+        }) {
+            self.trace.push(EdgeId {
+                automaton: self.automaton,
+                node: node_id,
+                edge,
+            });
+        }
+        next
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::state_machine::Transformation;
+    use crate::state_machine::{Automaton, Transformation};
 
     use super::*;
 
@@ -291,4 +361,61 @@ mod tests {
             .transformation;
         assert_eq!(func(0, String::new()), "Test1");
     }
+
+    lazy_static! {
+        static ref TEST_START: AutomatonNode<String> =
+            AutomatonNode::<String>::new().set_edges(vec![(1, &TEST_NODE1), (1, &TEST_NODE2)]);
+        static ref TEST_AUTOMATON: Automaton<String> = Automaton::<String> {
+            name: "test",
+            initial_node: &TEST_START,
+            generator: |_| String::new(),
+        };
+    }
+
+    fn count_adaptive_helper(weights: &AdaptiveWeights, expected: &str) -> usize {
+        (0..1000)
+            .filter(|seed| {
+                TEST_AUTOMATON.traverse_instrumented(
+                    String::new(),
+                    *seed,
+                    Some(weights),
+                    &mut vec![],
+                    None,
+                ) == expected
+            })
+            .count()
+    }
+
+    #[test]
+    fn adaptive_traversal_records_chosen_edges() {
+        let mut trace = vec![];
+        let result = TEST_AUTOMATON.traverse_instrumented(
+            String::new(),
+            1,
+            Some(&AdaptiveWeights::default()),
+            &mut trace,
+            None,
+        );
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].automaton, "test");
+        assert_eq!(trace[0].node, 0);
+        assert_eq!(result, if trace[0].edge == 0 { "Test1" } else { "Test2" });
+    }
+
+    #[test]
+    fn rewarded_edges_are_chosen_more_often() {
+        let mut weights = AdaptiveWeights::new(1.0, 0.0);
+        let before = count_adaptive_helper(&weights, "Test2");
+        for _ in 0..4 {
+            weights.update(
+                &[EdgeId {
+                    automaton: "test",
+                    node: 0,
+                    edge: 1,
+                }],
+                true,
+            );
+        }
+        assert!(count_adaptive_helper(&weights, "Test2") > before);
+    }
 }
@@ -0,0 +1,128 @@
+use super::super::helper::{pick, FINAL};
+use super::ENTITY_REFERENCES;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_ATTRIBUTE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DUPLICATE_ATTRIBUTE),
+            (3, &CONFUSE_NAMESPACE),
+            (3, &INJECT_VALUE),
+            (1, &SWITCH_QUOTES),
+        ]);
+    // attributes which aren't unique, which parsers either reject or
+    // resolve to the first or the last one
+    static ref DUPLICATE_ATTRIBUTE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, attribute| {
+            let (name, value) = split_attribute(&attribute);
+            let local = name.rsplit(':').next().unwrap_or(name);
+            match seed % 4 {
+                0 => format!("{} {}", attribute, attribute),
+                1 => format!("{} {}=\"_evil\"", attribute, name),
+                2 => format!("{}=\"_evil\" {}", name, attribute),
+                // the same attribute once the prefix is resolved
+                _ => format!("{} xmlns:dup=\"\" dup:{}={}", attribute, local, value),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ATTRIBUTE)]);
+    // namespace declarations which rebind prefixes or undeclare them
+    static ref CONFUSE_NAMESPACE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, attribute| {
+            let (name, value) = split_attribute(&attribute);
+            match (seed % 4, name.strip_prefix("xmlns")) {
+                (0, Some(prefix)) => format!("xmlns{}=\"{}\"", prefix, pick(seed / 4, &["", "http://evil.example/ns", "http://www.w3.org/XML/1998/namespace"])),
+                (1, Some(prefix)) => format!("{} xmlns{}=\"http://evil.example/ns\"", attribute, prefix),
+                (0, None) | (1, None) => format!("undeclared:{}={}", name, value),
+                (2, _) => format!("{} xmlns:{}=\"http://evil.example/ns\"", attribute, pick(seed / 4, &["xml", "xmlns", "ds", "saml"])),
+                _ => format!("{} xmlns=\"\"", attribute),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ATTRIBUTE)]);
+    // values which reference external or exponentially expanding entities,
+    // which break out of the attribute or which are normalized
+    static ref INJECT_VALUE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, attribute| {
+            let (name, value) = split_attribute(&attribute);
+            let quote = value.chars().next().unwrap_or('"');
+            let injected = match seed % 2 {
+                0 => pick(seed / 2, &ENTITY_REFERENCES),
+                _ => pick(seed / 2, &["\"><script>alert(1)</script>", "'", "<", "&", "\n\t\r", "&#10;&#9;", "javascript:alert(1)", "&lt;!--"]),
+            };
+            format!("{}={}{}{}", name, quote, injected, quote)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ATTRIBUTE)]);
+    static ref SWITCH_QUOTES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, attribute| {
+            let (name, value) = split_attribute(&attribute);
+            let inner = value.get(1..value.len().saturating_sub(1)).unwrap_or(value);
+            match seed % 3 {
+                0 => format!("{}={}", name, inner),
+                1 => format!("{}='{}\"", name, inner),
+                _ => format!("{}=`{}`", name, inner),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ATTRIBUTE)]);
+    static ref MORE_ATTRIBUTE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref ATTRIBUTE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::attribute",
+        initial_node: &START_ATTRIBUTE,
+        generator: |seed| String::from(pick(seed, &[
+            "ID=\"_1\"",
+            "xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\"",
+            "Algorithm='http://www.w3.org/2001/10/xml-exc-c14n#'",
+        ])),
+    };
+}
+
+/// Splits an attribute into its name and its quoted value
+fn split_attribute(attribute: &str) -> (&str, &str) {
+    match attribute.find('=') {
+        Some(end) => (
+            attribute[..end].trim_end(),
+            attribute[end + 1..].trim_start(),
+        ),
+        None => (attribute, "\"\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ATTRIBUTE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| ATTRIBUTE_AUTOMATON.traverse(String::from("ID=\"_1\""), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn attributes_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "ID=\"_1\" ID=\"_1\""));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "ID=\"_1\" xmlns:dup=\"\" dup:ID=\"_1\""));
+    }
+
+    #[test]
+    fn namespaces_are_confused() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "ID=\"_1\" xmlns:xml=\"http://evil.example/ns\""));
+        let fuzzed: Vec<String> = (1..100)
+            .map(|i| ATTRIBUTE_AUTOMATON.traverse(String::from("xmlns:ds=\"urn:a\""), i))
+            .collect();
+        assert!(fuzzed.iter().any(|value| value == "xmlns:ds=\"\""));
+    }
+
+    #[test]
+    fn entities_are_referenced() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "ID=\"&xxe;\""));
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value == "ID=\"<\""));
+    }
+}
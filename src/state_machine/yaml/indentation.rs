@@ -29,6 +29,7 @@ lazy_static! {
         })
         .set_cycle(1);
     pub static ref INDENTATION_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "yaml::indentation",
         initial_node: &START_INDENTATION,
         generator: |_| String::from("  "),
     };
@@ -1,2 +1,3 @@
 pub mod flow_scalar;
 pub mod indentation;
+pub mod nesting;
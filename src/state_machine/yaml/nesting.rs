@@ -0,0 +1,72 @@
+use crate::state_machine::json::nesting::{nest, nest_in_mixed, nesting_depth, UNBALANCE_CLOSERS};
+use crate::state_machine::{Automaton, AutomatonNode};
+
+lazy_static! {
+    static ref START_NESTING: AutomatonNode<String> =
+        AutomatonNode::<String>::new().set_edges(vec![
+            (1, &NEST_IN_FLOW_SEQUENCES),
+            (1, &NEST_IN_FLOW_MAPPINGS),
+            (1, &NEST_IN_FLOW_MIXED),
+            (1, &NEST_IN_BLOCK_SEQUENCES),
+            (1, &NEST_IN_COMPLEX_KEYS),
+            (1, &NEST_IN_BLOCK_MIXED),
+        ]);
+    static ref NEST_IN_FLOW_SEQUENCES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 0, &FLOW_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    static ref NEST_IN_FLOW_MAPPINGS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 1, &FLOW_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    static ref NEST_IN_FLOW_MIXED: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest_in_mixed(seed, &text, &FLOW_COLLECTIONS))
+        .set_edge(&UNBALANCE_CLOSERS);
+    // compact nested sequences, e.g. "- - - a", so that depth doesn't
+    // require indentation
+    static ref NEST_IN_BLOCK_SEQUENCES: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 0, &BLOCK_COLLECTIONS));
+    static ref NEST_IN_COMPLEX_KEYS: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest(&text, nesting_depth(seed), |_| 1, &BLOCK_COLLECTIONS));
+    static ref NEST_IN_BLOCK_MIXED: AutomatonNode<String> = AutomatonNode::<String>::new()
+        .set_func(|seed, text| nest_in_mixed(seed, &text, &BLOCK_COLLECTIONS));
+    pub static ref NESTING_AUTOMATON: Automaton<String> = Automaton::<String> {
+        name: "yaml::nesting",
+        initial_node: &START_NESTING,
+        generator: |_| String::from("a"),
+    };
+}
+
+/// (opening, closing) parts of the flow collections a YAML node can be
+/// nested in
+const FLOW_COLLECTIONS: [(&str, &str); 2] = [("[", "]"), ("{a: ", "}")];
+
+/// Compact block collections a YAML node can be nested in - sequence entries
+/// and complex mapping keys
+const BLOCK_COLLECTIONS: [(&str, &str); 2] = [("- ", ""), ("? ", "")];
+
+#[cfg(test)]
+mod tests {
+    use super::NESTING_AUTOMATON;
+    use itertools::Itertools;
+
+    lazy_static! {
+        static ref TEST_FUZZ_VALUES: Vec<String> = (1..100)
+            .map(|i| NESTING_AUTOMATON.generate(i))
+            .sorted()
+            .collect();
+    }
+
+    #[test]
+    fn nesting_automaton_is_seedable() {
+        assert_ne!(TEST_FUZZ_VALUES.last(), TEST_FUZZ_VALUES.first());
+    }
+
+    #[test]
+    fn block_and_flow_collections_are_nested() {
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("- - ")));
+        assert!(TEST_FUZZ_VALUES
+            .iter()
+            .any(|value| value.starts_with("{a: {a: ")));
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Statistics about a fuzzing run, collected by generators and mutators
+/// upon request.
+///
+/// Automaton states are identified by the name of their automaton and their
+/// position in the automaton's breadth-first order, starting from 0 for the
+/// start state. Edges are additionally identified by their position amongst
+/// the adjacent states of the state they start from, i.e. their position in
+/// the corresponding `set_edges` list.
+///
+/// The collected data is meant to help with tuning the h-coef and v-coef
+/// values - e.g. if edge cases are rarely visited, v-coef should be increased.
+#[derive(Clone, Debug, Default)]
+pub struct Statistics {
+    outputs: u64,
+    node_visits: BTreeMap<(&'static str, usize), u64>,
+    edge_choices: BTreeMap<(&'static str, usize, usize), u64>,
+    cycle_iterations: BTreeMap<(&'static str, usize), u64>,
+    tokens_chosen: BTreeMap<&'static str, u64>,
+    output_lengths: BTreeMap<usize, u64>,
+}
+
+impl Statistics {
+    /// Returns the number of produced fuzz values
+    pub fn outputs(&self) -> u64 {
+        self.outputs
+    }
+
+    /// Returns the number of visits per automaton state
+    pub fn node_visits(&self) -> &BTreeMap<(&'static str, usize), u64> {
+        &self.node_visits
+    }
+
+    /// Returns the number of times each edge has been chosen
+    pub fn edge_choices(&self) -> &BTreeMap<(&'static str, usize, usize), u64> {
+        &self.edge_choices
+    }
+
+    /// Returns the number of times each cyclic state has led back to a
+    /// previously visited state
+    pub fn cycle_iterations(&self) -> &BTreeMap<(&'static str, usize), u64> {
+        &self.cycle_iterations
+    }
+
+    /// Returns the number of times tokens of each type were chosen for
+    /// mutation. The type of a token is the name of its automaton.
+    pub fn tokens_chosen(&self) -> &BTreeMap<&'static str, u64> {
+        &self.tokens_chosen
+    }
+
+    /// Returns a histogram of the lengths of the produced values. Lengths are
+    /// grouped in buckets of powers of two - each key is the lower bound of
+    /// its bucket, e.g. 8 stands for lengths from 8 to 15 (incl).
+    pub fn output_lengths(&self) -> &BTreeMap<usize, u64> {
+        &self.output_lengths
+    }
+
+    pub(crate) fn record_output(&mut self, length: usize) {
+        self.outputs += 1;
+        let bucket = match length {
+            0 => 0,
+            _ => 1 << length.ilog2(),
+        };
+        *self.output_lengths.entry(bucket).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_token(&mut self, automaton: &'static str) {
+        *self.tokens_chosen.entry(automaton).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_visit(&mut self, automaton: &'static str, node: usize) {
+        *self.node_visits.entry((automaton, node)).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_edge(&mut self, automaton: &'static str, node: usize, edge: usize) {
+        *self
+            .edge_choices
+            .entry((automaton, node, edge))
+            .or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_cycle(&mut self, automaton: &'static str, node: usize) {
+        *self.cycle_iterations.entry((automaton, node)).or_insert(0) += 1;
+    }
+}
+
+impl fmt::Display for Statistics {
+    /// Prints a human-readable report of the collected statistics
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Outputs: {}", self.outputs)?;
+
+        writeln!(f, "\nState visits:")?;
+        for ((automaton, node), cnt) in &self.node_visits {
+            writeln!(f, "  {} #{}: {}", automaton, node, cnt)?;
+        }
+
+        writeln!(f, "\nChosen edges:")?;
+        for ((automaton, node, edge), cnt) in &self.edge_choices {
+            writeln!(f, "  {} #{} -> edge {}: {}", automaton, node, edge, cnt)?;
+        }
+
+        writeln!(f, "\nCycle iterations:")?;
+        for ((automaton, node), cnt) in &self.cycle_iterations {
+            writeln!(f, "  {} #{}: {}", automaton, node, cnt)?;
+        }
+
+        writeln!(f, "\nTokens chosen for mutation:")?;
+        for (automaton, cnt) in &self.tokens_chosen {
+            writeln!(f, "  {}: {}", automaton, cnt)?;
+        }
+
+        writeln!(f, "\nOutput lengths:")?;
+        for (bucket, cnt) in &self.output_lengths {
+            let upper = match bucket {
+                0 => 0,
+                _ => bucket * 2 - 1,
+            };
+            writeln!(f, "  {}..={}: {}", bucket, upper, cnt)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Statistics;
+
+    #[test]
+    fn output_lengths_are_grouped_by_powers_of_two() {
+        let mut stats = Statistics::default();
+        for length in [0, 1, 2, 3, 4, 7, 8, 100] {
+            stats.record_output(length);
+        }
+        assert_eq!(stats.outputs(), 8);
+        let buckets: Vec<(usize, u64)> = stats
+            .output_lengths()
+            .iter()
+            .map(|(bucket, cnt)| (*bucket, *cnt))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![(0, 1), (1, 1), (2, 2), (4, 2), (8, 1), (64, 1)]
+        );
+    }
+
+    #[test]
+    fn records_are_counted() {
+        let mut stats = Statistics::default();
+        stats.record_visit("test", 1);
+        stats.record_visit("test", 1);
+        stats.record_edge("test", 1, 0);
+        stats.record_cycle("test", 2);
+        stats.record_token("test");
+        assert_eq!(stats.node_visits()[&("test", 1)], 2);
+        assert_eq!(stats.edge_choices()[&("test", 1, 0)], 1);
+        assert_eq!(stats.cycle_iterations()[&("test", 2)], 1);
+        assert_eq!(stats.tokens_chosen()["test"], 1);
+    }
+
+    #[test]
+    fn report_contains_all_sections() {
+        let mut stats = Statistics::default();
+        stats.record_visit("json::number", 3);
+        stats.record_output(5);
+        let report = stats.to_string();
+        assert!(report.contains("Outputs: 1"));
+        assert!(report.contains("json::number #3: 1"));
+        assert!(report.contains("4..=7: 1"));
+        assert!(report.contains("Tokens chosen for mutation:"));
+    }
+}
@@ -0,0 +1,499 @@
+//! This module is used for binary protocols, which unlike text ones cannot
+//! be described by a PEG. Their messages are split into byte ranges as per a
+//! declarative [`Layout`] of their fields, or by a protocol-specific
+//! [`BinaryTokenizer`].
+
+use crate::selector::child_path;
+use crate::state_machine::binary::{
+    decode_varint, encode_varint, BITFIELD_AUTOMATON, BLOB_AUTOMATON, INT_AUTOMATON,
+    RECORD_AUTOMATON, VARINT_AUTOMATON,
+};
+use crate::state_machine::Automaton;
+use std::convert::TryFrom;
+
+/// Tokenizer for binary protocols
+pub trait BinaryTokenizer {
+    /// Splits `input` into the tokens of its fields in pre-order, i.e. each
+    /// token comes before the ones it contains. Result will be
+    /// [`std::option::Option::None`] if `input` doesn't match the format.
+    fn tokenize(&self, input: &[u8]) -> Option<Vec<BinaryToken>>;
+}
+
+/// Representation of a single field of a binary message - characterized by:
+/// - its first position in the input bytes
+/// - its last position in the input bytes
+/// - its corresponding automaton, if it is fuzzed on its own
+/// - its path within the message as a JSON Pointer, e.g. "/options/0/value"
+/// - the indices of the tokens within it, in order
+/// - the index of the token whose length it holds along with the encoding of
+///   that length, if it is a length prefix
+pub struct BinaryToken {
+    pub from: usize,
+    pub to: usize,
+    pub automaton: Option<&'static Automaton<Vec<u8>>>,
+    pub path: String,
+    pub children: Vec<usize>,
+    pub length_of: Option<(usize, IntEncoding)>,
+}
+
+/// Byte order of a fixed-width integer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+/// Encoding of an integer, e.g. a tag or a length prefix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntEncoding {
+    /// An integer of the given width in bytes, from 1 to 8
+    Fixed(usize, Endianness),
+    /// A base 128 varint, as used by protobuf
+    Varint,
+}
+
+impl IntEncoding {
+    /// Decodes an integer at the start of `input`. Outputs the value along
+    /// with the number of bytes it takes.
+    pub fn decode(self, input: &[u8]) -> Option<(u64, usize)> {
+        match self {
+            Self::Fixed(width, endianness) if (1..=8).contains(&width) => {
+                let bytes = input.get(..width)?;
+                let value = match endianness {
+                    Endianness::Big => bytes
+                        .iter()
+                        .fold(0_u64, |value, &byte| value << 8 | u64::from(byte)),
+                    Endianness::Little => bytes
+                        .iter()
+                        .rev()
+                        .fold(0_u64, |value, &byte| value << 8 | u64::from(byte)),
+                };
+                Some((value, width))
+            }
+            Self::Fixed(..) => None,
+            Self::Varint => decode_varint(input),
+        }
+    }
+
+    /// Encodes `value`, truncating it if it doesn't fit a fixed width
+    pub fn encode(self, value: u64) -> Vec<u8> {
+        match self {
+            Self::Fixed(width, Endianness::Big) => value.to_be_bytes()[8 - width.min(8)..].to_vec(),
+            Self::Fixed(width, Endianness::Little) => value.to_le_bytes()[..width.min(8)].to_vec(),
+            Self::Varint => encode_varint(value),
+        }
+    }
+
+    fn automaton(self) -> &'static Automaton<Vec<u8>> {
+        match self {
+            Self::Fixed(..) => &INT_AUTOMATON,
+            Self::Varint => &VARINT_AUTOMATON,
+        }
+    }
+}
+
+/// Declarative description of a field of a binary message. Each field is
+/// named, which makes up the paths of the tokens within it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// An unsigned integer, e.g. a version or a message type
+    Int {
+        name: &'static str,
+        encoding: IntEncoding,
+    },
+    /// Flags or other values packed into bits, `width` bytes in total
+    Bitfield { name: &'static str, width: usize },
+    /// A blob of a fixed number of bytes, e.g. a magic number or a hash
+    Bytes { name: &'static str, length: usize },
+    /// A blob taking the rest of the enclosing field
+    Rest { name: &'static str },
+    /// A field preceded by its length in bytes
+    LengthPrefixed {
+        name: &'static str,
+        length: IntEncoding,
+        content: Box<Field>,
+    },
+    /// Type-length-value records taking the rest of the enclosing field, e.g.
+    /// TCP options
+    Tlv {
+        name: &'static str,
+        tag: IntEncoding,
+        length: IntEncoding,
+    },
+    /// A sequence of fields
+    Struct {
+        name: &'static str,
+        fields: Vec<Field>,
+    },
+    /// A field repeated until the end of the enclosing field. The elements'
+    /// paths are their indices rather than their names.
+    Repeated {
+        name: &'static str,
+        element: Box<Field>,
+    },
+}
+
+impl Field {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Int { name, .. }
+            | Self::Bitfield { name, .. }
+            | Self::Bytes { name, .. }
+            | Self::Rest { name }
+            | Self::LengthPrefixed { name, .. }
+            | Self::Tlv { name, .. }
+            | Self::Struct { name, .. }
+            | Self::Repeated { name, .. } => name,
+        }
+    }
+
+    fn automaton(&self) -> Option<&'static Automaton<Vec<u8>>> {
+        match self {
+            Self::Int { encoding, .. } => Some(encoding.automaton()),
+            Self::Bitfield { .. } => Some(&BITFIELD_AUTOMATON),
+            Self::Bytes { .. } | Self::Rest { .. } => Some(&BLOB_AUTOMATON),
+            Self::LengthPrefixed { .. } | Self::Struct { .. } => Some(&RECORD_AUTOMATON),
+            Self::Tlv { .. } | Self::Repeated { .. } => None,
+        }
+    }
+}
+
+/// Layout of a binary message, i.e. the sequence of its top-level fields
+///
+/// # Examples
+/// ```rust
+/// use talasum::binary::{Endianness, Field, IntEncoding, Layout};
+///
+/// let layout = Layout::new(vec![
+///     Field::Int { name: "version", encoding: IntEncoding::Fixed(1, Endianness::Big) },
+///     Field::LengthPrefixed {
+///         name: "payload",
+///         length: IntEncoding::Fixed(2, Endianness::Big),
+///         content: Box::new(Field::Rest { name: "data" }),
+///     },
+/// ]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    pub fn new(fields: Vec<Field>) -> Self {
+        Self { fields }
+    }
+}
+
+impl BinaryTokenizer for Layout {
+    fn tokenize(&self, input: &[u8]) -> Option<Vec<BinaryToken>> {
+        let mut tokens = vec![];
+        let mut pos = 0;
+        for field in &self.fields {
+            pos = tokenize_field(
+                field,
+                input,
+                pos,
+                input.len(),
+                child_path("", field.name()),
+                &mut tokens,
+            )?;
+        }
+        if pos == input.len() {
+            Some(tokens)
+        } else {
+            None
+        }
+    }
+}
+
+/// Pushes a token without any tokens within it
+fn push_leaf(
+    tokens: &mut Vec<BinaryToken>,
+    from: usize,
+    to: usize,
+    automaton: &'static Automaton<Vec<u8>>,
+    path: String,
+) -> usize {
+    tokens.push(BinaryToken {
+        from,
+        to,
+        automaton: Some(automaton),
+        path,
+        children: vec![],
+        length_of: None,
+    });
+    tokens.len() - 1
+}
+
+/// Pushes the tokens of a `field` starting at `from` and all tokens within
+/// it in pre-order. The field may not go past `end`. Outputs the position
+/// right after it, or None if `input` doesn't match it.
+fn tokenize_field(
+    field: &Field,
+    input: &[u8],
+    from: usize,
+    end: usize,
+    path: String,
+    tokens: &mut Vec<BinaryToken>,
+) -> Option<usize> {
+    let idx = tokens.len();
+    tokens.push(BinaryToken {
+        from,
+        to: from,
+        automaton: field.automaton(),
+        path: path.clone(),
+        children: vec![],
+        length_of: None,
+    });
+
+    let mut children = vec![];
+    let to = match field {
+        Field::Int { encoding, .. } => from + encoding.decode(&input[from..end])?.1,
+        Field::Bitfield { width, .. } | Field::Bytes { length: width, .. } => {
+            Some(from + width).filter(|&to| to <= end)?
+        }
+        Field::Rest { .. } => end,
+        Field::LengthPrefixed {
+            length, content, ..
+        } => {
+            let (prefix, content_from, content_end) =
+                tokenize_length(*length, input, from, end, path.clone(), tokens)?;
+            let content_idx = tokens.len();
+            let content_path = child_path(&path, content.name());
+            if tokenize_field(
+                content,
+                input,
+                content_from,
+                content_end,
+                content_path,
+                tokens,
+            )? != content_end
+            {
+                return None;
+            }
+            tokens[prefix].length_of = Some((content_idx, *length));
+            children.extend(vec![prefix, content_idx]);
+            content_end
+        }
+        Field::Tlv { tag, length, .. } => {
+            let mut pos = from;
+            while pos < end {
+                let record_path = child_path(&path, &children.len().to_string());
+                let record = push_leaf(tokens, pos, pos, &RECORD_AUTOMATON, record_path.clone());
+                let tag_size = tag.decode(&input[pos..end])?.1;
+                let tag_idx = push_leaf(
+                    tokens,
+                    pos,
+                    pos + tag_size,
+                    tag.automaton(),
+                    child_path(&record_path, "tag"),
+                );
+                let (prefix, value_from, value_end) = tokenize_length(
+                    *length,
+                    input,
+                    pos + tag_size,
+                    end,
+                    child_path(&record_path, "length"),
+                    tokens,
+                )?;
+                let value = push_leaf(
+                    tokens,
+                    value_from,
+                    value_end,
+                    &BLOB_AUTOMATON,
+                    child_path(&record_path, "value"),
+                );
+                tokens[prefix].length_of = Some((value, *length));
+                tokens[record].to = value_end;
+                tokens[record].children = vec![tag_idx, prefix, value];
+                children.push(record);
+                pos = value_end;
+            }
+            end
+        }
+        Field::Struct { fields, .. } => {
+            let mut pos = from;
+            for inner in fields {
+                children.push(tokens.len());
+                pos = tokenize_field(
+                    inner,
+                    input,
+                    pos,
+                    end,
+                    child_path(&path, inner.name()),
+                    tokens,
+                )?;
+            }
+            pos
+        }
+        Field::Repeated { element, .. } => {
+            let mut pos = from;
+            while pos < end {
+                children.push(tokens.len());
+                let element_path = child_path(&path, &(children.len() - 1).to_string());
+                let next = tokenize_field(element, input, pos, end, element_path, tokens)?;
+                // an empty element would be repeated forever
+                if next == pos {
+                    return None;
+                }
+                pos = next;
+            }
+            end
+        }
+    };
+
+    tokens[idx].to = to;
+    tokens[idx].children = children;
+    Some(to)
+}
+
+/// Pushes the token of a length prefix starting at `from`. Outputs its index
+/// along with the range of the bytes it holds the length of.
+fn tokenize_length(
+    length: IntEncoding,
+    input: &[u8],
+    from: usize,
+    end: usize,
+    path: String,
+    tokens: &mut Vec<BinaryToken>,
+) -> Option<(usize, usize, usize)> {
+    let (value, size) = length.decode(&input[from..end])?;
+    let content_from = from + size;
+    let content_end = content_from.checked_add(usize::try_from(value).ok()?)?;
+    if content_end > end {
+        return None;
+    }
+    let prefix = push_leaf(tokens, from, content_from, length.automaton(), path);
+    Some((prefix, content_from, content_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryTokenizer, Endianness, Field, IntEncoding, Layout};
+
+    fn layout() -> Layout {
+        Layout::new(vec![
+            Field::Int {
+                name: "version",
+                encoding: IntEncoding::Fixed(2, Endianness::Little),
+            },
+            Field::Bitfield {
+                name: "flags",
+                width: 1,
+            },
+            Field::LengthPrefixed {
+                name: "payload",
+                length: IntEncoding::Varint,
+                content: Box::new(Field::Rest { name: "data" }),
+            },
+            Field::Tlv {
+                name: "options",
+                tag: IntEncoding::Fixed(1, Endianness::Big),
+                length: IntEncoding::Fixed(1, Endianness::Big),
+            },
+        ])
+    }
+
+    #[test]
+    fn ints_are_decoded_and_encoded() {
+        let big = IntEncoding::Fixed(2, Endianness::Big);
+        let little = IntEncoding::Fixed(2, Endianness::Little);
+        assert_eq!(big.decode(&[1, 2, 3]), Some((0x0102, 2)));
+        assert_eq!(little.decode(&[1, 2, 3]), Some((0x0201, 2)));
+        assert_eq!(big.decode(&[1]), None);
+        assert_eq!(IntEncoding::Fixed(9, Endianness::Big).decode(&[0; 9]), None);
+        assert_eq!(big.encode(0x10203), vec![2, 3]);
+        assert_eq!(little.encode(0x10203), vec![3, 2]);
+        assert_eq!(IntEncoding::Varint.encode(300), vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn fields_are_tokenized() {
+        let tokens = layout()
+            .tokenize(&[1, 0, 0x80, 2, b'h', b'i', 3, 1, b'x', 4, 0])
+            .unwrap();
+        let ranges: Vec<(&str, usize, usize)> = tokens
+            .iter()
+            .map(|token| (token.path.as_str(), token.from, token.to))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("/version", 0, 2),
+                ("/flags", 2, 3),
+                ("/payload", 3, 6),
+                ("/payload", 3, 4),
+                ("/payload/data", 4, 6),
+                ("/options", 6, 11),
+                ("/options/0", 6, 9),
+                ("/options/0/tag", 6, 7),
+                ("/options/0/length", 7, 8),
+                ("/options/0/value", 8, 9),
+                ("/options/1", 9, 11),
+                ("/options/1/tag", 9, 10),
+                ("/options/1/length", 10, 11),
+                ("/options/1/value", 11, 11),
+            ]
+        );
+        assert_eq!(tokens[2].children, vec![3, 4]);
+        assert_eq!(tokens[3].length_of, Some((4, IntEncoding::Varint)));
+        assert_eq!(tokens[5].children, vec![6, 10]);
+        assert!(tokens[5].automaton.is_none());
+    }
+
+    #[test]
+    fn nested_fields_are_tokenized() {
+        let layout = Layout::new(vec![Field::Repeated {
+            name: "items",
+            element: Box::new(Field::Struct {
+                name: "item",
+                fields: vec![
+                    Field::Bytes {
+                        name: "id",
+                        length: 2,
+                    },
+                    Field::LengthPrefixed {
+                        name: "name",
+                        length: IntEncoding::Fixed(1, Endianness::Big),
+                        content: Box::new(Field::Rest { name: "text" }),
+                    },
+                ],
+            }),
+        }]);
+        let tokens = layout.tokenize(&[1, 2, 1, b'a', 3, 4, 0]).unwrap();
+        let paths: Vec<&str> = tokens.iter().map(|token| token.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/items",
+                "/items/0",
+                "/items/0/id",
+                "/items/0/name",
+                "/items/0/name",
+                "/items/0/name/text",
+                "/items/1",
+                "/items/1/id",
+                "/items/1/name",
+                "/items/1/name",
+                "/items/1/name/text",
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatched_input_is_not_tokenized() {
+        // truncated version
+        assert!(layout().tokenize(&[1]).is_none());
+        // payload longer than the message
+        assert!(layout().tokenize(&[1, 0, 0, 5, b'h']).is_none());
+        // unterminated varint
+        assert!(layout().tokenize(&[1, 0, 0, 0x80]).is_none());
+        // trailing bytes
+        assert!(Layout::new(vec![Field::Bytes {
+            name: "magic",
+            length: 1
+        }])
+        .tokenize(&[1, 2])
+        .is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use super::Automaton;
+use super::LexerRule;
+
+use crate::state_machine::csv::field::FIELD_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/csv.pest"]
+pub struct CsvLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::comma_field
+            | Rule::semicolon_field
+            | Rule::tab_field
+            | Rule::pipe_field
+            | Rule::column_field => Some(&FIELD_AUTOMATON),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{CsvLexer, Rule};
+
+    fn tokenize_csv_input_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<CsvLexer, Rule>(input, Rule::csv)
+    }
+
+    fn fields(input: &str) -> Vec<&str> {
+        tokenize_csv_input_helper(input)
+            .unwrap()
+            .iter()
+            .map(|token| &input[token.from..token.to])
+            .rev()
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_rfc_4180() {
+        assert_eq!(
+            fields("id,name\r\n1,\"a,\r\n\"\"b\"\"\"\r\n2,\r\n"),
+            vec!["id", "name", "1", "\"a,\r\n\"\"b\"\"\"", "2", ""]
+        );
+    }
+
+    #[test]
+    fn tokenize_dialects() {
+        assert_eq!(
+            fields("\u{feff}a;\"b;c\";d,e\nf;g"),
+            vec!["a", "\"b;c\"", "d,e", "f", "g"]
+        );
+        assert_eq!(fields("a\tb\r1\t2"), vec!["a", "b", "1", "2"]);
+        assert_eq!(fields("a|b\n"), vec!["a", "b"]);
+        assert_eq!(fields("a\n\"b,c\"\n"), vec!["a", "\"b,c\""]);
+    }
+
+    #[test]
+    fn tokenize_malformed_fields() {
+        assert_eq!(fields("a,\"b\"c,\"d\n"), vec!["a", "\"b\"c", "\"d"]);
+        assert!(tokenize_csv_input_helper("\u{0}\r\r\n").is_some());
+    }
+}
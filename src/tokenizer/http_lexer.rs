@@ -0,0 +1,153 @@
+use super::Automaton;
+use super::{LexerRule, PathRole};
+
+use crate::state_machine::http::body::{BODY_AUTOMATON, RESPONSE_BODY_AUTOMATON};
+use crate::state_machine::http::header::{
+    EXTRA_HEADERS_AUTOMATON, EXTRA_RESPONSE_HEADERS_AUTOMATON, HEADER_AUTOMATON,
+};
+use crate::state_machine::http::header_name::HEADER_NAME_AUTOMATON;
+use crate::state_machine::http::header_value::HEADER_VALUE_AUTOMATON;
+use crate::state_machine::http::method::METHOD_AUTOMATON;
+use crate::state_machine::http::reason::REASON_AUTOMATON;
+use crate::state_machine::http::request_line::REQUEST_LINE_AUTOMATON;
+use crate::state_machine::http::status_code::STATUS_CODE_AUTOMATON;
+use crate::state_machine::http::status_line::STATUS_LINE_AUTOMATON;
+use crate::state_machine::http::target::TARGET_AUTOMATON;
+use crate::state_machine::http::version::VERSION_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/http.pest"]
+pub struct HttpLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::request_line => Some(&REQUEST_LINE_AUTOMATON),
+            Rule::method => Some(&METHOD_AUTOMATON),
+            Rule::target => Some(&TARGET_AUTOMATON),
+            Rule::version => Some(&VERSION_AUTOMATON),
+            Rule::header => Some(&HEADER_AUTOMATON),
+            Rule::header_name => Some(&HEADER_NAME_AUTOMATON),
+            Rule::header_value => Some(&HEADER_VALUE_AUTOMATON),
+            Rule::headers_end => Some(&EXTRA_HEADERS_AUTOMATON),
+            Rule::body => Some(&BODY_AUTOMATON),
+            Rule::status_line => Some(&STATUS_LINE_AUTOMATON),
+            Rule::status_code => Some(&STATUS_CODE_AUTOMATON),
+            Rule::reason => Some(&REASON_AUTOMATON),
+            Rule::response_headers_end => Some(&EXTRA_RESPONSE_HEADERS_AUTOMATON),
+            Rule::response_body => Some(&RESPONSE_BODY_AUTOMATON),
+            _ => None,
+        }
+    }
+
+    fn pest_to_path_role(self) -> PathRole {
+        match &self {
+            Rule::header => PathRole::Member,
+            _ => PathRole::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{HttpLexer, Rule};
+
+    fn tokenize_http_input_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<HttpLexer, Rule>(input, Rule::request)
+    }
+
+    fn tokenize_http_response_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<HttpLexer, Rule>(input, Rule::response)
+    }
+
+    fn token_names(tokens: Option<Vec<AutomatonToken<'_>>>) -> Vec<&'static str> {
+        tokens
+            .unwrap()
+            .iter()
+            .map(|token| token.automaton.name())
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_request() {
+        assert_eq!(
+            token_names(tokenize_http_input_helper(
+                "POST /a HTTP/1.1\r\nHost: localhost\r\n\r\nb=c"
+            )),
+            vec![
+                "http::body",
+                "http::extra_headers",
+                "http::header_value",
+                "http::header_name",
+                "http::header",
+                "http::version",
+                "http::target",
+                "http::method",
+                "http::request_line",
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_request_with_bare_lf() {
+        let tokens = tokenize_http_input_helper("GET / HTTP/1.0\nA: b\nC:\n\n").unwrap();
+        assert_eq!(tokens.len(), 12);
+        assert_eq!((tokens[0].from, tokens[0].to), (24, 24));
+    }
+
+    #[test]
+    fn headers_have_paths() {
+        let tokens = tokenize_http_input_helper("GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["", "", "/Host", "/Host", "/Host", "", "", "", ""]
+        );
+    }
+
+    #[test]
+    fn tokenize_response() {
+        assert_eq!(
+            token_names(tokenize_http_response_helper(
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}"
+            )),
+            vec![
+                "http::response_body",
+                "http::extra_response_headers",
+                "http::header_value",
+                "http::header_name",
+                "http::header",
+                "http::reason",
+                "http::status_code",
+                "http::version",
+                "http::status_line",
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_response_without_reason() {
+        assert_eq!(
+            token_names(tokenize_http_response_helper("HTTP/1.0 204\n\n")),
+            vec![
+                "http::response_body",
+                "http::extra_response_headers",
+                "http::status_code",
+                "http::version",
+                "http::status_line",
+            ]
+        );
+        assert!(tokenize_http_response_helper("HTTP/1.1 20 OK\r\n\r\n").is_none());
+    }
+
+    #[test]
+    fn reject_invalid_requests() {
+        assert!(tokenize_http_input_helper("GET /\r\n\r\n").is_none());
+        assert!(tokenize_http_input_helper("GET / HTTP/1.1\r\nHost: a\r\n").is_none());
+        assert!(tokenize_http_input_helper("GET / HTTP/1.1\r\nHo st: a\r\n\r\n").is_none());
+    }
+}
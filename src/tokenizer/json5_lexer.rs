@@ -0,0 +1,126 @@
+use super::Automaton;
+use super::{LexerRule, PathRole};
+
+use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+use crate::state_machine::json::null::NULL_AUTOMATON;
+use crate::state_machine::json5::array::ARRAY_AUTOMATON;
+use crate::state_machine::json5::comment::COMMENT_AUTOMATON;
+use crate::state_machine::json5::key::KEY_AUTOMATON;
+use crate::state_machine::json5::number::NUMBER_AUTOMATON;
+use crate::state_machine::json5::object::OBJECT_AUTOMATON;
+use crate::state_machine::json5::string::STRING_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/json5.pest"]
+pub struct Json5Lexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::string => Some(&STRING_AUTOMATON),
+            Rule::key => Some(&KEY_AUTOMATON),
+            Rule::number => Some(&NUMBER_AUTOMATON),
+            Rule::boolean => Some(&BOOL_AUTOMATON),
+            Rule::object => Some(&OBJECT_AUTOMATON),
+            Rule::array => Some(&ARRAY_AUTOMATON),
+            Rule::null => Some(&NULL_AUTOMATON),
+            Rule::COMMENT => Some(&COMMENT_AUTOMATON),
+            _ => None,
+        }
+    }
+
+    fn pest_to_path_role(self) -> PathRole {
+        match &self {
+            Rule::pair => PathRole::Member,
+            Rule::array => PathRole::Elements,
+            Rule::COMMENT => PathRole::Skipped,
+            _ => PathRole::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{Json5Lexer, Rule};
+
+    fn tokenize_json5_input_helper(input: &str) -> Vec<AutomatonToken<'_>> {
+        super::super::tokenize_input::<Json5Lexer, Rule>(input, Rule::json5).unwrap()
+    }
+
+    fn token_names(input: &str) -> Vec<&'static str> {
+        tokenize_json5_input_helper(input)
+            .iter()
+            .map(|token| token.automaton.name())
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_json() {
+        assert_eq!(
+            token_names("{\"a\": [1, true, null]}"),
+            vec![
+                "json::null",
+                "json::boolean",
+                "json5::number",
+                "json5::array",
+                "json5::key",
+                "json5::object"
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_jsonc() {
+        let input = "{\n  // comment\n  \"a\": 1, /* another */\n}";
+        let result = tokenize_json5_input_helper(input);
+        let comments: Vec<&str> = result
+            .iter()
+            .filter(|token| token.automaton.name() == "json5::comment")
+            .map(|token| &input[token.from..token.to])
+            .collect();
+        assert_eq!(comments, vec!["/* another */", "// comment"]);
+    }
+
+    #[test]
+    fn tokenize_json5_literals() {
+        for input in [
+            "0x1F",
+            "-Infinity",
+            "+NaN",
+            ".5",
+            "5.",
+            "'single'",
+            "\"multi\\\nline\"",
+            "{unquoted: 1, $a_1: 2, 'single': 3,}",
+            "[1, 2,]",
+        ] {
+            assert!(!tokenize_json5_input_helper(input).is_empty(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn keys_are_single_tokens() {
+        let input = "{'a b': 1}";
+        let result = tokenize_json5_input_helper(input);
+        assert_eq!(result.len(), 3);
+        assert_eq!(&input[result[1].from..result[1].to], "'a b'");
+    }
+
+    #[test]
+    fn comments_are_not_elements() {
+        let result = tokenize_json5_input_helper("[/* a */ 1, {'b': 2, c: 3}]");
+        let paths: Vec<&str> = result.iter().map(|token| token.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["/1/c", "/1/c", "/1/b", "/1/b", "/1", "/0", "", ""]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn fail_to_tokenize_invalid_json5() {
+        tokenize_json5_input_helper("[1,,]");
+    }
+}
@@ -1,12 +1,13 @@
 use super::Automaton;
-use super::LexerRule;
+use super::{LexerRule, PathRole};
 
 use crate::state_machine::json::array::ARRAY_AUTOMATON;
 use crate::state_machine::json::boolean::BOOL_AUTOMATON;
 use crate::state_machine::json::null::NULL_AUTOMATON;
 use crate::state_machine::json::number::NUMBER_AUTOMATON;
 use crate::state_machine::json::object::OBJECT_AUTOMATON;
-use crate::state_machine::json::string::STRING_AUTOMATON;
+use crate::state_machine::json::separator::{COLON_AUTOMATON, COMMA_AUTOMATON, GAP_AUTOMATON};
+use crate::state_machine::json::string::{STRING_AUTOMATON, STRING_BYTES_AUTOMATON};
 
 #[derive(Parser)]
 //#[derive(Tokenizer)] // add a macro function that generates an alias function for parse
@@ -25,6 +26,31 @@ impl LexerRule for Rule {
             _ => None,
         }
     }
+
+    fn pest_to_byte_automaton(self) -> Option<&'static Automaton<Vec<u8>>> {
+        match &self {
+            Rule::string => Some(&STRING_BYTES_AUTOMATON),
+            _ => None,
+        }
+    }
+
+    fn pest_to_separator_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::comma => Some(&COMMA_AUTOMATON),
+            Rule::colon => Some(&COLON_AUTOMATON),
+            Rule::gap => Some(&GAP_AUTOMATON),
+            _ => None,
+        }
+    }
+
+    fn pest_to_path_role(self) -> PathRole {
+        match &self {
+            Rule::pair => PathRole::Member,
+            Rule::array => PathRole::Elements,
+            Rule::comma | Rule::gap => PathRole::Skipped,
+            _ => PathRole::None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -33,7 +59,7 @@ mod tests {
 
     use super::{JsonLexer, Rule};
 
-    fn tokenize_json_input_helper(input: &str) -> Vec<AutomatonToken> {
+    fn tokenize_json_input_helper(input: &str) -> Vec<AutomatonToken<'_>> {
         super::super::tokenize_input::<JsonLexer, Rule>(input, Rule::value).unwrap()
     }
     #[test]
@@ -100,6 +126,59 @@ mod tests {
         assert_eq!(result[3].to, 7);
     }
 
+    #[test]
+    fn tokenize_separators_and_gaps() {
+        let input = "[ 1 , {\"a\" :2} ]";
+        let tokens =
+            super::super::tokenize_input_with_separators::<JsonLexer, Rule>(input, Rule::value)
+                .unwrap();
+        let result: Vec<(&str, &str)> = tokens
+            .iter()
+            .filter(|token| token.automaton.name() != "json::number")
+            .map(|token| (token.automaton.name(), &input[token.from..token.to]))
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                ("json::gap", ""),
+                ("json::gap", ""),
+                ("json::colon", ":"),
+                ("json::string", "\"a\""),
+                ("json::gap", ""),
+                ("json::object", "{\"a\" :2}"),
+                ("json::comma", ","),
+                ("json::gap", ""),
+                ("json::array", input)
+            ]
+        );
+        let gaps: Vec<usize> = tokens
+            .iter()
+            .filter(|token| token.automaton.name() == "json::gap")
+            .map(|token| token.from)
+            .collect();
+        assert_eq!(gaps, vec![15, 13, 7, 2]);
+    }
+
+    #[test]
+    fn tokens_have_paths() {
+        let result = tokenize_json_input_helper("{\"a/b\": [1, {\"c\": null}], \"d\": 2}");
+        let paths: Vec<&str> = result.iter().map(|token| token.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/d",
+                "/d",
+                "/a~1b/1/c",
+                "/a~1b/1/c",
+                "/a~1b/1",
+                "/a~1b/0",
+                "/a~1b",
+                "/a~1b",
+                ""
+            ]
+        );
+    }
+
     #[test]
     #[should_panic]
     fn fail_to_tokenize_invalid_json() {
@@ -0,0 +1,106 @@
+use super::Automaton;
+use super::LexerRule;
+
+use crate::state_machine::markdown::code::{CODE_FENCE_AUTOMATON, CODE_SPAN_AUTOMATON};
+use crate::state_machine::markdown::container::{
+    HEADING_MARKER_AUTOMATON, LIST_MARKER_AUTOMATON, QUOTE_MARKER_AUTOMATON,
+};
+use crate::state_machine::markdown::emphasis::EMPHASIS_AUTOMATON;
+use crate::state_machine::markdown::entity::ENTITY_AUTOMATON;
+use crate::state_machine::markdown::html::HTML_AUTOMATON;
+use crate::state_machine::markdown::link::{
+    AUTOLINK_AUTOMATON, LINK_AUTOMATON, LINK_DEFINITION_AUTOMATON,
+};
+use crate::state_machine::markdown::table::TABLE_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/markdown.pest"]
+pub struct MarkdownLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::quote_marker => Some(&QUOTE_MARKER_AUTOMATON),
+            Rule::list_marker => Some(&LIST_MARKER_AUTOMATON),
+            Rule::heading_marker => Some(&HEADING_MARKER_AUTOMATON),
+            Rule::code_fence => Some(&CODE_FENCE_AUTOMATON),
+            Rule::html_block | Rule::inline_html => Some(&HTML_AUTOMATON),
+            Rule::link_definition => Some(&LINK_DEFINITION_AUTOMATON),
+            Rule::table => Some(&TABLE_AUTOMATON),
+            Rule::code_span => Some(&CODE_SPAN_AUTOMATON),
+            Rule::autolink => Some(&AUTOLINK_AUTOMATON),
+            Rule::link => Some(&LINK_AUTOMATON),
+            Rule::emphasis => Some(&EMPHASIS_AUTOMATON),
+            Rule::entity => Some(&ENTITY_AUTOMATON),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{MarkdownLexer, Rule};
+
+    fn tokenize_markdown_input_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<MarkdownLexer, Rule>(input, Rule::markdown)
+    }
+
+    fn token_spans(input: &str) -> Vec<(&'static str, &str)> {
+        tokenize_markdown_input_helper(input)
+            .unwrap()
+            .iter()
+            .map(|token| (token.automaton.name(), &input[token.from..token.to]))
+            .rev()
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_inlines() {
+        assert_eq!(
+            token_spans("# A *b* and __c__ with `d`, [e](f) <ab:c> &amp; <br>"),
+            vec![
+                ("markdown::heading_marker", "#"),
+                ("markdown::emphasis", "*b*"),
+                ("markdown::emphasis", "__c__"),
+                ("markdown::code_span", "`d`"),
+                ("markdown::link", "[e](f)"),
+                ("markdown::autolink", "<ab:c>"),
+                ("markdown::entity", "&amp;"),
+                ("markdown::html", "<br>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_blocks() {
+        let input = "> - a\n\n```rust\nlet a = *b*;\n```\n\n| a | b |\n|---|:-:|\n| 1 | 2 |\n\n[ref]: /url \"title\"\n<div>\n*a*\n</div>\n\n* * *\n1. x";
+        assert_eq!(
+            token_spans(input),
+            vec![
+                ("markdown::quote_marker", ">"),
+                ("markdown::list_marker", " -"),
+                ("markdown::code_fence", "```rust\nlet a = *b*;\n```"),
+                ("markdown::table", "| a | b |\n|---|:-:|\n| 1 | 2 |"),
+                ("markdown::link_definition", "[ref]: /url \"title\""),
+                ("markdown::html", "<div>\n*a*\n</div>"),
+                ("markdown::list_marker", "1."),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_unclosed_constructs() {
+        assert_eq!(
+            token_spans("*a **b `c [d\n~~~\n```"),
+            vec![("markdown::code_fence", "~~~\n```")]
+        );
+    }
+
+    #[test]
+    fn tokenize_any_text() {
+        assert!(tokenize_markdown_input_helper("\u{0}\r\n\t\\").is_some());
+        assert!(tokenize_markdown_input_helper(&"*a ".repeat(1000)).is_some());
+    }
+}
@@ -1,41 +1,96 @@
+pub mod binary;
+pub mod csv_lexer;
+pub mod http_lexer;
+pub mod json5_lexer;
 pub mod json_lexer;
+pub mod markdown_lexer;
+pub mod protobuf;
+pub mod toml_lexer;
+pub mod unicode_lexer;
+pub mod uri_lexer;
+pub mod xml_lexer;
 pub mod yaml_lexer;
 
 use super::state_machine::Automaton;
+use crate::selector::child_path;
 
 /// This module is used for PEG-parsable (e.g. text) protocols.
 /// It expects that a state_machine logic and a FSM-to-PEG mapping
-/// has been defined. For binary protocols go to [?].`
+/// has been defined. For binary protocols go to [`binary`].
 use pest::{Parser, RuleType};
 
 /// This trait is mandatory for Rule enums from all Pest implementations
 pub trait LexerRule: RuleType {
     /// maps the underlying rule to its inner representation as Automaton
     fn pest_to_automaton(self) -> Option<&'static Automaton<String>>;
+
+    /// maps the underlying rule to an Automaton fuzzing its raw bytes, if
+    /// there is one - used for emitting invalid encodings
+    fn pest_to_byte_automaton(self) -> Option<&'static Automaton<Vec<u8>>> {
+        None
+    }
+
+    /// maps the underlying rule to an Automaton fuzzing it as a separator,
+    /// e.g. a comma between elements - separators are only tokenized on
+    /// request, see [`tokenize_input_with_separators`]
+    fn pest_to_separator_automaton(self) -> Option<&'static Automaton<String>> {
+        None
+    }
+
+    /// maps the underlying rule to the part it plays in the paths of tokens
+    fn pest_to_path_role(self) -> PathRole {
+        PathRole::None
+    }
+}
+
+/// Part a rule plays in the paths of the tokens within it, e.g.
+/// "/items/0/price"
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PathRole {
+    /// Doesn't add to the path, e.g. a scalar
+    None,
+    /// Adds its key, which is its first inner pair, e.g. an object member
+    Member,
+    /// Adds the index of each of its inner pairs, e.g. an array
+    Elements,
+    /// Isn't one of the elements of its parent, e.g. a comment
+    Skipped,
 }
 
 /// Representation of a single token - characterized by:
 /// - its first position in the input text
 /// - its last position in the input text
 /// - its corresponding automaton
+/// - its corresponding byte-level automaton, if any
+/// - its path within the document as a JSON Pointer, e.g. "/items/0"
 pub struct AutomatonToken<'a> {
     pub from: usize,
     pub to: usize,
     pub automaton: &'a Automaton<String>,
+    pub bytes: Option<&'a Automaton<Vec<u8>>>,
+    pub path: String,
 }
 
 /// Converts a Pest pair to its corresponding token
 fn pest_pair_to_token<'a, T: 'a + LexerRule>(
     pair: &pest::iterators::Pair<T>,
+    path: &str,
+    separators: bool,
 ) -> Option<AutomatonToken<'a>> {
     let rule = pair.as_rule();
     let start = pair.as_span().start();
     let end = pair.as_span().end();
 
-    rule.pest_to_automaton().map(|rule| AutomatonToken {
+    let automaton = match rule.pest_to_automaton() {
+        None if separators => rule.pest_to_separator_automaton(),
+        automaton => automaton,
+    };
+    automaton.map(|automaton| AutomatonToken {
         from: start,
         to: end,
-        automaton: rule,
+        automaton,
+        bytes: rule.pest_to_byte_automaton(),
+        path: String::from(path),
     })
 }
 
@@ -44,27 +99,111 @@ fn pest_pair_to_token<'a, T: 'a + LexerRule>(
 pub fn tokenize_input<'a, P: Parser<R>, R: 'a + LexerRule>(
     text: &'a str,
     parent_rule: R,
-) -> Option<Vec<AutomatonToken>> {
+) -> Option<Vec<AutomatonToken<'a>>> {
+    tokenize_text::<P, R>(text, parent_rule, false)
+}
+
+/// Same as [`tokenize_input`], but separators such as commas and colons are
+/// tokens as well, as per [`LexerRule::pest_to_separator_automaton`]
+pub fn tokenize_input_with_separators<'a, P: Parser<R>, R: 'a + LexerRule>(
+    text: &'a str,
+    parent_rule: R,
+) -> Option<Vec<AutomatonToken<'a>>> {
+    tokenize_text::<P, R>(text, parent_rule, true)
+}
+
+fn tokenize_text<'a, P: Parser<R>, R: 'a + LexerRule>(
+    text: &'a str,
+    parent_rule: R,
+    separators: bool,
+) -> Option<Vec<AutomatonToken<'a>>> {
     if text.is_empty() {
         Some(vec![])
     } else {
         match P::parse(parent_rule, text) {
-            Ok(pairs) => Some(tokenize_peg_tree::<R>(pairs)),
+            Ok(pairs) => Some(tokenize_peg_tree::<R>(pairs, separators)),
             Err(_) => None,
         }
     }
 }
 
+/// Tokenizes newline-delimited text, e.g. JSON Lines, where each line is a
+/// separate document as per `parent_rule`. Blank lines are skipped and a line
+/// may end in CRLF. Outputs the tokens of each line in order, or `None` if any
+/// of the lines is invalid.
+pub fn tokenize_lines<'a, P: Parser<R>, R: 'a + LexerRule>(
+    text: &'a str,
+    parent_rule: R,
+) -> Option<Vec<AutomatonToken<'a>>> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let record = line.trim_end_matches('\n').trim_end_matches('\r');
+        if !record.trim().is_empty() {
+            tokens.extend(
+                tokenize_input::<P, R>(record, parent_rule)?
+                    .into_iter()
+                    .map(|token| AutomatonToken {
+                        from: token.from + start,
+                        to: token.to + start,
+                        ..token
+                    }),
+            );
+        }
+        start += line.len();
+    }
+    Some(tokens)
+}
+
 /// Iterates through all pairs in a Pest tree and generates a list of tokens
 /// in an order such that each element doesn't depend on another after it
 fn tokenize_peg_tree<'a, T: 'a + LexerRule>(
     tree_root: pest::iterators::Pairs<'a, T>,
-) -> Vec<AutomatonToken> {
-    tree_root
-        .flatten()
-        .filter_map(|aut| pest_pair_to_token::<T>(&aut))
-        .rev()
-        .collect()
+    separators: bool,
+) -> Vec<AutomatonToken<'a>> {
+    let mut tokens = vec![];
+    for pair in tree_root {
+        tokenize_pair(pair, String::new(), separators, &mut tokens);
+    }
+    tokens.reverse();
+    tokens
+}
+
+/// Pushes the tokens of a pair and all pairs within it in pre-order, along
+/// with their paths within the document
+fn tokenize_pair<'a, T: 'a + LexerRule>(
+    pair: pest::iterators::Pair<'a, T>,
+    path: String,
+    separators: bool,
+    tokens: &mut Vec<AutomatonToken<'a>>,
+) {
+    let role = pair.as_rule().pest_to_path_role();
+    let path = match pair.clone().into_inner().next() {
+        Some(key) if role == PathRole::Member => child_path(&path, unquote(key.as_str())),
+        _ => path,
+    };
+    tokens.extend(pest_pair_to_token::<T>(&pair, &path, separators));
+
+    let mut elements = 0;
+    for inner in pair.into_inner() {
+        let inner_path = match inner.as_rule().pest_to_path_role() {
+            PathRole::Skipped => path.clone(),
+            _ if role == PathRole::Elements => {
+                elements += 1;
+                child_path(&path, &(elements - 1).to_string())
+            }
+            _ => path.clone(),
+        };
+        tokenize_pair(inner, inner_path, separators, tokens);
+    }
+}
+
+/// Strips the quotes off a key, if there are any
+fn unquote(key: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| key.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(key)
 }
 
 #[cfg(test)]
@@ -98,6 +237,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_lines_with_offsets() {
+        let result =
+            super::tokenize_lines::<MockLexer, Rule>("(1)\r\n\n  \n(1)", Rule::nested).unwrap();
+        assert_eq!(result.len(), 4);
+
+        assert_eq!(result[1].from, 0);
+        assert_eq!(result[1].to, 3);
+
+        assert_eq!(result[2].from, 10);
+        assert_eq!(result[2].to, 11);
+    }
+
+    #[test]
+    fn tokenize_lines_with_invalid_line() {
+        assert!(super::tokenize_lines::<MockLexer, Rule>("(1)\n(", Rule::nested).is_none());
+    }
+
     #[test]
     fn tokenize_input_successfully() {
         let result = super::tokenize_input::<MockLexer, Rule>("(((1)))", Rule::nested).unwrap();
@@ -116,10 +273,10 @@ mod tests {
         assert_eq!(result[3].to, 7);
     }
 
-    fn tokenize_peg_tree_helper(rule: Rule, text: &str) -> Vec<AutomatonToken> {
+    fn tokenize_peg_tree_helper(rule: Rule, text: &str) -> Vec<AutomatonToken<'_>> {
         let pairs =
             MockLexer::parse(rule, text).unwrap_or_else(|e| panic!("Invalid test setup: {}", e));
-        super::tokenize_peg_tree(pairs)
+        super::tokenize_peg_tree(pairs, false)
     }
 
     #[test]
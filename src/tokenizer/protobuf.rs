@@ -0,0 +1,255 @@
+use super::binary::{BinaryToken, BinaryTokenizer, IntEncoding};
+use crate::selector::child_path;
+use crate::state_machine::binary::decode_varint;
+use crate::state_machine::protobuf::length::{BYTES_AUTOMATON, LENGTH_AUTOMATON};
+use crate::state_machine::protobuf::message::{FIELD_AUTOMATON, MESSAGE_AUTOMATON};
+use crate::state_machine::protobuf::scalar::{FIXED_AUTOMATON, VARINT_AUTOMATON};
+use crate::state_machine::protobuf::tag::TAG_AUTOMATON;
+use crate::state_machine::protobuf::{decode_tag, WireType};
+use crate::state_machine::Automaton;
+use std::convert::TryFrom;
+
+/// Nested messages deeper than this are treated as opaque bytes
+const MAX_DEPTH: usize = 64;
+
+/// Tokenizer for protobuf messages in the wire format, which needs no
+/// schema.
+///
+/// Each field is split into its tag and value, with the path of both being
+/// the field number, e.g. "/2/1" for field 1 of a message in field 2.
+/// Length-delimited values which can be decoded as messages are treated as
+/// nested messages, unless they look like text. Groups aren't supported, as
+/// they're deprecated.
+pub struct ProtobufTokenizer;
+
+impl BinaryTokenizer for ProtobufTokenizer {
+    fn tokenize(&self, input: &[u8]) -> Option<Vec<BinaryToken>> {
+        let mut tokens = vec![];
+        tokenize_message(input, 0, input.len(), String::new(), 0, &mut tokens)?;
+        Some(tokens)
+    }
+}
+
+fn push_token(
+    tokens: &mut Vec<BinaryToken>,
+    from: usize,
+    to: usize,
+    automaton: &'static Automaton<Vec<u8>>,
+    path: &str,
+) -> usize {
+    tokens.push(BinaryToken {
+        from,
+        to,
+        automaton: Some(automaton),
+        path: String::from(path),
+        children: vec![],
+        length_of: None,
+    });
+    tokens.len() - 1
+}
+
+/// Pushes the token of a message spanning from `from` to `end` along with
+/// the tokens of its fields in pre-order. Outputs the index of the message
+/// token, or None if the bytes aren't a message.
+fn tokenize_message(
+    input: &[u8],
+    from: usize,
+    end: usize,
+    path: String,
+    depth: usize,
+    tokens: &mut Vec<BinaryToken>,
+) -> Option<usize> {
+    let message = push_token(tokens, from, end, &MESSAGE_AUTOMATON, &path);
+    let mut pos = from;
+    while pos < end {
+        let (field, next) = tokenize_field(input, pos, end, &path, depth, tokens)?;
+        tokens[message].children.push(field);
+        pos = next;
+    }
+    Some(message)
+}
+
+/// Pushes the tokens of the field starting at `from`. Outputs the index of
+/// the field token along with the position right after the field.
+fn tokenize_field(
+    input: &[u8],
+    from: usize,
+    end: usize,
+    path: &str,
+    depth: usize,
+    tokens: &mut Vec<BinaryToken>,
+) -> Option<(usize, usize)> {
+    let (number, wire_type, tag_size) = decode_tag(&input[from..end])?;
+    if number == 0 {
+        return None;
+    }
+    let path = child_path(path, &number.to_string());
+    let field = push_token(tokens, from, from, &FIELD_AUTOMATON, &path);
+    let tag = push_token(tokens, from, from + tag_size, &TAG_AUTOMATON, &path);
+    let value_from = from + tag_size;
+
+    let children = match wire_type {
+        WireType::Varint => {
+            let (_, size) = decode_varint(&input[value_from..end])?;
+            vec![
+                tag,
+                push_token(
+                    tokens,
+                    value_from,
+                    value_from + size,
+                    &VARINT_AUTOMATON,
+                    &path,
+                ),
+            ]
+        }
+        WireType::Fixed64 | WireType::Fixed32 => {
+            let size = if wire_type == WireType::Fixed64 { 8 } else { 4 };
+            if value_from + size > end {
+                return None;
+            }
+            vec![
+                tag,
+                push_token(
+                    tokens,
+                    value_from,
+                    value_from + size,
+                    &FIXED_AUTOMATON,
+                    &path,
+                ),
+            ]
+        }
+        WireType::LengthDelimited => {
+            let (length, size) = decode_varint(&input[value_from..end])?;
+            let payload_from = value_from + size;
+            let payload_end = payload_from.checked_add(usize::try_from(length).ok()?)?;
+            if payload_end > end {
+                return None;
+            }
+            let prefix = push_token(tokens, value_from, payload_from, &LENGTH_AUTOMATON, &path);
+            let payload = tokenize_payload(input, payload_from, payload_end, path, depth, tokens);
+            tokens[prefix].length_of = Some((payload, IntEncoding::Varint));
+            vec![tag, prefix, payload]
+        }
+        WireType::StartGroup | WireType::EndGroup => return None,
+    };
+
+    let to = tokens[*children.last()?].to;
+    tokens[field].to = to;
+    tokens[field].children = children;
+    Some((field, to))
+}
+
+/// Pushes the tokens of a length-delimited value, either as a nested message
+/// or as opaque bytes. Outputs the index of its token.
+fn tokenize_payload(
+    input: &[u8],
+    from: usize,
+    end: usize,
+    path: String,
+    depth: usize,
+    tokens: &mut Vec<BinaryToken>,
+) -> usize {
+    let payload = &input[from..end];
+    if !payload.is_empty() && depth < MAX_DEPTH && !looks_like_text(payload) {
+        let checkpoint = tokens.len();
+        match tokenize_message(input, from, end, path.clone(), depth + 1, tokens) {
+            Some(message) => return message,
+            None => tokens.truncate(checkpoint),
+        }
+    }
+    push_token(tokens, from, end, &BYTES_AUTOMATON, &path)
+}
+
+/// Returns whether `bytes` are likely a string rather than a message, i.e.
+/// they are UTF-8 text without control characters other than whitespace
+fn looks_like_text(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text
+            .chars()
+            .all(|ch| !ch.is_control() || ch.is_whitespace()),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryTokenizer, ProtobufTokenizer};
+
+    /// Field 1 is the varint 150, field 2 the string "hi", field 3 a message
+    /// with field 1 being the fixed32 1 and field 4 the double 1.0
+    const MESSAGE: &[u8] = &[
+        0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x1a, 0x05, 0x0d, 0x01, 0x00, 0x00, 0x00, 0x21,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f,
+    ];
+
+    #[test]
+    fn messages_are_tokenized() {
+        let tokens = ProtobufTokenizer.tokenize(MESSAGE).unwrap();
+        let ranges: Vec<(&str, &str, usize, usize)> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.automaton.unwrap().name(),
+                    token.path.as_str(),
+                    token.from,
+                    token.to,
+                )
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("protobuf::message", "", 0, 23),
+                ("protobuf::field", "/1", 0, 3),
+                ("protobuf::tag", "/1", 0, 1),
+                ("protobuf::varint", "/1", 1, 3),
+                ("protobuf::field", "/2", 3, 7),
+                ("protobuf::tag", "/2", 3, 4),
+                ("protobuf::length", "/2", 4, 5),
+                ("protobuf::bytes", "/2", 5, 7),
+                ("protobuf::field", "/3", 7, 14),
+                ("protobuf::tag", "/3", 7, 8),
+                ("protobuf::length", "/3", 8, 9),
+                ("protobuf::message", "/3", 9, 14),
+                ("protobuf::field", "/3/1", 9, 14),
+                ("protobuf::tag", "/3/1", 9, 10),
+                ("protobuf::fixed", "/3/1", 10, 14),
+                ("protobuf::field", "/4", 14, 23),
+                ("protobuf::tag", "/4", 14, 15),
+                ("protobuf::fixed", "/4", 15, 23),
+            ]
+        );
+        assert_eq!(tokens[6].length_of.map(|(idx, _)| idx), Some(7));
+        assert_eq!(tokens[0].children, vec![1, 4, 8, 15]);
+    }
+
+    #[test]
+    fn bytes_which_are_not_messages_are_kept_opaque() {
+        // field 1 holds 0x08 0x80, an unterminated varint
+        let tokens = ProtobufTokenizer
+            .tokenize(&[0x0a, 0x02, 0x08, 0x80])
+            .unwrap();
+        assert_eq!(
+            tokens.last().unwrap().automaton.unwrap().name(),
+            "protobuf::bytes"
+        );
+        assert_eq!(tokens.len(), 5);
+    }
+
+    #[test]
+    fn invalid_messages_are_not_tokenized() {
+        // truncated value
+        assert!(ProtobufTokenizer.tokenize(&MESSAGE[..20]).is_none());
+        // field number 0
+        assert!(ProtobufTokenizer.tokenize(&[0x00, 0x01]).is_none());
+        // wire type 7
+        assert!(ProtobufTokenizer.tokenize(&[0x0f, 0x01]).is_none());
+        // groups
+        assert!(ProtobufTokenizer.tokenize(&[0x0b, 0x0c]).is_none());
+    }
+
+    #[test]
+    fn empty_message_is_tokenized() {
+        assert_eq!(ProtobufTokenizer.tokenize(&[]).unwrap().len(), 1);
+    }
+}
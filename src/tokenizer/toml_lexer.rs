@@ -0,0 +1,125 @@
+use super::Automaton;
+use super::LexerRule;
+
+use crate::state_machine::json::boolean::BOOL_AUTOMATON;
+use crate::state_machine::toml::collection::{ARRAY_AUTOMATON, INLINE_TABLE_AUTOMATON};
+use crate::state_machine::toml::datetime::DATETIME_AUTOMATON;
+use crate::state_machine::toml::key::KEY_AUTOMATON;
+use crate::state_machine::toml::number::{FLOAT_AUTOMATON, INTEGER_AUTOMATON};
+use crate::state_machine::toml::string::STRING_AUTOMATON;
+use crate::state_machine::toml::table::TABLE_HEADER_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/toml.pest"]
+pub struct TomlLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::key => Some(&KEY_AUTOMATON),
+            Rule::table_header => Some(&TABLE_HEADER_AUTOMATON),
+            Rule::string => Some(&STRING_AUTOMATON),
+            Rule::integer => Some(&INTEGER_AUTOMATON),
+            Rule::float => Some(&FLOAT_AUTOMATON),
+            Rule::boolean => Some(&BOOL_AUTOMATON),
+            Rule::datetime => Some(&DATETIME_AUTOMATON),
+            Rule::array => Some(&ARRAY_AUTOMATON),
+            Rule::inline_table => Some(&INLINE_TABLE_AUTOMATON),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{Rule, TomlLexer};
+
+    fn tokenize_toml_input_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<TomlLexer, Rule>(input, Rule::toml)
+    }
+
+    fn token_spans(input: &str) -> Vec<&str> {
+        tokenize_toml_input_helper(input)
+            .unwrap()
+            .iter()
+            .map(|token| &input[token.from..token.to])
+            .rev()
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_keys_and_tables() {
+        assert_eq!(
+            token_spans(
+                "# config\n[server]\nhost.name = \"a\" # b\n\n[[\"bins\"]]\n'k' = true\r\n"
+            ),
+            vec![
+                "[server]",
+                "host.name",
+                "\"a\"",
+                "[[\"bins\"]]",
+                "'k'",
+                "true"
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_scalars() {
+        assert_eq!(
+            token_spans("a = 0xDEAD_beef\nb = -1_000\nc = 6.626e-34\nd = nan\ne = 1979-05-27T00:32:00.999-07:00\nf = 07:32:00"),
+            vec![
+                "a",
+                "0xDEAD_beef",
+                "b",
+                "-1_000",
+                "c",
+                "6.626e-34",
+                "d",
+                "nan",
+                "e",
+                "1979-05-27T00:32:00.999-07:00",
+                "f",
+                "07:32:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_multiline_strings() {
+        assert_eq!(
+            token_spans("a = \"\"\"\nb \\\n  c\"\"\"\"\nd = '''\n'e'\n'''"),
+            vec!["a", "\"\"\"\nb \\\n  c\"\"\"\"", "d", "'''\n'e'\n'''"]
+        );
+    }
+
+    #[test]
+    fn tokenize_collections() {
+        assert_eq!(
+            token_spans("a = [ 1, [\"b\"], # c\n]\nd = { e = 1, f.g = {}, }"),
+            vec![
+                "a",
+                "[ 1, [\"b\"], # c\n]",
+                "1",
+                "[\"b\"]",
+                "\"b\"",
+                "d",
+                "{ e = 1, f.g = {}, }",
+                "e",
+                "1",
+                "f.g",
+                "{}"
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_invalid_documents() {
+        assert!(tokenize_toml_input_helper("a = ").is_none());
+        assert!(tokenize_toml_input_helper("[a").is_none());
+        assert!(tokenize_toml_input_helper("a = \"b\nc\"").is_none());
+        assert!(tokenize_toml_input_helper("a = 1 b = 2").is_none());
+    }
+}
@@ -0,0 +1,35 @@
+use super::Automaton;
+use super::LexerRule;
+
+use crate::state_machine::unicode::UNICODE_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/unicode.pest"]
+pub struct UnicodeLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::word => Some(&UNICODE_AUTOMATON),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rule, UnicodeLexer};
+
+    #[test]
+    fn tokenize_words() {
+        let text = "caf\u{e9} \u{3000}au\tlait\n";
+        let tokens = super::super::tokenize_input::<UnicodeLexer, Rule>(text, Rule::text).unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| &text[token.from..token.to])
+                .collect::<Vec<_>>(),
+            vec!["lait", "au", "caf\u{e9}"]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::Automaton;
+use super::LexerRule;
+
+use crate::state_machine::xml::attribute::ATTRIBUTE_AUTOMATON;
+use crate::state_machine::xml::declaration::DECLARATION_AUTOMATON;
+use crate::state_machine::xml::doctype::DOCTYPE_AUTOMATON;
+use crate::state_machine::xml::element::ELEMENT_AUTOMATON;
+use crate::state_machine::xml::markup::{
+    CDATA_AUTOMATON, COMMENT_AUTOMATON, PROCESSING_INSTRUCTION_AUTOMATON,
+};
+use crate::state_machine::xml::text::TEXT_AUTOMATON;
+
+#[derive(Parser)]
+#[grammar = "../resources/xml.pest"]
+pub struct XmlLexer;
+
+impl LexerRule for Rule {
+    fn pest_to_automaton(self) -> Option<&'static Automaton<String>> {
+        match &self {
+            Rule::xml_declaration => Some(&DECLARATION_AUTOMATON),
+            Rule::doctype => Some(&DOCTYPE_AUTOMATON),
+            Rule::element => Some(&ELEMENT_AUTOMATON),
+            Rule::attribute => Some(&ATTRIBUTE_AUTOMATON),
+            Rule::text => Some(&TEXT_AUTOMATON),
+            Rule::cdata => Some(&CDATA_AUTOMATON),
+            Rule::comment => Some(&COMMENT_AUTOMATON),
+            Rule::processing_instruction => Some(&PROCESSING_INSTRUCTION_AUTOMATON),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::AutomatonToken;
+
+    use super::{Rule, XmlLexer};
+
+    fn tokenize_xml_input_helper(input: &str) -> Option<Vec<AutomatonToken<'_>>> {
+        super::super::tokenize_input::<XmlLexer, Rule>(input, Rule::xml)
+    }
+
+    fn token_spans(input: &str) -> Vec<(&'static str, &str)> {
+        tokenize_xml_input_helper(input)
+            .unwrap()
+            .iter()
+            .map(|token| (token.automaton.name(), &input[token.from..token.to]))
+            .rev()
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_prolog() {
+        let input = "<?xml version=\"1.0\"?>\n<!-- a -->\n<!DOCTYPE a [<!ENTITY b \"]>\">]>\n<a/>";
+        assert_eq!(
+            token_spans(input),
+            vec![
+                ("xml::declaration", "<?xml version=\"1.0\"?>"),
+                ("xml::comment", "<!-- a -->"),
+                ("xml::doctype", "<!DOCTYPE a [<!ENTITY b \"]>\">]>"),
+                ("xml::element", "<a/>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_elements() {
+        let input = "<ds:a b=\"1\" c='>'><d>e &amp; f</d><![CDATA[<g>]]><?h i?></ds:a >";
+        assert_eq!(
+            token_spans(input),
+            vec![
+                ("xml::element", input),
+                ("xml::attribute", "b=\"1\""),
+                ("xml::attribute", "c='>'"),
+                ("xml::element", "<d>e &amp; f</d>"),
+                ("xml::text", "e &amp; f"),
+                ("xml::cdata", "<![CDATA[<g>]]>"),
+                ("xml::processing_instruction", "<?h i?>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_invalid_input() {
+        assert!(tokenize_xml_input_helper("<a><b></a></b>").is_none());
+        assert!(tokenize_xml_input_helper("<a>").is_none());
+        assert!(tokenize_xml_input_helper("<a/><b/>").is_none());
+        assert!(tokenize_xml_input_helper("text").is_none());
+    }
+}
@@ -4,6 +4,7 @@ use super::LexerRule;
 use crate::state_machine::yaml::flow_scalar::FLOW_SCALAR_AUTOMATON;
 use crate::state_machine::yaml::indentation::INDENTATION_AUTOMATON;
 
+#[allow(dead_code)]
 #[derive(Parser)]
 //#[derive(Tokenizer)] // add a macro function that generates an alias function for parse
 #[grammar = "../resources/yaml.pest"]
@@ -31,7 +32,7 @@ mod tests {
 
     use super::{Rule, YamlLexer};
 
-    fn tokenize_yaml_input_helper(input: &str) -> Vec<AutomatonToken> {
+    fn tokenize_yaml_input_helper(input: &str) -> Vec<AutomatonToken<'_>> {
         super::super::tokenize_input::<YamlLexer, Rule>(input, Rule::value).unwrap()
     }
     #[test]
@@ -0,0 +1,143 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::toml::collection::INLINE_TABLE_AUTOMATON;
+use crate::state_machine::toml::datetime::DATETIME_AUTOMATON;
+use crate::state_machine::toml::key::KEY_AUTOMATON;
+use crate::state_machine::toml::number::INTEGER_AUTOMATON;
+use crate::state_machine::toml::string::STRING_AUTOMATON;
+use crate::state_machine::toml::table::DOCUMENT_AUTOMATON;
+use crate::tokenizer::toml_lexer::{Rule, TomlLexer};
+
+/// Returns a TOML key generator, which produces quoted keys equal to bare
+/// ones, empty and deeply dotted keys and characters not allowed in bare keys
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::key(1234).take(10) {
+///     println!("New key of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn key(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&KEY_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML string generator, which produces invalid escapes, escaped
+/// line endings and runs of quotes within multi-line strings
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::string(1234).take(10) {
+///     println!("New string: {:?}", fuzzed);
+/// }
+/// ```
+pub fn string(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&STRING_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML integer generator, which produces hexadecimal, octal and
+/// binary prefixes, misplaced underscores and values out of the 64 bits range
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::integer(1234).take(10) {
+///     println!("New integer: {}", fuzzed);
+/// }
+/// ```
+pub fn integer(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&INTEGER_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML datetime generator, which produces offset and local
+/// variants, unusual separators and dates or times which don't exist
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::datetime(1234).take(10) {
+///     println!("New datetime: {}", fuzzed);
+/// }
+/// ```
+pub fn datetime(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&DATETIME_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML inline table generator, which produces trailing commas,
+/// line breaks, duplicate keys and deeply nested tables
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::inline_table(1234).take(10) {
+///     println!("New inline table of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn inline_table(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&INLINE_TABLE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML document generator, which produces duplicate tables and
+/// keys, tables redefined by dotted keys and conflicting arrays of tables
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// for fuzzed in toml::document(1234).take(10) {
+///     println!("New document of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn document(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&DOCUMENT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a TOML Mutator. On top of its keys and values being mutated,
+/// every other mutation also changes the document as a whole, e.g. by
+/// defining one of its tables twice.
+///
+/// # Examples
+/// ```rust
+/// use talasum::toml;
+///
+/// match toml::mutate("[package]\nname = \"talasum\"\nversion = \"0.1.0\"\n", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New document of length: {}", fuzzed.len());
+///         }
+///     },
+///     None => panic!("Your input could not be tokenized")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<TomlLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::toml,
+        Box::new(Config::new()),
+    )
+    .map(|mutator| mutator.with_records(&DOCUMENT_AUTOMATON))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+
+    #[test]
+    fn documents_are_mutated() {
+        let document = "[package]\nname = \"talasum\"\n";
+        let mutated: Vec<String> = mutate(document, 1234).unwrap().take(100).collect();
+        // the document as a whole is changed, as well as its keys and values
+        assert!(mutated.iter().any(|value| value.contains("[[package]]")));
+        assert!(mutated.iter().any(|value| !value.contains("\"talasum\"")));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::unicode::{GRAPHEME_AUTOMATON, UNICODE_AUTOMATON};
+use crate::tokenizer::unicode_lexer::{Rule, UnicodeLexer};
+
+/// Returns a Unicode text generator, which produces text with combining
+/// marks, zero-width and bidi control characters, confusables, characters
+/// changed by normalization or case mapping, noncharacters and private use
+/// code points
+///
+/// # Examples
+/// ```rust
+/// use talasum::unicode;
+///
+/// for fuzzed in unicode::text(1234).take(10) {
+///     println!("New text: {:?}", fuzzed);
+/// }
+/// ```
+pub fn text(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&UNICODE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a generator of single grapheme clusters, each of them thousands
+/// of code points long
+///
+/// # Examples
+/// ```rust
+/// use talasum::unicode;
+///
+/// for fuzzed in unicode::grapheme(1234).take(10) {
+///     println!("New grapheme of {} code points", fuzzed.chars().count());
+/// }
+/// ```
+pub fn grapheme(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&GRAPHEME_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a Unicode text Mutator, which fuzzes each of the words of the
+/// text on its own
+///
+/// # Examples
+/// ```rust
+/// use talasum::unicode;
+///
+/// match unicode::mutate("Stra\u{df}e caf\u{e9} admin", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New text: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input could not be tokenized")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<UnicodeLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::text,
+        Box::new(Config::new()),
+    )
+}
@@ -0,0 +1,110 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::uri::host::HOST_AUTOMATON;
+use crate::state_machine::uri::path::PATH_AUTOMATON;
+use crate::state_machine::uri::port::PORT_AUTOMATON;
+use crate::state_machine::uri::scheme::SCHEME_AUTOMATON;
+use crate::tokenizer::uri_lexer::{Rule, UriLexer};
+
+/// Returns a URI scheme generator, which produces schemes reaching internal
+/// services and local files, as well as ones with control characters
+///
+/// # Examples
+/// ```rust
+/// use talasum::uri;
+///
+/// for fuzzed in uri::scheme(1234).take(10) {
+///     println!("New scheme: {:?}", fuzzed);
+/// }
+/// ```
+pub fn scheme(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&SCHEME_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a URI host generator, which produces IPv4 addresses in various
+/// notations, IPv6 literals, internationalized lookalikes and hosts that
+/// parsers disagree on
+///
+/// # Examples
+/// ```rust
+/// use talasum::uri;
+///
+/// for fuzzed in uri::host(1234).take(10) {
+///     println!("New host: {}", fuzzed);
+/// }
+/// ```
+pub fn host(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&HOST_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a URI port generator, which produces overflowing ports
+///
+/// # Examples
+/// ```rust
+/// use talasum::uri;
+///
+/// for fuzzed in uri::port(1234).take(10) {
+///     println!("New port: {}", fuzzed);
+/// }
+/// ```
+pub fn port(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&PORT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a URI path generator, which produces dot-segments, backslashes
+/// and malformed or double percent-encodings
+///
+/// # Examples
+/// ```rust
+/// use talasum::uri;
+///
+/// for fuzzed in uri::path(1234).take(10) {
+///     println!("New path: {}", fuzzed);
+/// }
+/// ```
+pub fn path(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&PATH_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns a URI Mutator. Only absolute URIs, i.e. ones with a scheme, are
+/// accepted.
+///
+/// # Examples
+/// ```rust
+/// use talasum::uri;
+///
+/// match uri::mutate("https://user@example.com:8080/a/b?c=d#e", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New URI: {:?}", fuzzed);
+///         }
+///     },
+///     None => panic!("Your input was not a valid URI")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<UriLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::uri,
+        Box::new(Config::new()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+
+    #[test]
+    fn uris_are_mutated() {
+        let uri = "http://example.com/a?b=c";
+        let mutated: Vec<String> = mutate(uri, 1234).unwrap().take(100).collect();
+        assert!(mutated.iter().any(|value| value.contains("127.0.0.1")
+            || value.contains("0x7f")
+            || value.contains("[::")));
+        assert!(mutate("example.com/a", 1234).is_none());
+    }
+}
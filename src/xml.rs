@@ -0,0 +1,111 @@
+use crate::configuration::Config;
+use crate::generator::Generator;
+use crate::mutator::Mutator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::xml::attribute::ATTRIBUTE_AUTOMATON;
+use crate::state_machine::xml::declaration::DECLARATION_AUTOMATON;
+use crate::state_machine::xml::doctype::DOCTYPE_AUTOMATON;
+use crate::state_machine::xml::element::ELEMENT_AUTOMATON;
+use crate::tokenizer::xml_lexer::{Rule, XmlLexer};
+
+/// Returns a DOCTYPE generator, which produces external entities pointing
+/// at local files (XXE), exponentially expanding entities (billion laughs)
+/// and external DTDs
+///
+/// # Examples
+/// ```rust
+/// use talasum::xml;
+///
+/// for fuzzed in xml::doctype(1234).take(10) {
+///     println!("New DOCTYPE of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn doctype(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&DOCTYPE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an XML element generator, which produces duplicated elements as
+/// in signature wrapping, confused namespace prefixes and mismatched tags
+///
+/// # Examples
+/// ```rust
+/// use talasum::xml;
+///
+/// for fuzzed in xml::element(1234).take(10) {
+///     println!("New element of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn element(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&ELEMENT_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an XML attribute generator, which produces duplicate
+/// attributes, rebound namespaces and entity references
+///
+/// # Examples
+/// ```rust
+/// use talasum::xml;
+///
+/// for fuzzed in xml::attribute(1234).take(10) {
+///     println!("New attribute: {}", fuzzed);
+/// }
+/// ```
+pub fn attribute(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&ATTRIBUTE_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an XML declaration generator, which produces encodings
+/// mismatching the document's one and byte order marks
+///
+/// # Examples
+/// ```rust
+/// use talasum::xml;
+///
+/// for fuzzed in xml::declaration(1234).take(10) {
+///     println!("New declaration: {:?}", fuzzed);
+/// }
+/// ```
+pub fn declaration(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&DECLARATION_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}
+
+/// Returns an XML Mutator. Only well-formed documents, i.e. ones with a
+/// single root element and matching tags, are accepted.
+///
+/// # Examples
+/// ```rust
+/// use talasum::xml;
+///
+/// match xml::mutate("<?xml version=\"1.0\"?><a b=\"c\"><d>e</d></a>", 1234) {
+///     Some(mutator) => {
+///         for fuzzed in mutator.take(10) {
+///             println!("New document of length: {}", fuzzed.len());
+///         }
+///     },
+///     None => panic!("Your input could not be tokenized")
+/// }
+///
+/// ```
+pub fn mutate(input: &str, seed: u64) -> Option<Mutator<'_>> {
+    Mutator::new::<XmlLexer, Rule>(
+        Box::new(PRandomizer::new(seed)),
+        input,
+        Rule::xml,
+        Box::new(Config::new()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mutate;
+
+    #[test]
+    fn documents_are_mutated() {
+        let document = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Response ID=\"_1\"><Assertion>admin</Assertion></Response>";
+        let mutated: Vec<String> = mutate(document, 1234).unwrap().take(100).collect();
+        assert!(mutated.iter().any(|value| value.contains("<!ENTITY")
+            || value.contains("&xxe;")
+            || value.contains("&lol9;")));
+        assert!(mutate("<a><b></a>", 1234).is_none());
+    }
+}
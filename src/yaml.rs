@@ -0,0 +1,20 @@
+use crate::generator::Generator;
+use crate::randomness::PRandomizer;
+use crate::state_machine::yaml::nesting::NESTING_AUTOMATON;
+
+/// Returns a generator of deeply nested YAML nodes, meant for exhausting the
+/// stack of recursive parsers. Nodes are nested in flow collections, in
+/// compact block sequences or in complex mapping keys. The max depth can be
+/// configured through the TALASUM_MAX_NESTING_DEPTH environment variable.
+///
+/// # Examples
+/// ```rust
+/// use talasum::yaml;
+///
+/// for fuzzed in yaml::deep_nesting(1234).take(10) {
+///     println!("New nested node of length: {}", fuzzed.len());
+/// }
+/// ```
+pub fn deep_nesting(seed: u64) -> Generator<String> {
+    Generator::<String>::new(&NESTING_AUTOMATON, Box::new(PRandomizer::new(seed)))
+}